@@ -0,0 +1,30 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the heap item held across an `.await`, the async fn is analyzed by the resume fn of
+// its generator where the locals living across the suspension point are the fields of the state.
+// RLC should not report `hold_box`: the Box saved in the state is dropped by the drop glue of the state
+// even if the future is dropped before completion.
+// RLC should report `hold_raw`: the raw pointer saved in the state is freed only after the resume, so the
+// heap item leaks if the future is dropped at the `.await`.
+
+async fn tick() {}
+
+async fn hold_box() -> i32 {
+    let b = Box::new(1);
+    tick().await;
+    *b
+}
+
+async fn hold_raw() -> i32 {
+    let p = Box::into_raw(Box::new(1));
+    tick().await;
+    let b = unsafe { Box::from_raw(p) };
+    *b
+}
+
+fn main() {
+    let f = hold_box();
+    let g = hold_raw();
+    drop(f);
+    drop(g);
+}
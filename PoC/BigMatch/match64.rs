@@ -0,0 +1,153 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC is a benchmark of the join of a 64-arm match, the arms of the SwitchInt fan out and merge back at a
+// shared block with 64 pre nodes. The out states of the pre nodes are merged by reference, so the analysis should
+// stay linear in the number of arms. Run it with `RLC_STATS=1` to see the time spent, the join itself is checked
+// by the test `merge_64_predecessors` in flow_analysis.rs.
+// RLC should not report `fan_in`: the box built in every arm is dropped after the join.
+// RLC should report `fan_in_leak`: the box is turned into a raw pointer before the match and never recovered.
+
+fn fan_in(n: u8) {
+    let b = match n {
+        0 => Box::new(0),
+        1 => Box::new(1),
+        2 => Box::new(2),
+        3 => Box::new(3),
+        4 => Box::new(4),
+        5 => Box::new(5),
+        6 => Box::new(6),
+        7 => Box::new(7),
+        8 => Box::new(8),
+        9 => Box::new(9),
+        10 => Box::new(10),
+        11 => Box::new(11),
+        12 => Box::new(12),
+        13 => Box::new(13),
+        14 => Box::new(14),
+        15 => Box::new(15),
+        16 => Box::new(16),
+        17 => Box::new(17),
+        18 => Box::new(18),
+        19 => Box::new(19),
+        20 => Box::new(20),
+        21 => Box::new(21),
+        22 => Box::new(22),
+        23 => Box::new(23),
+        24 => Box::new(24),
+        25 => Box::new(25),
+        26 => Box::new(26),
+        27 => Box::new(27),
+        28 => Box::new(28),
+        29 => Box::new(29),
+        30 => Box::new(30),
+        31 => Box::new(31),
+        32 => Box::new(32),
+        33 => Box::new(33),
+        34 => Box::new(34),
+        35 => Box::new(35),
+        36 => Box::new(36),
+        37 => Box::new(37),
+        38 => Box::new(38),
+        39 => Box::new(39),
+        40 => Box::new(40),
+        41 => Box::new(41),
+        42 => Box::new(42),
+        43 => Box::new(43),
+        44 => Box::new(44),
+        45 => Box::new(45),
+        46 => Box::new(46),
+        47 => Box::new(47),
+        48 => Box::new(48),
+        49 => Box::new(49),
+        50 => Box::new(50),
+        51 => Box::new(51),
+        52 => Box::new(52),
+        53 => Box::new(53),
+        54 => Box::new(54),
+        55 => Box::new(55),
+        56 => Box::new(56),
+        57 => Box::new(57),
+        58 => Box::new(58),
+        59 => Box::new(59),
+        60 => Box::new(60),
+        61 => Box::new(61),
+        62 => Box::new(62),
+        _ => Box::new(63),
+    };
+    drop(b);
+}
+
+fn fan_in_leak(n: u8) -> u8 {
+    let p = Box::into_raw(Box::new(n));
+    match n {
+        0 => 0,
+        1 => 2,
+        2 => 4,
+        3 => 6,
+        4 => 8,
+        5 => 10,
+        6 => 12,
+        7 => 14,
+        8 => 16,
+        9 => 18,
+        10 => 20,
+        11 => 22,
+        12 => 24,
+        13 => 26,
+        14 => 28,
+        15 => 30,
+        16 => 32,
+        17 => 34,
+        18 => 36,
+        19 => 38,
+        20 => 40,
+        21 => 42,
+        22 => 44,
+        23 => 46,
+        24 => 48,
+        25 => 50,
+        26 => 52,
+        27 => 54,
+        28 => 56,
+        29 => 58,
+        30 => 60,
+        31 => 62,
+        32 => 64,
+        33 => 66,
+        34 => 68,
+        35 => 70,
+        36 => 72,
+        37 => 74,
+        38 => 76,
+        39 => 78,
+        40 => 80,
+        41 => 82,
+        42 => 84,
+        43 => 86,
+        44 => 88,
+        45 => 90,
+        46 => 92,
+        47 => 94,
+        48 => 96,
+        49 => 98,
+        50 => 100,
+        51 => 102,
+        52 => 104,
+        53 => 106,
+        54 => 108,
+        55 => 110,
+        56 => 112,
+        57 => 114,
+        58 => 116,
+        59 => 118,
+        60 => 120,
+        61 => 122,
+        62 => 124,
+        _ => 126,
+    }
+}
+
+fn main() {
+    fan_in(7);
+    fan_in_leak(7);
+}
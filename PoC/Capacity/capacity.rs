@@ -0,0 +1,38 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the buffer allocated ahead of the elements. Vec::with_capacity(16) (and reserve) allocates
+// even if nothing is pushed, thus forgetting the empty Vec in `capacity_leak` and `reserve_leak` leaks the buffer.
+// `capacity_handed_out` takes the raw pointer of the buffer before forgetting the Vec, the ownership is given to
+// the caller deliberately, and `capacity_ok` drops the Vec as usual.
+
+use std::mem;
+
+fn capacity_leak() {
+    let v: Vec<u64> = Vec::with_capacity(16);
+    mem::forget(v);
+}
+
+fn reserve_leak() {
+    let mut v: Vec<u8> = Vec::new();
+    v.reserve(16);
+    mem::forget(v);
+}
+
+fn capacity_handed_out() -> *mut u64 {
+    let mut v: Vec<u64> = Vec::with_capacity(16);
+    let p = v.as_mut_ptr();
+    mem::forget(v);
+    p
+}
+
+fn capacity_ok() {
+    let v: Vec<u64> = Vec::with_capacity(16);
+}
+
+fn main() {
+    capacity_leak();
+    reserve_leak();
+    let p = capacity_handed_out();
+    unsafe { drop(Vec::from_raw_parts(p, 0, 16)); }
+    capacity_ok();
+}
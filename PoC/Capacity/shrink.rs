@@ -0,0 +1,36 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the reallocation of the buffer. reserve and shrink_to_fit free the old buffer and the container
+// owns the new one in place of it, the container still owns a single buffer afterwards.
+// RLC should not report `resize_and_drop` and `resize_string_and_drop`: the buffer (reallocated twice) is dropped
+// with the container.
+// RLC should report `resize_and_forget`: the buffer reallocated by reserve is never freed.
+
+use std::mem;
+
+fn resize_and_drop() {
+    let mut v: Vec<u64> = Vec::new();
+    v.reserve(64);
+    v.push(1);
+    v.shrink_to_fit();
+}
+
+fn resize_string_and_drop() {
+    let mut s = String::new();
+    s.reserve(64);
+    s.push('a');
+    s.shrink_to_fit();
+}
+
+fn resize_and_forget() {
+    let mut v: Vec<u64> = Vec::with_capacity(4);
+    v.reserve(64);
+    v.shrink_to_fit();
+    mem::forget(v);
+}
+
+fn main() {
+    resize_and_drop();
+    resize_string_and_drop();
+    resize_and_forget();
+}
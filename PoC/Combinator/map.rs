@@ -0,0 +1,33 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the closures run by the combinators of Option and Result. The closure passed to the combinator
+// is a callee of the caller in the call graph, thus it is analyzed before the caller (and in the scope of RLC_ONLY
+// with RLC_ONLY_CALLEES), and the finding in it is located in the closure body.
+// RLC should report the closure in `map_and_forget`: the box allocated in it is turned into a raw pointer and forgotten.
+// RLC should report the closure in `and_then_and_forget` for the same reason.
+// RLC should not report the closure in `map_and_drop`: the box is dropped at the end of the closure body.
+
+fn map_and_forget(opt: Option<u8>) {
+    opt.map(|x| {
+        let p = Box::into_raw(Box::new(x));
+    });
+}
+
+fn and_then_and_forget(res: Result<u8, ()>) -> Result<u8, ()> {
+    res.and_then(|x| {
+        let p = Box::into_raw(Box::new(x));
+        Ok(x)
+    })
+}
+
+fn map_and_drop(opt: Option<u8>) {
+    opt.map(|x| {
+        let _ = Box::new(x);
+    });
+}
+
+fn main() {
+    map_and_forget(Some(1));
+    and_then_and_forget(Ok(1));
+    map_and_drop(Some(1));
+}
@@ -0,0 +1,20 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the initializers of const and static analyzed as bodies given by mir_for_ctfe.
+// The value of the initializer is the return value of its body, thus it escapes and is not a leak.
+
+static EMPTY: Vec<Box<u8>> = Vec::new();
+
+const NONE: Option<Box<u8>> = None;
+
+const LEN: usize = {
+    let v: Vec<Box<u8>> = Vec::new();
+    std::mem::forget(v);
+    0
+};
+
+fn main() {
+    let v = &EMPTY;
+    let n = NONE;
+    let a = [0u8; LEN];
+}
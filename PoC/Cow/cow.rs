@@ -0,0 +1,47 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects Cow as a variant-sensitive owner, Cow::Owned owns the String while Cow::Borrowed owns nothing.
+// RLC should report `drop_owned_on_one_path`: the Cow::Owned returned by `owned` is taken over by the raw pointer
+// and recovered only if `flag` holds, the String in it leaks on the other path.
+// RLC should not report `drop_borrowed_on_one_path`: the Cow is matched as Cow::Borrowed before it is taken over,
+// the arm starts with the Cow owning nothing.
+// RLC should not report `drop_owned`: the Cow::Owned is recovered on every path.
+
+use std::borrow::Cow;
+
+fn owned() -> Cow<'static, str> {
+    Cow::Owned(String::from("rlc"))
+}
+
+fn drop_owned_on_one_path(flag: bool) {
+    let p = Box::into_raw(Box::new(owned()));
+    if flag {
+        unsafe { drop(Box::from_raw(p)); }
+    }
+}
+
+fn drop_borrowed_on_one_path(c: Cow<'static, str>, flag: bool) {
+    match c {
+        Cow::Borrowed(s) => {
+            let keep = std::mem::ManuallyDrop::new(c);
+            if flag {
+                drop(std::mem::ManuallyDrop::into_inner(keep));
+            }
+        },
+        Cow::Owned(_) => (),
+    }
+}
+
+fn drop_owned(flag: bool) {
+    let p = Box::into_raw(Box::new(owned()));
+    let c = unsafe { Box::from_raw(p) };
+    if flag {
+        drop(c);
+    }
+}
+
+fn main() {
+    drop_owned_on_one_path(true);
+    drop_borrowed_on_one_path(Cow::Borrowed("rlc"), true);
+    drop_owned(false);
+}
@@ -0,0 +1,19 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the RAII wrapper that frees its heap data in a hand-written drop.
+// The raw pointer held by `Wrapper` is recovered into a Box in `Drop::drop`, so there is no leak.
+// RLC should not report `main` since the drop of `w` consumes all the fields of `Wrapper`.
+
+struct Wrapper {
+    ptr: *mut i32,
+}
+
+impl Drop for Wrapper {
+    fn drop(&mut self) {
+        unsafe { drop(Box::from_raw(self.ptr)); }
+    }
+}
+
+fn main() {
+    let w = Wrapper { ptr: Box::into_raw(Box::new(1)) };
+}
@@ -0,0 +1,19 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the blocks that can never be executed, e.g., the arm of an uninhabited variant and the
+// otherwise target of the switch on a discriminant. The dead blocks are not visited nor merged into the live
+// blocks, thus RLC should not report `dead_arm` whose pointer is recovered on every live path.
+
+use std::convert::Infallible;
+
+fn dead_arm(r: Result<u8, Infallible>) {
+    let p = Box::into_raw(Box::new(1u8));
+    match r {
+        Ok(_) => unsafe { drop(Box::from_raw(p)); },
+        Err(e) => match e {},
+    }
+}
+
+fn main() {
+    dead_arm(Ok(1));
+}
@@ -0,0 +1,29 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the divergent ownership at a join point (reported with -DIVERGENT). In `divergent`,
+// the raw pointer owns the heap item from the else branch but not from the then branch where it is recovered
+// and dropped, thus the paths disagree at the join. In `agreed`, both branches recover the ownership.
+
+fn divergent(flag: bool) -> *mut i32 {
+    let p = Box::into_raw(Box::new(1));
+    if flag {
+        unsafe { drop(Box::from_raw(p)); }
+    } else {
+        println!("kept");
+    }
+    p
+}
+
+fn agreed(flag: bool) {
+    let p = Box::into_raw(Box::new(1));
+    if flag {
+        unsafe { drop(Box::from_raw(p)); }
+    } else {
+        unsafe { drop(Box::from_raw(p)); }
+    }
+}
+
+fn main() {
+    divergent(true);
+    agreed(false);
+}
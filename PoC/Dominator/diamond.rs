@@ -0,0 +1,17 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC is the diamond cfg for `Graph::dominators`, the body of `diamond` is
+// bb0 (switch on c) -> bb1 | bb2 -> bb3 (drop b) -> return.
+// The immediate dominator of bb1, bb2 and bb3 is bb0, and bb1 does not dominate bb3.
+// The drop of `b` in bb3 dominates the exit, so RLC should not report `diamond`.
+
+fn diamond(c: bool) -> i32 {
+    let b = Box::new(1);
+    let n = if c { 1 } else { 2 };
+    drop(b);
+    n
+}
+
+fn main() {
+    diamond(true);
+}
@@ -0,0 +1,12 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the double free caused by running the drop glue by hand.
+// The box is dropped in place through a raw pointer and then dropped again by the drop glue at the end of `main`.
+// RLC reports it with the flag `-DOUBLE-FREE`.
+
+use std::ptr;
+
+fn main() {
+    let mut b = Box::new(1);
+    unsafe { ptr::drop_in_place(&mut b); }
+}
@@ -0,0 +1,60 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the leak within a destructor, the raw pointer fields of the receiver own the heap buffers
+// allocated in the constructor, and Drop::drop is the last chance to free them.
+// RLC should report `<Pair as Drop>::drop`: the buffer of `back` is taken out of the field but never recovered by
+// Box::from_raw. Note a field is owned by the first read of it in the destructor, a field never read is not checked.
+// RLC should not report `<Freed as Drop>::drop`: both buffers are recovered and dropped.
+// RLC should not report `<Borrowed as Drop>::drop`: the `*const` field does not own the buffer.
+
+struct Pair {
+    front: *mut [u8; 16],
+    back: *mut [u8; 16],
+}
+
+impl Pair {
+    fn new() -> Self {
+        Pair {
+            front: Box::into_raw(Box::new([0; 16])),
+            back: Box::into_raw(Box::new([0; 16])),
+        }
+    }
+}
+
+impl Drop for Pair {
+    fn drop(&mut self) {
+        unsafe {
+            drop(Box::from_raw(self.front));
+        }
+        let back = self.back;
+        self.back = std::ptr::null_mut();
+    }
+}
+
+struct Freed {
+    front: *mut [u8; 16],
+    back: *mut [u8; 16],
+}
+
+impl Drop for Freed {
+    fn drop(&mut self) {
+        unsafe {
+            drop(Box::from_raw(self.front));
+            drop(Box::from_raw(self.back));
+        }
+    }
+}
+
+struct Borrowed {
+    buf: *const [u8; 16],
+}
+
+impl Drop for Borrowed {
+    fn drop(&mut self) {
+        let _ = self.buf;
+    }
+}
+
+fn main() {
+    let p = Pair::new();
+}
@@ -0,0 +1,53 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the drop guard that can be disarmed, its hand-written drop frees the heap data
+// only if the flag `disarmed` is false. The flag is found in `Drop::drop` as the bool field whose
+// branch reaches no call, and it is followed per branch in the caller.
+// RLC should not report `armed` and `checked`: the guard is dropped while armed on every path.
+// RLC should report `disarmed`: the guard is disarmed on the branch of `c` and the Box leaks there.
+// RLC should report `unchecked`: the guard is known to be disarmed in the branch of `if g.disarmed`.
+
+struct Guard {
+    ptr: *mut i32,
+    disarmed: bool,
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        if !self.disarmed {
+            unsafe { drop(Box::from_raw(self.ptr)); }
+        }
+    }
+}
+
+fn armed() {
+    let g = Guard { ptr: Box::into_raw(Box::new(1)), disarmed: false };
+}
+
+fn disarmed(c: bool) {
+    let mut g = Guard { ptr: Box::into_raw(Box::new(1)), disarmed: false };
+    if c {
+        g.disarmed = true;
+    }
+}
+
+fn checked(c: bool) {
+    let mut g = Guard { ptr: Box::into_raw(Box::new(1)), disarmed: c };
+    if g.disarmed {
+        g.disarmed = false;
+    }
+}
+
+fn unchecked(c: bool) {
+    let g = Guard { ptr: Box::into_raw(Box::new(1)), disarmed: c };
+    if g.disarmed {
+        println!("disarmed");
+    }
+}
+
+fn main() {
+    armed();
+    disarmed(true);
+    checked(true);
+    unchecked(true);
+}
@@ -0,0 +1,25 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the drops of several owned locals in the same scope. Each drop is a terminator of its
+// own block and the storage dead statements do not free anything, thus `one_dropped` leaks the second box
+// whose ownership is taken over by the raw pointer, while the first box is dropped. `both_dropped` is safe.
+
+fn one_dropped() {
+    let a = Box::new(1);
+    let b = Box::new(2);
+    let p = Box::into_raw(b);
+    drop(a);
+}
+
+fn both_dropped() {
+    let a = Box::new(1);
+    let b = Box::new(2);
+    let p = Box::into_raw(b);
+    drop(a);
+    unsafe { drop(Box::from_raw(p)); }
+}
+
+fn main() {
+    one_dropped();
+    both_dropped();
+}
@@ -0,0 +1,34 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the calls through trait objects whose callee is chosen by the vtable at runtime.
+// The owned args passed by value (the Box in `consume_dyn`) are consumed by the unknown callee, while the raw
+// pointer passed to it in `ptr_to_dyn` keeps its ownership in the caller by default (-DYN=PESSIMISTIC), thus
+// it is reported as an approximated leak. With -DYN=OPTIMISTIC, the callee is assumed to take it over.
+
+trait Sink {
+    fn put(&self, v: Box<i32>);
+    fn put_raw(&self, p: *mut i32);
+}
+
+struct Free;
+
+impl Sink for Free {
+    fn put(&self, v: Box<i32>) {}
+    fn put_raw(&self, p: *mut i32) {
+        unsafe { drop(Box::from_raw(p)); }
+    }
+}
+
+fn consume_dyn(s: Box<dyn Sink>) {
+    s.put(Box::new(1));
+}
+
+fn ptr_to_dyn(s: Box<dyn Sink>) {
+    let p = Box::into_raw(Box::new(1));
+    s.put_raw(p);
+}
+
+fn main() {
+    consume_dyn(Box::new(Free));
+    ptr_to_dyn(Box::new(Free));
+}
@@ -0,0 +1,28 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the early return in the desugaring of `?`, i.e., `match Try::branch(x)` whose Break arm
+// calls `FromResidual::from_residual` and returns. The box is dropped on both the error path and the normal
+// path, thus RLC should not report `read_ok`, while the raw pointer in `read_leak` is leaked on the error path.
+
+use std::fs::File;
+use std::io::{self, Read};
+
+fn read_ok(file: &mut File) -> io::Result<Box<[u8; 16]>> {
+    let mut buf = Box::new([0u8; 16]);
+    file.read(&mut buf[..])?;
+    Ok(buf)
+}
+
+fn read_leak(file: &mut File) -> io::Result<()> {
+    let p = Box::into_raw(Box::new([0u8; 16]));
+    file.read(unsafe { &mut (*p)[..] })?;
+    unsafe { drop(Box::from_raw(p)); }
+    Ok(())
+}
+
+fn main() {
+    if let Ok(mut file) = File::open("/dev/null") {
+        let _ = read_ok(&mut file);
+        let _ = read_leak(&mut file);
+    }
+}
@@ -0,0 +1,32 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the ownership escaping from the function, which is not a leak.
+// The box or the raw pointer taking over its ownership is returned to the caller, or it is stored
+// behind an out-pointer parameter. RLC should not report any of `make`, `make_raw` and `make_out`.
+
+fn make() -> Box<u8> {
+    let b = Box::new(1);
+    b
+}
+
+fn make_raw() -> *mut u8 {
+    let b = Box::new(1);
+    let p = Box::into_raw(b);
+    p
+}
+
+fn make_out(out: &mut *mut u8) {
+    let b = Box::new(1);
+    *out = Box::into_raw(b);
+}
+
+fn main() {
+    let b = make();
+    let p = make_raw();
+    let mut q = std::ptr::null_mut();
+    make_out(&mut q);
+    unsafe {
+        drop(Box::from_raw(p));
+        drop(Box::from_raw(q));
+    }
+}
@@ -0,0 +1,15 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC declares foreign functions that have no MIR body in the current crate.
+// RLC should skip them and still analyze the rest of the crate, the leak in `main` is expected to be reported.
+
+extern "C" {
+    fn abs(input: i32) -> i32;
+    fn free(ptr: *mut u8);
+}
+
+fn main() {
+    let b = Box::new(1);
+    let ptr = Box::into_raw(b);
+    let n = unsafe { abs(-3) };
+}
@@ -0,0 +1,49 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// The boxes of unsized types are fat pointers, the box owns the heap item whatever its pointee is.
+// RLC should report `leak_dyn`: the trait object is called through `&*b` and the raw pointer is never recovered.
+// RLC should report `leak_slice`: the boxed slice is leaked by the raw pointer.
+// RLC should report `leak_str`: the boxed str is leaked by the raw pointer.
+// RLC should not report `recover_dyn`: the raw pointer of the trait object is recovered by Box::from_raw.
+
+trait Shape {
+    fn area(&self) -> u32;
+}
+
+struct Square(u32);
+
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+}
+
+fn leak_dyn() -> u32 {
+    let b: Box<dyn Shape> = Box::new(Square(2));
+    let area = b.area();
+    let p = Box::into_raw(b);
+    area
+}
+
+fn leak_slice() {
+    let b: Box<[u8]> = vec![1, 2, 3].into_boxed_slice();
+    let p = Box::into_raw(b);
+}
+
+fn leak_str() {
+    let b: Box<str> = String::from("rlc").into_boxed_str();
+    let p = Box::into_raw(b);
+}
+
+fn recover_dyn() -> u32 {
+    let p: *mut dyn Shape = Box::into_raw(Box::new(Square(3)));
+    let b = unsafe { Box::from_raw(p) };
+    b.area()
+}
+
+fn main() {
+    leak_dyn();
+    leak_slice();
+    leak_str();
+    recover_dyn();
+}
@@ -0,0 +1,32 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the ownership crossing the FFI boundary. `alloc_thing` is exported to C and returns the
+// raw pointer of a box for C to free later by `free_thing`. It is not reported by default (-FFI=FRIENDLY),
+// while "-FFI=STRICT" reports the ownership handed to C. The pointer passed to the foreign `c_consume` is
+// assumed to be freed by C in the friendly mode as well.
+
+#[no_mangle]
+pub extern "C" fn alloc_thing() -> *mut u8 {
+    Box::into_raw(Box::new(0u8))
+}
+
+#[no_mangle]
+pub extern "C" fn free_thing(p: *mut u8) {
+    if !p.is_null() {
+        unsafe { drop(Box::from_raw(p)); }
+    }
+}
+
+extern "C" {
+    fn c_consume(p: *mut u8);
+}
+
+fn give_to_c() {
+    let p = Box::into_raw(Box::new(1u8));
+    unsafe { c_consume(p); }
+}
+
+fn main() {
+    let p = alloc_thing();
+    free_thing(p);
+}
@@ -0,0 +1,29 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the taint tracked through the fields of struct. The raw pointer moved into `s.a` carries
+// the taint of the leaked box, `field_leak` drops the other field `s.b` and moves `s.a` out, thus only the local
+// receiving `s.a` is blamed. `field_ok` recovers the box held by the field before the struct is dropped.
+
+struct Pair {
+    a: *mut i32,
+    b: Box<i32>,
+}
+
+fn field_leak() {
+    let p = Box::into_raw(Box::new(1));
+    let mut s = Pair { a: std::ptr::null_mut(), b: Box::new(2) };
+    s.a = p;
+    drop(s.b);
+    let q = s.a;
+}
+
+fn field_ok() {
+    let p = Box::into_raw(Box::new(1));
+    let s = Pair { a: p, b: Box::new(2) };
+    unsafe { drop(Box::from_raw(s.a)); }
+}
+
+fn main() {
+    field_leak();
+    field_ok();
+}
@@ -0,0 +1,53 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the loop whose state may not converge: the raw pointers swapped at each round carry the
+// ownership of the other one, and the nested loop keeps re-visiting the header. If a block of the loop hits
+// the fixpoint cap, only that block keeps its last state, the blocks after the loop are still visited.
+// RLC should report `swap_leak` (with low confidence if the cap is hit): the two boxes swapped in the loop
+// are never turned back into Boxes after it.
+// RLC should not report `swap_free`: the boxes are freed after the loop.
+
+fn ready(n: i32) -> bool {
+    n > 3
+}
+
+fn swap_leak() {
+    let mut a = Box::into_raw(Box::new(1));
+    let mut b = Box::into_raw(Box::new(2));
+    let mut n = 0;
+    while !ready(n) {
+        let mut m = 0;
+        while !ready(m) {
+            let t = a;
+            a = b;
+            b = t;
+            m += 1;
+        }
+        n += 1;
+    }
+}
+
+fn swap_free() {
+    let mut a = Box::into_raw(Box::new(1));
+    let mut b = Box::into_raw(Box::new(2));
+    let mut n = 0;
+    while !ready(n) {
+        let mut m = 0;
+        while !ready(m) {
+            let t = a;
+            a = b;
+            b = t;
+            m += 1;
+        }
+        n += 1;
+    }
+    unsafe {
+        drop(Box::from_raw(a));
+        drop(Box::from_raw(b));
+    }
+}
+
+fn main() {
+    swap_leak();
+    swap_free();
+}
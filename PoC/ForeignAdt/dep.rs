@@ -0,0 +1,19 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// The dependency of the two-crate fixture, build it first with `rustc --crate-type=lib dep.rs`.
+// `Buffer` owns its heap data through a Vec, the ownership is rebuilt from the metadata of this crate
+// when `main.rs` is analyzed.
+
+pub struct Buffer {
+    data: Vec<u8>,
+}
+
+impl Buffer {
+    pub fn new(len: usize) -> Self {
+        Self { data: vec![0; len] }
+    }
+}
+
+pub fn make<T>(value: T) -> Box<T> {
+    Box::new(value)
+}
@@ -0,0 +1,15 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the leaked type defined in the dependency, analyze it with
+// `rlc main.rs --extern dep=libdep.rlib -L .` after building `dep.rs`.
+// RLC should report `main`: the Box<Buffer> is leaked into a raw pointer, and Buffer owns heap
+// data according to the layout loaded from the dependency.
+
+extern crate dep;
+
+use dep::Buffer;
+
+fn main() {
+    let b = dep::make(Buffer::new(16));
+    let ptr = Box::into_raw(b);
+}
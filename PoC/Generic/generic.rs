@@ -0,0 +1,28 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the leaks of generic functions depending on the type params. The leaked `v: T` in
+// `forget_read` owns a heap item only if `T` does, thus the leak is resolved at the call sites: it is
+// reported for the instance `forget_read::<Vec<u8>>` and skipped for `forget_read::<i32>`. The leaked
+// `Vec<T>` buffer in `leak_vec` is reported for the instance `leak_vec::<Box<u8>>` with owned elements.
+
+use std::ptr;
+
+fn forget_read<T>(x: &mut T, y: T) {
+    let p = x as *mut T;
+    let v: T = unsafe { ptr::read(p) };
+    unsafe { ptr::write(p, y); }
+    let q = Box::into_raw(Box::new(v));
+}
+
+fn leak_vec<T>(v: Vec<T>) {
+    let b = Box::new(v);
+    let p = Box::into_raw(b);
+}
+
+fn main() {
+    let mut a = vec![1u8];
+    forget_read(&mut a, vec![2u8]);
+    let mut b = 1i32;
+    forget_read(&mut b, 2);
+    leak_vec(vec![Box::new(1u8)]);
+}
@@ -0,0 +1,28 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the context-sensitive analysis of small leaf callees enabled by "-INLINE=<n>".
+// The summary of `pass` computed in isolation does not know that the returned pointer is the tainted one
+// given by the caller, thus `roundtrip_ok` is reported as a leak without inlining. Analyzing `pass` again with
+// the taint of the arg gives the ownership to `q`, which is recovered by Box::from_raw, and the verdict turns
+// safe, while `roundtrip_leak` never recovers `q` and is still reported.
+
+fn pass(p: *mut i32) -> *mut i32 {
+    p
+}
+
+fn roundtrip_ok() {
+    let p = Box::into_raw(Box::new(1));
+    let q = pass(p);
+    unsafe { drop(Box::from_raw(q)); }
+}
+
+fn roundtrip_leak() -> i32 {
+    let p = Box::into_raw(Box::new(1));
+    let q = pass(p);
+    unsafe { *q }
+}
+
+fn main() {
+    roundtrip_ok();
+    roundtrip_leak();
+}
@@ -0,0 +1,19 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the leak methods of std that return a `&'static mut` and never free the heap item
+// deliberately. RLC should not report any of them as a leak.
+
+fn leak_box() -> &'static mut i32 {
+    Box::leak(Box::new(5))
+}
+
+fn leak_vec() -> &'static mut [u8] {
+    let v = vec![1, 2, 3];
+    v.leak()
+}
+
+fn main() {
+    let x = leak_box();
+    let s = leak_vec();
+    let y = Box::leak(Box::new(5));
+}
@@ -0,0 +1,40 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the ownership of the elements moved out of a container by `into_iter`.
+// The iterator takes over the elements from the Vec, and each `next` hands one of them to the loop body.
+// RLC should not report `drain`: every box is dropped in the loop and the rest are dropped with the iterator.
+// RLC should not report `drain_partly`: the iterator still owns the boxes left after the break and drops them.
+// RLC should report `drain_into_raw`: each box is turned into a raw pointer that is never recovered.
+
+fn make() -> Vec<Box<u8>> {
+    vec![Box::new(1), Box::new(2), Box::new(3)]
+}
+
+fn drain() {
+    let v = make();
+    for b in v {
+        drop(b);
+    }
+}
+
+fn drain_partly() {
+    let v = make();
+    for b in v {
+        if *b == 2 {
+            break;
+        }
+    }
+}
+
+fn drain_into_raw() {
+    let v = make();
+    for b in v {
+        let p = Box::into_raw(b);
+    }
+}
+
+fn main() {
+    drain();
+    drain_partly();
+    drain_into_raw();
+}
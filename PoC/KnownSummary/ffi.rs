@@ -0,0 +1,31 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the known summaries of the functions without mir, the foreign `malloc` and `free` declared
+// here are not the ones in the default summaries (keyed by `libc::malloc` and `libc::free`), and their summaries
+// are given by the file: RLC_SUMMARIES=summaries.toml.
+// RLC should report `forget_buffer` only with the summaries: the buffer returned by malloc is never freed.
+// RLC should not report `free_buffer`: the buffer is given back to free.
+// Without the summaries, the pointer returned by the foreign function owns nothing and neither is reported.
+
+extern "C" {
+    fn malloc(size: usize) -> *mut u8;
+    fn free(p: *mut u8);
+}
+
+fn free_buffer() {
+    unsafe {
+        let p = malloc(16);
+        free(p);
+    }
+}
+
+fn forget_buffer() {
+    unsafe {
+        let p = malloc(16);
+    }
+}
+
+fn main() {
+    free_buffer();
+    forget_buffer();
+}
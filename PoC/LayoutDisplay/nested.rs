@@ -0,0 +1,22 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC is the snapshot of the readable ownership layout in the report of a nested struct.
+// RLC should report `main` with the note (and the `layout` field of RLC_JSON):
+//     the leaked local is owning by its layout
+//     `*mut struct Outer { inner: struct Inner { data: owned, id: not-owned }, flag: not-owned }`
+// The ownership of Outer comes from the Vec inside Inner, while the plain fields own nothing.
+
+struct Inner {
+    data: Vec<u8>,
+    id: u32,
+}
+
+struct Outer {
+    inner: Inner,
+    flag: bool,
+}
+
+fn main() {
+    let outer = Outer { inner: Inner { data: vec![1, 2, 3], id: 7 }, flag: true };
+    let ptr = Box::into_raw(Box::new(outer));
+}
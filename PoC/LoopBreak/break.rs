@@ -0,0 +1,49 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the owned value broken out of a loop, it flows through the break edge into the
+// block after the loop while the back edge keeps the loop in the fixpoint iteration.
+// RLC should not report `break_box` and `break_raw`: the broken value escapes to the caller.
+// RLC should report `break_leak`: the raw pointer broken out of the loop is never turned back into a Box.
+
+fn ready(n: i32) -> bool {
+    n > 3
+}
+
+fn break_box() -> Box<i32> {
+    let mut n = 0;
+    let b = loop {
+        if ready(n) {
+            break Box::new(1)
+        }
+        n += 1;
+    };
+    b
+}
+
+fn break_raw() -> *mut i32 {
+    let mut n = 0;
+    let p = loop {
+        if ready(n) {
+            break Box::into_raw(Box::new(1))
+        }
+        n += 1;
+    };
+    p
+}
+
+fn break_leak() {
+    let mut n = 0;
+    let p = loop {
+        if ready(n) {
+            break Box::into_raw(Box::new(1))
+        }
+        n += 1;
+    };
+}
+
+fn main() {
+    let b = break_box();
+    let p = break_raw();
+    unsafe { drop(Box::from_raw(p)); }
+    break_leak();
+}
@@ -0,0 +1,33 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the drops that differ between the MIR phases selected by -MIR-PHASE.
+// In the analysis MIR (by default) and the built MIR, `b` has one drop at the end of its scope, which frees nothing
+// on the arm where it is moved. The drop elaboration of the optimized MIR guards the drop by a drop flag instead,
+// e.g., `_f = const false` before the move and `switchInt(_f)` before the drop, and the switch on the flag is not
+// correlated with the arm moving `b`.
+// RLC should not report `consumed_on_one_arm` on the analysis or built MIR: the box is either consumed by the callee
+// or dropped at the end of the scope. With -MIR-PHASE=OPTIMIZED, RLC reports it: the path taking the else arm and
+// then the arm of the flag skipping the drop still owns the heap item.
+// RLC should report `leaked_on_one_arm` in all the phases: the raw pointer takes over the box and is never recovered.
+
+fn consume(b: Box<u32>) {}
+
+fn consumed_on_one_arm(flag: bool) {
+    let p = Box::into_raw(Box::new(1));
+    let b = unsafe { Box::from_raw(p) };
+    if flag {
+        consume(b);
+    }
+}
+
+fn leaked_on_one_arm(flag: bool) {
+    let b = Box::new(2);
+    if flag {
+        let p = Box::into_raw(b);
+    }
+}
+
+fn main() {
+    consumed_on_one_arm(true);
+    leaked_on_one_arm(false);
+}
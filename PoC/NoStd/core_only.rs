@@ -0,0 +1,47 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+#![no_std]
+#![crate_type = "lib"]
+
+// This PoC reflects a no_std crate without alloc, there is no Box or Vec and the heap is a bump arena of its own.
+// RLC should run on it without a panic, although the items of std and alloc (e.g., the lang item of Box) are missing.
+// RLC should not report any function: no raw pointer takes over the ownership of a heap item of the global allocator.
+// Note `core::mem::take` and `core::ptr::write` are still recognized by their paths in std.
+
+use core::cell::UnsafeCell;
+use core::mem;
+use core::ptr;
+
+pub struct Arena {
+    buf: UnsafeCell<[u8; 1024]>,
+    top: UnsafeCell<usize>,
+}
+
+impl Arena {
+    pub const fn new() -> Self {
+        Arena { buf: UnsafeCell::new([0; 1024]), top: UnsafeCell::new(0) }
+    }
+
+    pub fn alloc(&self, size: usize) -> *mut u8 {
+        unsafe {
+            let top = &mut *self.top.get();
+            if *top + size > 1024 {
+                return ptr::null_mut();
+            }
+            let p = (self.buf.get() as *mut u8).add(*top);
+            *top += size;
+            p
+        }
+    }
+}
+
+pub fn write_in_place(arena: &Arena) -> *mut u32 {
+    let p = arena.alloc(4) as *mut u32;
+    if !p.is_null() {
+        unsafe { ptr::write(p, 1) };
+    }
+    p
+}
+
+pub fn take_slot(slot: &mut Option<u32>) -> Option<u32> {
+    mem::take(slot)
+}
@@ -0,0 +1,26 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+#![no_std]
+#![crate_type = "lib"]
+
+// This PoC reflects a no_std crate with alloc, whose items are seen by their paths in alloc and core rather than std.
+// RLC should report `leaked`: the raw pointer takes over the box and is never recovered.
+// RLC should not report `forgotten`: `core::mem::forget` leaks the box deliberately.
+// RLC should not report `deallocated`: `alloc::alloc::dealloc` is recognized as `std::alloc::dealloc` and frees the box.
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use core::mem;
+
+pub fn leaked() {
+    let p = Box::into_raw(Box::new(1));
+}
+
+pub fn forgotten() {
+    mem::forget(Box::new(2));
+}
+
+pub fn deallocated() {
+    let p = Box::into_raw(Box::new(3));
+    unsafe { alloc::alloc::dealloc(p as *mut u8, core::alloc::Layout::new::<u32>()) };
+}
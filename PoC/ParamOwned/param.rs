@@ -0,0 +1,26 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the source of the taint, the heap item turned into a raw pointer is either handed in by a
+// parameter or allocated in the function.
+// RLC should not report `take_and_drop`: the box handed in is dropped.
+// RLC should report `take_and_forget` only with "-PARAM-OWNED": the box handed in by the caller is turned into
+// a raw pointer and never recovered, the leak is left to the caller by default.
+// RLC should report `alloc_and_forget`: the box allocated in the function is never recovered.
+
+fn take_and_drop(b: Box<i32>) {
+    drop(b);
+}
+
+fn take_and_forget(b: Box<i32>) {
+    let p = Box::into_raw(b);
+}
+
+fn alloc_and_forget() {
+    let p = Box::into_raw(Box::new(1));
+}
+
+fn main() {
+    take_and_drop(Box::new(1));
+    take_and_forget(Box::new(2));
+    alloc_and_forget();
+}
@@ -0,0 +1,43 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the ownership moved out of the elements of tuples and arrays.
+// RLC should report `tuple_one_dropped`: the pair holds two raw pointers taken over from the boxes, `a` is
+// moved out then recovered while `b` is never recovered.
+// RLC should not report `tuple_both_dropped`: both elements are moved out and recovered.
+// RLC should report `array_one_dropped`: the element moved out of the array by the pattern carries the ownership
+// of the array, the array elements share one entry in the layout.
+// RLC should not report `boxes_moved_out`: the boxes moved out of the tuple are dropped by the drop glue.
+
+fn tuple_one_dropped() {
+    let pair = (Box::into_raw(Box::new(1)), Box::into_raw(Box::new(2)));
+    let (a, b) = pair;
+    unsafe { drop(Box::from_raw(a)); }
+}
+
+fn tuple_both_dropped() {
+    let pair = (Box::into_raw(Box::new(1)), Box::into_raw(Box::new(2)));
+    let (a, b) = pair;
+    unsafe {
+        drop(Box::from_raw(a));
+        drop(Box::from_raw(b));
+    }
+}
+
+fn array_one_dropped() {
+    let arr = [Box::into_raw(Box::new(1)), Box::into_raw(Box::new(2))];
+    let [a, _] = arr;
+    unsafe { drop(Box::from_raw(a)); }
+}
+
+fn boxes_moved_out() {
+    let pair = (Box::new(1), Box::new(2));
+    let (a, b) = pair;
+    drop(a);
+}
+
+fn main() {
+    tuple_one_dropped();
+    tuple_both_dropped();
+    array_one_dropped();
+    boxes_moved_out();
+}
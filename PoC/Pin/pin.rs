@@ -0,0 +1,32 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the pinned allocations. Pin<Box<T>> has the same ownership as Box<T>, so the
+// pinned future-like box leaked by `pin_leak` through into_raw is reported as the leaked Box,
+// while `pin_ok` drops the box when it goes out of scope.
+
+use std::future::Future;
+use std::pin::Pin;
+
+struct Ready(u64);
+
+impl Future for Ready {
+    type Output = u64;
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut std::task::Context<'_>) -> std::task::Poll<u64> {
+        std::task::Poll::Ready(self.0)
+    }
+}
+
+fn pin_ok() {
+    let fut: Pin<Box<dyn Future<Output = u64>>> = Box::pin(Ready(1));
+}
+
+fn pin_leak() -> *mut Ready {
+    let fut = Box::pin(Ready(2));
+    unsafe { Box::into_raw(Pin::into_inner_unchecked(fut)) }
+}
+
+fn main() {
+    pin_ok();
+    let p = pin_leak();
+}
@@ -0,0 +1,35 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the process terminated by exit or abort. No destructor runs after them, thus the box
+// held by `exit_held` when exit(1) is called is given up deliberately and not reported (even with -UNWIND),
+// while `return_leak` leaks the raw pointer on the normal return.
+
+use std::process;
+
+fn exit_held(flag: bool) {
+    let b = Box::new(1);
+    let p = Box::into_raw(b);
+    if flag {
+        process::exit(1);
+    }
+    unsafe { drop(Box::from_raw(p)); }
+}
+
+fn abort_held() {
+    let v = Box::into_raw(Box::new(vec![1, 2, 3]));
+    process::abort();
+}
+
+fn return_leak(flag: bool) {
+    let p = Box::into_raw(Box::new(1));
+    if flag {
+        return;
+    }
+    unsafe { drop(Box::from_raw(p)); }
+}
+
+fn main() {
+    return_leak(true);
+    exit_held(false);
+    abort_held();
+}
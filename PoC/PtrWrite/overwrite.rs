@@ -0,0 +1,43 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the owned place overwritten without dropping the old value.
+// RLC should not report `safe_overwrite`: the assignment drops the first Box by the drop glue before the overwrite.
+// RLC should report `write_overwrite`: ptr::write does not drop the first Box owned by `b`, it leaks.
+// RLC should not report `write_after_drop_in_place`: the first Box is dropped in place before it is overwritten.
+// RLC should not report `write_uninit`: the MaybeUninit owns nothing before it is written.
+
+use std::mem::MaybeUninit;
+use std::ptr;
+
+fn safe_overwrite() {
+    let mut b = Box::new(1);
+    b = Box::new(2);
+}
+
+fn write_overwrite() {
+    let mut b = Box::new(1);
+    unsafe { ptr::write(&mut b, Box::new(2)); }
+}
+
+fn write_after_drop_in_place() {
+    let mut b = Box::new(1);
+    unsafe {
+        ptr::drop_in_place(&mut b);
+        ptr::write(&mut b, Box::new(2));
+    }
+}
+
+fn write_uninit() -> Box<i32> {
+    let mut slot: MaybeUninit<Box<i32>> = MaybeUninit::uninit();
+    unsafe {
+        ptr::write(slot.as_mut_ptr(), Box::new(3));
+        slot.assume_init()
+    }
+}
+
+fn main() {
+    safe_overwrite();
+    write_overwrite();
+    write_after_drop_in_place();
+    write_uninit();
+}
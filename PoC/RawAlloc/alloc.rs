@@ -0,0 +1,52 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the manual heap management through the allocator API. The pointer returned by
+// alloc (or alloc_zeroed, realloc and GlobalAlloc::alloc) owns the allocation until it is passed to dealloc.
+// `alloc_ok` and `realloc_ok` free the memory on every path, while `alloc_leak` forgets it on the early
+// return and `global_leak` never frees it.
+
+use std::alloc::{alloc, alloc_zeroed, dealloc, realloc, GlobalAlloc, Layout, System};
+
+fn alloc_ok() {
+    let layout = Layout::new::<u64>();
+    unsafe {
+        let p = alloc(layout) as *mut u64;
+        *p = 1;
+        dealloc(p as *mut u8, layout);
+    }
+}
+
+fn realloc_ok() {
+    let layout = Layout::array::<u32>(4).unwrap();
+    unsafe {
+        let p = alloc_zeroed(layout);
+        let q = realloc(p, layout, 32);
+        dealloc(q, Layout::array::<u32>(8).unwrap());
+    }
+}
+
+fn alloc_leak(flag: bool) {
+    let layout = Layout::new::<u64>();
+    unsafe {
+        let p = alloc(layout);
+        if flag {
+            return;
+        }
+        dealloc(p, layout);
+    }
+}
+
+fn global_leak() {
+    let layout = Layout::new::<u64>();
+    unsafe {
+        let p = System.alloc(layout);
+        *p = 1;
+    }
+}
+
+fn main() {
+    alloc_ok();
+    realloc_ok();
+    alloc_leak(true);
+    global_leak();
+}
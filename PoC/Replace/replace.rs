@@ -0,0 +1,38 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects mem::replace, mem::take and Option::take that move the old value out to the caller.
+// The ownership held by the old raw pointer belongs to the returned value, thus `replace_leak` is a leak of
+// the ignored old pointer, while `replace_ok` recovers it. The owned values returned by `take_dropped`
+// (including the ignored temporary) are dropped by the drop glue, which is not a leak.
+
+use std::mem;
+
+fn replace_ok() {
+    let mut p = Box::into_raw(Box::new(1));
+    let q = Box::into_raw(Box::new(2));
+    let old = mem::replace(&mut p, q);
+    unsafe {
+        drop(Box::from_raw(old));
+        drop(Box::from_raw(p));
+    }
+}
+
+fn replace_leak() {
+    let mut p = Box::into_raw(Box::new(1));
+    let q = Box::into_raw(Box::new(2));
+    let _ = mem::replace(&mut p, q);
+    unsafe { drop(Box::from_raw(p)); }
+}
+
+fn take_dropped() {
+    let mut v = vec![Box::new(1)];
+    let old = mem::take(&mut v);
+    let mut o = Some(Box::new(2));
+    let _ = o.take();
+}
+
+fn main() {
+    replace_ok();
+    replace_leak();
+    take_dropped();
+}
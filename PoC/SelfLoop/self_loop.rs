@@ -0,0 +1,32 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the cfg edges normalized by `Graph::merge_parallel_edges`.
+// The loop of `spin` is a single block jumping to itself, the block is flagged as a self loop (and cyclic) so that
+// its state is merged from the entry and from itself. RLC should not report `spin`: the raw pointer is recovered
+// after the loop.
+// The Drop of `b` in `drop_in_loop` may have the real and the unwind successors collapsed to the same block with
+// "-UNWIND", the parallel edges are merged into one pre node. RLC should report `drop_in_loop`: the raw pointer
+// taken in the loop is never recovered.
+
+fn spin(n: usize) {
+    let p = Box::into_raw(Box::new(n));
+    let mut i = 0;
+    while i < n {
+        i += 1;
+    }
+    unsafe { drop(Box::from_raw(p)); }
+}
+
+fn drop_in_loop(n: usize) {
+    let mut last = std::ptr::null_mut();
+    for i in 0..n {
+        let b = Box::new(i);
+        last = Box::into_raw(Box::new(i));
+        drop(b);
+    }
+}
+
+fn main() {
+    spin(3);
+    drop_in_loop(3);
+}
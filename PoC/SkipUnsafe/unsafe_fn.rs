@@ -0,0 +1,31 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the `unsafe fn` skipped by RLC_SKIP_UNSAFE.
+// RLC should report `leak_in_unsafe` by default: the raw pointer taken over from the Box is never recovered.
+// RLC should not report it with RLC_SKIP_UNSAFE, its body is trusted and not analyzed.
+// RLC should not report `hand_to_unsafe` in both cases: the pointer is consumed by `free_later` (its summary
+// is opaque with RLC_SKIP_UNSAFE, it takes over all the args), and the safe `leak_in_safe` is still reported.
+
+unsafe fn leak_in_unsafe(n: usize) -> usize {
+    let p = Box::into_raw(Box::new(n));
+    *p
+}
+
+unsafe fn free_later(p: *mut usize) {
+    drop(Box::from_raw(p));
+}
+
+fn hand_to_unsafe(n: usize) {
+    let p = Box::into_raw(Box::new(n));
+    unsafe { free_later(p); }
+}
+
+fn leak_in_safe(n: usize) {
+    let p = Box::into_raw(Box::new(n));
+}
+
+fn main() {
+    unsafe { leak_in_unsafe(1); }
+    hand_to_unsafe(2);
+    leak_in_safe(3);
+}
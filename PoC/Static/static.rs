@@ -0,0 +1,41 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+#![feature(thread_local)]
+
+// This PoC reflects the owned values stored into statics, they live until the program exits.
+// RLC should not report `store_box` and `store_raw`: the Box (and the raw pointer taking over the Box) moved
+// into the `static mut` escapes from the function, they are reported as stored in static only by `-STATIC`.
+// RLC should not report `store_thread_local` either: the `#[thread_local]` static is accessed in the same way.
+// RLC should report `store_copy`: only the value in the Box is copied into the static while the Box leaks.
+
+static mut CACHE: Option<Box<i32>> = None;
+static mut RAW: *mut i32 = std::ptr::null_mut();
+
+#[thread_local]
+static mut LOCAL: Option<Box<i32>> = None;
+
+fn store_box() {
+    let b = Box::new(1);
+    unsafe { CACHE = Some(b); }
+}
+
+fn store_raw() {
+    let p = Box::into_raw(Box::new(2));
+    unsafe { RAW = p; }
+}
+
+fn store_thread_local() {
+    let b = Box::new(3);
+    unsafe { LOCAL = Some(b); }
+}
+
+fn store_copy() {
+    let p = Box::into_raw(Box::new(4));
+    unsafe { CACHE = Some(Box::new(*p)); }
+}
+
+fn main() {
+    store_box();
+    store_raw();
+    store_thread_local();
+    store_copy();
+}
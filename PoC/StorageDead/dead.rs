@@ -0,0 +1,34 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects StorageDead distinguished from the Drop terminator. StorageDead never drops the local,
+// thus the box moved out of `b` in `moved_out` reaches StorageDead with nothing owned and is not a leak,
+// the Copy value in `copy_dead` is irrelevant, while the raw pointer `p` in `owned_dead` still owns the heap
+// item at its StorageDead and is reported with a note there.
+
+fn consume(b: Box<i32>) {}
+
+fn moved_out() {
+    {
+        let b = Box::new(1);
+        consume(b);
+    }
+}
+
+fn copy_dead() {
+    {
+        let x = 1;
+        let y = x;
+    }
+}
+
+fn owned_dead() {
+    {
+        let p = Box::into_raw(Box::new(1));
+    }
+}
+
+fn main() {
+    moved_out();
+    copy_dead();
+    owned_dead();
+}
@@ -0,0 +1,22 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the mutually recursive functions analyzed as a group of the call graph.
+// `even` and `odd` pass the pointer to each other and `odd` takes back its ownership at last,
+// the summaries of both are refined until stable, thus `main` is not a leak.
+
+fn even(p: *mut u8, n: usize) {
+    odd(p, n)
+}
+
+fn odd(p: *mut u8, n: usize) {
+    if n == 0 {
+        unsafe { drop(Box::from_raw(p)); }
+    } else {
+        even(p, n - 1)
+    }
+}
+
+fn main() {
+    let p = Box::into_raw(Box::new(1));
+    even(p, 4);
+}
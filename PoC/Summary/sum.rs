@@ -0,0 +1,28 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the ownership summaries of the callees consulted at the call sites.
+// `release` takes back the ownership of its param, thus `free_ok` is not a leak.
+// `make_raw` returns a pointer carrying the ownership, thus `leak` is a leak of the returned pointer.
+
+fn make_raw() -> *mut u8 {
+    let b = Box::new(1);
+    Box::into_raw(b)
+}
+
+fn release(p: *mut u8) {
+    unsafe { drop(Box::from_raw(p)); }
+}
+
+fn free_ok() {
+    let p = make_raw();
+    release(p);
+}
+
+fn leak() {
+    let p = make_raw();
+}
+
+fn main() {
+    free_ok();
+    leak();
+}
@@ -0,0 +1,38 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the reference returned into a local allocation, the borrow checker rejects it unless the
+// lifetime of the reference is laundered, and the box is dropped at the end of the function.
+// RLC should report `laundered` with -UAF: the reference is transmuted to `'static` but points into the heap
+// item of `b`, which is freed before the return.
+// RLC should report `reborrowed` with -UAF: the reference is reborrowed before it is transmuted.
+// RLC should not report `leaked`: the box is leaked so its heap item outlives the function.
+// RLC should not report `borrowed`: the returned reference points into the parameter, which is not dropped.
+
+fn laundered() -> &'static u32 {
+    let b = Box::new(1);
+    unsafe { std::mem::transmute::<&u32, &'static u32>(&*b) }
+}
+
+fn reborrowed() -> &'static u32 {
+    let b = Box::new(2);
+    let r = &*b;
+    let s = &*r;
+    unsafe { std::mem::transmute::<&u32, &'static u32>(s) }
+}
+
+fn leaked() -> &'static u32 {
+    let b = Box::new(3);
+    Box::leak(b)
+}
+
+fn borrowed(b: &Box<u32>) -> &u32 {
+    &*b
+}
+
+fn main() {
+    let _ = laundered();
+    let _ = reborrowed();
+    let _ = leaked();
+    let b = Box::new(4);
+    let _ = borrowed(&b);
+}
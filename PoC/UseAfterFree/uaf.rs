@@ -0,0 +1,14 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the use after free of a raw pointer.
+// The ownership of the box is taken over by `p` and recovered by `Box::from_raw`, which is dropped at once.
+// The following dereference of `p` reads the freed heap item. RLC reports it with the flag `-UAF`.
+
+fn main() {
+    let b = Box::new(1);
+    let p = Box::into_raw(b);
+    unsafe {
+        drop(Box::from_raw(p));
+        let v = *p;
+    }
+}
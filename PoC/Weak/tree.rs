@@ -0,0 +1,32 @@
+#![cfg_attr(debug_assertions, allow(dead_code, unused_imports, unused_variables, unused_mut, dead_code))]
+
+// This PoC reflects the Weak pointers in the reference cycle check with "-RC-CYCLE".
+// RLC should not report `TreeNode`: the children are held by Rc while the back-pointer to the parent is a Weak,
+// which does not keep the parent alive, thus the tree is freed once the root is dropped.
+// RLC should report `ListNode`: the node can reach itself through Rc and the cycle is never freed.
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+struct TreeNode {
+    value: i32,
+    parent: RefCell<Weak<TreeNode>>,
+    children: RefCell<Vec<Rc<TreeNode>>>,
+}
+
+struct ListNode {
+    value: i32,
+    next: RefCell<Option<Rc<ListNode>>>,
+}
+
+fn tree() {
+    let leaf = Rc::new(TreeNode { value: 3, parent: RefCell::new(Weak::new()), children: RefCell::new(vec![]) });
+    let branch = Rc::new(TreeNode { value: 5, parent: RefCell::new(Weak::new()), children: RefCell::new(vec![leaf.clone()]) });
+    *leaf.parent.borrow_mut() = Rc::downgrade(&branch);
+}
+
+fn main() {
+    tree();
+    let a = Rc::new(ListNode { value: 1, next: RefCell::new(None) });
+    *a.next.borrow_mut() = Some(a.clone());
+}
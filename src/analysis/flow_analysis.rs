@@ -2,30 +2,110 @@ pub mod ownership;
 pub mod order;
 pub mod intro_visitor;
 pub mod inter_visitor;
+pub mod call_graph;
+pub mod summary;
 
 use rustc_middle::ty::TyCtxt;
 use rustc_span::def_id::DefId;
-use rustc_middle::mir::Body;
+use rustc_middle::mir::{BasicBlock, Body, Local, SourceInfo, TerminatorKind};
+use rustc_span::Span;
+use rustc_data_structures::fx::FxHashMap;
 
 use crate::{Elapsed, rlc_info, RlcGlobalCtxt};
-use crate::analysis::type_analysis::{AdtOwner, OwnershipLayout, Unique};
-use crate::analysis::type_analysis::type_visitor::{TyWithIndex, mir_body};
-use crate::analysis::flow_analysis::ownership::{IntroVar, Taint};
+use crate::analysis::type_analysis::{AdtOwner, OwnershipLayout, Unique, is_drop_method};
+use crate::analysis::type_analysis::type_visitor::{TyWithIndex, is_analyzable_body, mir_body};
+use crate::analysis::flow_analysis::ownership::{AllocId, IntroVar, Taint, TaintOrigin};
+use crate::analysis::flow_analysis::intro_visitor::{ExitKind, escaping_locals, param_aliases};
 use crate::analysis::{IcxMut, IcxSliceMut, Rcx, RcxMut};
+use crate::components::metrics::Approximation;
+use crate::components::report::{LeakReport, dedup_reports};
+use crate::components::report::json::emit_json;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::{Debug, Formatter};
-use std::env;
+use std::ops::Range;
 
-pub type MirGraph = HashMap<DefId, Graph>;
+use z3::ast;
+use stopwatch::Stopwatch;
+
+pub type MirGraph = FxHashMap<DefId, Graph>;
 pub type ToPo = Vec<usize>;
 pub type Edges = Vec<Vec<usize>>;
+// the kinds of the edges, aligned with the successors in Edges
+pub type EdgeKinds = Vec<Vec<EdgeKind>>;
+
+// The kind of a cfg edge by the terminator it comes from.
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub enum EdgeKind {
+    Goto,
+    // an arm of SwitchInt, including the otherwise arm
+    SwitchArm,
+    // the normal return of a Call
+    CallReturn,
+    // the drop glue finishes without panic
+    DropSuccess,
+    AssertSuccess,
+    // the unwind edge of Call, Drop or Assert to the cleanup block
+    Cleanup,
+}
 
-#[derive(Debug, Clone)]
+impl EdgeKind {
+    pub fn is_cleanup(&self) -> bool {
+        *self == EdgeKind::Cleanup
+    }
+}
+// The source position of a basic block, recorded along with its edges.
+#[derive(Debug, Copy, Clone)]
+pub struct BlockMeta {
+    // the mir block (as `bb3` in the mir dump) of the node
+    bb: BasicBlock,
+    // the offset of the first statement and the number of statements, counted over the blocks in index order
+    start: usize,
+    len: usize,
+    // the span from the first statement to the terminator
+    span: Span,
+    terminator: SourceInfo,
+}
+
+impl BlockMeta {
+    pub fn new(bb: BasicBlock, start: usize, len: usize, span: Span, terminator: SourceInfo) -> Self {
+        Self { bb, start, len, span, terminator }
+    }
+
+    pub fn bb(&self) -> BasicBlock {
+        self.bb
+    }
+
+    pub fn statements(&self) -> Range<usize> {
+        self.start..self.start + self.len
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn terminator(&self) -> SourceInfo {
+        self.terminator
+    }
+}
+
+// the kind and block of the exit, and the constraint that each local is freed at this exit
+pub type ExitConstraints<'ctx> = (ExitKind, usize, Vec<(usize, ast::Bool<'ctx>)>);
+
+// The graph is (de)serialized without the block meta, which refers to the spans of the session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Graph {
     e: Edges,
     pre: Edges,
     topo: ToPo,
+    kinds: EdgeKinds,
+    // whether the block belongs to a non-trivial scc (a loop) that needs fixpoint iteration
+    cyclic: Vec<bool>,
+    // whether the block jumps to itself, e.g., a loop of a single block, the self edge is one of its pre nodes
+    self_loop: Vec<bool>,
+    // the source positions of the blocks, empty for the graphs not built from a mir body (e.g., the call graph)
+    #[serde(skip)]
+    meta: Vec<BlockMeta>,
 }
 
 impl Default for Graph {
@@ -34,6 +114,10 @@ impl Default for Graph {
             e: Vec::default(),
             pre: Vec::default(),
             topo: Vec::default(),
+            kinds: Vec::default(),
+            cyclic: Vec::default(),
+            self_loop: Vec::default(),
+            meta: Vec::default(),
         }
     }
 }
@@ -44,6 +128,10 @@ impl Graph {
             e: vec![Vec::new() ; len],
             pre: vec![Vec::new() ; len],
             topo: Vec::new(),
+            kinds: vec![Vec::new() ; len],
+            cyclic: vec![false ; len],
+            self_loop: vec![false ; len],
+            meta: Vec::new(),
         }
     }
 
@@ -63,6 +151,21 @@ impl Graph {
         &mut self.pre
     }
 
+    pub fn get_edge_kinds(&self) -> &EdgeKinds {
+        &self.kinds
+    }
+
+    pub fn get_edge_kinds_mut(&mut self) -> &mut EdgeKinds {
+        &mut self.kinds
+    }
+
+    // the kind of the edge from `from` to `to`, none if there is no such edge or its kind is not recorded
+    // (e.g., the edges of the call graph)
+    pub fn edge_kind(&self, from: usize, to: usize) -> Option<EdgeKind> {
+        let idx = self.e.get(from)?.iter().position(|next| *next == to)?;
+        self.kinds.get(from)?.get(idx).copied()
+    }
+
     pub fn get_topo(&self) -> &ToPo {
         &self.topo
     }
@@ -70,21 +173,426 @@ impl Graph {
     pub fn get_topo_mut(&mut self) -> &mut ToPo {
         &mut self.topo
     }
+
+    // walk the reachable blocks in topo order, e.g., for the forward dataflow
+    pub fn topo_order(&self) -> impl DoubleEndedIterator<Item=usize> + '_ {
+        self.topo.iter().copied()
+    }
+
+    // walk the reachable blocks in reverse topo order, e.g., for the backward liveness
+    pub fn reverse_topo_order(&self) -> impl Iterator<Item=usize> + '_ {
+        self.topo_order().rev()
+    }
+
+    pub fn num_blocks(&self) -> usize {
+        self.e.len()
+    }
+
+    pub fn successors(&self, bb: usize) -> impl Iterator<Item=usize> + '_ {
+        debug_assert!(bb < self.num_blocks(), "bb{} is out of range of the {} blocks", bb, self.num_blocks());
+        self.e[bb].iter().copied()
+    }
+
+    pub fn predecessors(&self, bb: usize) -> impl Iterator<Item=usize> + '_ {
+        debug_assert!(bb < self.num_blocks(), "bb{} is out of range of the {} blocks", bb, self.num_blocks());
+        self.pre[bb].iter().copied()
+    }
+
+    // the checked accessors, none if the block is out of range
+    pub fn get_successors(&self, bb: usize) -> Option<&Vec<usize>> {
+        self.e.get(bb)
+    }
+
+    pub fn get_predecessors(&self, bb: usize) -> Option<&Vec<usize>> {
+        self.pre.get(bb)
+    }
+
+    // assert (in debug build) that the block is in range of the graph of the function `did`
+    pub fn check_block(&self, did: DefId, bb: usize) {
+        debug_assert!(
+            bb < self.num_blocks(),
+            "bb{} is out of range of the {} blocks in {:?}",
+            bb,
+            self.num_blocks(),
+            did
+        );
+    }
+
+    pub fn is_cyclic(&self, idx: usize) -> bool {
+        self.cyclic[idx]
+    }
+
+    pub fn set_cyclic(&mut self, idx: usize) {
+        self.cyclic[idx] = true;
+    }
+
+    pub fn has_self_loop(&self, idx: usize) -> bool {
+        self.self_loop[idx]
+    }
+
+    pub fn meta(&self) -> &Vec<BlockMeta> {
+        &self.meta
+    }
+
+    pub fn block_meta(&self, idx: usize) -> Option<&BlockMeta> {
+        self.meta.get(idx)
+    }
+
+    // the mir block of the node for diagnostics, the node is the block of the same index if the graph carries no
+    // block meta (the indices coincide as long as no block is pruned or reindexed)
+    pub fn mir_bb(&self, idx: usize) -> BasicBlock {
+        self.block_meta(idx).map_or_else(|| BasicBlock::from_usize(idx), BlockMeta::bb)
+    }
+
+    // the source range of the block, none if the graph carries no block meta
+    pub fn block_span(&self, idx: usize) -> Option<Span> {
+        self.block_meta(idx).map(BlockMeta::span)
+    }
+
+    // Normalize the edges so that each successor appears once for a block, e.g., the real and unwind successors of
+    // a Drop collapsing to the same block after the cleanup is pruned. The first edge (the real one) keeps its kind
+    // and the pre nodes are rebuilt from the deduped edges. The self edges are recorded as self loops.
+    pub fn merge_parallel_edges(&mut self) {
+        for block in 0..self.e.len() {
+            let mut seen: HashSet<usize> = HashSet::default();
+            let mut kinds = self.kinds[block].iter();
+            let (mut edges, mut edge_kinds) = (Vec::new(), Vec::new());
+            for next in self.e[block].iter() {
+                let kind = kinds.next();
+                if !seen.insert(*next) {
+                    continue;
+                }
+                edges.push(*next);
+                edge_kinds.extend(kind.copied());
+            }
+            self.self_loop[block] = edges.contains(&block);
+            self.e[block] = edges;
+            self.kinds[block] = edge_kinds;
+        }
+
+        for pre in self.pre.iter_mut() {
+            pre.clear();
+        }
+        for block in 0..self.e.len() {
+            for next in self.e[block].clone() {
+                self.pre[next].push(block);
+            }
+        }
+    }
+
+    // the blocks reachable from the start block by bfs over the forward edges, including the start itself
+    pub fn reachable_from(&self, start: usize) -> HashSet<usize> {
+        let mut reachable = HashSet::default();
+        if start >= self.e.len() {
+            return reachable;
+        }
+
+        let mut queue = VecDeque::from([start]);
+        reachable.insert(start);
+        while let Some(top) = queue.pop_front() {
+            for next in self.e[top].iter() {
+                if reachable.insert(*next) {
+                    queue.push_back(*next);
+                }
+            }
+        }
+        reachable
+    }
+
+    // find the strongly connected components by tarjan's algorithm on the forward edges
+    // the sccs are returned in reverse topo order of the condensed dag and each scc is sorted by index
+    pub fn find_sccs(&self) -> Vec<Vec<usize>> {
+        let len = self.e.len();
+        let mut index = vec![usize::MAX ; len];
+        let mut low = vec![0 ; len];
+        let mut on_stack = vec![false ; len];
+        let mut stack = Vec::new();
+        let mut sccs = Vec::new();
+        let mut counter = 0;
+
+        for root in 0..len {
+            if index[root] != usize::MAX {
+                continue;
+            }
+
+            // the dfs is iterative to avoid overflow on huge bodies, each frame is (node, next edge to visit)
+            let mut dfs = vec![(root, 0)];
+            index[root] = counter;
+            low[root] = counter;
+            counter += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some((node, edge)) = dfs.last().copied() {
+                if edge < self.e[node].len() {
+                    dfs.last_mut().unwrap().1 += 1;
+                    let next = self.e[node][edge];
+                    if index[next] == usize::MAX {
+                        index[next] = counter;
+                        low[next] = counter;
+                        counter += 1;
+                        stack.push(next);
+                        on_stack[next] = true;
+                        dfs.push((next, 0));
+                    } else if on_stack[next] {
+                        low[node] = low[node].min(index[next]);
+                    }
+                    continue;
+                }
+
+                dfs.pop();
+                if let Some((parent, _)) = dfs.last() {
+                    low[*parent] = low[*parent].min(low[node]);
+                }
+
+                if low[node] == index[node] {
+                    let mut scc = Vec::new();
+                    while let Some(top) = stack.pop() {
+                        on_stack[top] = false;
+                        scc.push(top);
+                        if top == node {
+                            break;
+                        }
+                    }
+                    scc.sort_unstable();
+                    sccs.push(scc);
+                }
+            }
+        }
+
+        sccs
+    }
+
+    // compute the dominator tree of the blocks reachable from bb0 on the forward edges
+    // by the iterative algorithm of Cooper, Harvey and Kennedy over the reverse post order
+    pub fn dominators(&self) -> Dominators {
+        let len = self.e.len();
+        let mut idom = vec![None ; len];
+        if len == 0 {
+            return Dominators { idom };
+        }
+
+        let mut pre = vec![Vec::new() ; len];
+        for (bidx, edges) in self.e.iter().enumerate() {
+            for next in edges {
+                pre[*next].push(bidx);
+            }
+        }
+
+        // the post order by an iterative dfs, each frame is (node, next edge to visit)
+        let mut post = Vec::with_capacity(len);
+        let mut visited = vec![false ; len];
+        let mut dfs = vec![(0, 0)];
+        visited[0] = true;
+        while let Some((node, edge)) = dfs.last().copied() {
+            if edge < self.e[node].len() {
+                dfs.last_mut().unwrap().1 += 1;
+                let next = self.e[node][edge];
+                if !visited[next] {
+                    visited[next] = true;
+                    dfs.push((next, 0));
+                }
+                continue;
+            }
+            dfs.pop();
+            post.push(node);
+        }
+
+        let mut rpo = vec![usize::MAX ; len];
+        for (pos, bidx) in post.iter().rev().enumerate() {
+            rpo[*bidx] = pos;
+        }
+
+        // bb0 is its own dominator during the iteration, the walk up the tree stops there
+        idom[0] = Some(0);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for bidx in post.iter().rev().skip(1) {
+                let mut new_idom: Option<usize> = None;
+                for p in pre[*bidx].iter().copied() {
+                    if idom[p].is_none() {
+                        continue;
+                    }
+                    new_idom = Some(match new_idom {
+                        Some(cur) => intersect_dominators(&idom, &rpo, p, cur),
+                        None => p,
+                    });
+                }
+                if new_idom != idom[*bidx] {
+                    idom[*bidx] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+
+        Dominators { idom }
+    }
+
+    // emit the graph in graphviz dot format
+    // the label of each node is the index and terminator kind of the basic block
+    // and the topo attribute is the index of the block in topo order (-1 if not in the order)
+    pub fn to_dot(&self, body: &Body) -> String {
+        let mut dot = String::from("digraph mir {\n    node [shape=box];\n");
+
+        for (bidx, data) in body.basic_blocks().iter().enumerate() {
+            let topo = match self.topo.iter().position(|t| *t == bidx) {
+                Some(idx) => idx as isize,
+                None => -1,
+            };
+            dot.push_str(&format!(
+                "    bb{} [label=\"{:?}\\n{}\", topo={}];\n",
+                bidx,
+                self.mir_bb(bidx),
+                terminator_kind_name(&data.terminator().kind),
+                topo,
+            ));
+        }
+
+        // the cleanup edges are dashed red and the switch arms are blue
+        for (bidx, edges) in self.e.iter().enumerate() {
+            for next in edges {
+                let style = match self.edge_kind(bidx, *next) {
+                    Some(EdgeKind::Cleanup) => " [color=red, style=dashed]",
+                    Some(EdgeKind::SwitchArm) => " [color=blue]",
+                    _ => "",
+                };
+                dot.push_str(&format!("    bb{} -> bb{}{};\n", bidx, next, style));
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+// the nearest common dominator of two blocks, found by walking up the tree from the later one in rpo
+fn intersect_dominators(idom: &[Option<usize>], rpo: &[usize], a: usize, b: usize) -> usize {
+    let (mut a, mut b) = (a, b);
+    while a != b {
+        while rpo[a] > rpo[b] {
+            a = idom[a].unwrap();
+        }
+        while rpo[b] > rpo[a] {
+            b = idom[b].unwrap();
+        }
+    }
+    a
+}
+
+// The dominator tree of a cfg, block a dominates block b if every path from bb0 to b goes through a,
+// e.g., a drop dominating the exit runs on all paths. The blocks unreachable from bb0 have no dominator.
+#[derive(Debug, Clone, Default)]
+pub struct Dominators {
+    idom: Vec<Option<usize>>,
+}
+
+impl Dominators {
+    // the immediate dominator of the block, none for bb0 and the unreachable blocks
+    pub fn immediate_dominator(&self, bb: usize) -> Option<usize> {
+        match self.idom.get(bb).copied().flatten() {
+            Some(idom) if idom != bb => Some(idom),
+            _ => None,
+        }
+    }
+
+    pub fn is_reachable(&self, bb: usize) -> bool {
+        matches!(self.idom.get(bb), Some(Some(_)))
+    }
+
+    // whether a dominates b, every reachable block dominates itself
+    pub fn dominates(&self, a: usize, b: usize) -> bool {
+        if !self.is_reachable(a) || !self.is_reachable(b) {
+            return false;
+        }
+        let mut cur = b;
+        loop {
+            if cur == a {
+                return true;
+            }
+            match self.immediate_dominator(cur) {
+                Some(idom) => cur = idom,
+                None => return false,
+            }
+        }
+    }
+}
+
+fn terminator_kind_name(kind: &TerminatorKind) -> &'static str {
+    match kind {
+        TerminatorKind::Goto { .. } => "Goto",
+        TerminatorKind::SwitchInt { .. } => "SwitchInt",
+        TerminatorKind::Resume => "Resume",
+        TerminatorKind::Abort => "Abort",
+        TerminatorKind::Return => "Return",
+        TerminatorKind::Unreachable => "Unreachable",
+        TerminatorKind::Drop { .. } => "Drop",
+        TerminatorKind::DropAndReplace { .. } => "DropAndReplace",
+        TerminatorKind::Call { .. } => "Call",
+        TerminatorKind::Assert { .. } => "Assert",
+        TerminatorKind::Yield { .. } => "Yield",
+        TerminatorKind::GeneratorDrop => "GeneratorDrop",
+        TerminatorKind::FalseEdge { .. } => "FalseEdge",
+        TerminatorKind::FalseUnwind { .. } => "FalseUnwind",
+        TerminatorKind::InlineAsm { .. } => "InlineAsm",
+    }
 }
 
 pub struct FlowAnalysis<'tcx, 'a> {
     rcx: &'a mut RlcGlobalCtxt<'tcx>,
     fn_set: Unique,
+    // analyze the clean-up blocks (unwinding) as well as the normal paths
+    unwind: bool,
+    // the functions analyzed by the intro phase in the order of analysis
+    analyzed: Vec<DefId>,
+    // the findings of each analyzed function, they are collected when the analysis is done
+    results: Vec<(DefId, Vec<LeakReport>)>,
 }
 
 impl<'tcx, 'a> FlowAnalysis<'tcx, 'a> {
     pub fn new(rcx: &'a mut RlcGlobalCtxt<'tcx>) -> Self {
         Self {
             rcx,
-            fn_set: HashSet::new(),
+            fn_set: Unique::default(),
+            unwind: false,
+            analyzed: Vec::new(),
+            results: Vec::new(),
         }
     }
 
+    pub fn with_unwind(mut self, unwind: bool) -> Self {
+        self.unwind = unwind;
+        self
+    }
+
+    pub fn unwind(&self) -> bool {
+        self.unwind
+    }
+
+    pub(crate) fn analyzed_mut(&mut self) -> &mut Vec<DefId> {
+        &mut self.analyzed
+    }
+
+    // the analyzed functions and their findings (empty if the function is safe) after `start` or `start_body`
+    pub fn results(&self) -> impl Iterator<Item = (DefId, &[LeakReport])> + '_ {
+        self.results.iter().map(|(did, reports)| (*did, reports.as_slice()))
+    }
+
+    // group the final findings by the functions, a function missing in the reports has no finding
+    fn collect_results(&mut self) {
+        let mut results: Vec<(DefId, Vec<LeakReport>)> = self.analyzed
+            .iter()
+            .map(|did| (*did, Vec::new()))
+            .collect();
+        for report in self.rcx().reports().iter() {
+            match results.iter_mut().find(|(did, _)| *did == report.did()) {
+                Some((_, reports)) => reports.push(report.clone()),
+                None => results.push((report.did(), vec![report.clone()])),
+            }
+        }
+        self.results = results;
+    }
+
     pub fn fn_set(&self) -> &Unique {
         &self.fn_set
     }
@@ -103,16 +611,102 @@ impl<'tcx, 'a> FlowAnalysis<'tcx, 'a> {
 
     pub fn start(&mut self) {
         // this phase determines the final order of all basic blocks for us to visit
-        // Note: we will not visit the clean-up blocks (unwinding)
-        self.order();
+        // Note: we will not visit the clean-up blocks (unwinding) unless the unwind mode is enabled
+        {
+            let _span = tracing::info_span!("order").entered();
+            self.order();
+        }
+        // dump the graph of each function for debugging if the env var RLC_DOT is given
+        if let Some(dir) = self.rcx().config().dot_path().cloned() {
+            self.dump_dot(&dir);
+        }
+        // the dry run stops before the intro phase, i.e., it checks the construction of the graphs and the layouts
+        if self.rcx().config().dry_run() {
+            self.print_dry_run();
+            return;
+        }
         // this phase will generate the intro procedural visitor for us to visit the block
         // note that the inter procedural part is inside in this function but cod in module inter_visitor
-        self.intro_run();
+        let mut sw = Stopwatch::start_new();
+        {
+            let _span = tracing::info_span!("intro").entered();
+            self.intro_run();
+        }
+        self.rcx_mut().metrics_mut().add_time_intro(sw.elapsed_ms());
+        sw.stop();
+        // emit the findings in source order regardless of the order of analysis
+        self.sort_reports();
+        self.collect_results();
+        if self.rcx().config().stats() {
+            rlc_info!("Statistics: {}", self.rcx().metrics());
+            self.print_approximations();
+        }
+        // write the findings as a plain JSON array if the env var RLC_JSON is given
+        if let Some(path) = self.rcx().config().json_path() {
+            emit_json(self.tcx(), self.rcx().reports(), path);
+        }
 
         // rlc_info!("@@@@@@@@@@@@@Build Analysis:{:?}", self.rcx().get_time_build());
         // rlc_info!("@@@@@@@@@@@@@Solve Analysis:{:?}", self.rcx().get_time_solve());
     }
 
+    // analyze the single function rather than the whole crate
+    pub fn start_body(&mut self, did: DefId) {
+        let tcx = self.tcx();
+        if !did.is_local() || !is_analyzable_body(tcx, did) {
+            self.rcx_mut().metrics_mut().add_skipped();
+            return;
+        }
+        self.order_body(did);
+        self.intro_run_body(did, &mut Unique::default());
+        self.sort_reports();
+        self.collect_results();
+    }
+
+    fn print_dry_run(&self) {
+        let graphs = self.mir_graph();
+        let blocks: usize = graphs.values().map(Graph::num_blocks).sum();
+        rlc_info!(
+            "Dry Run: Graphs: {}, Blocks: {}, Adts: {}",
+            graphs.len(),
+            blocks,
+            self.rcx().adt_owner().len()
+        );
+        rlc_info!("Statistics: {}", self.rcx().metrics());
+    }
+
+    // the histogram of the approximated constructs and the functions where they are encountered
+    fn print_approximations(&self) {
+        let approximations = self.rcx().approximations();
+        if approximations.is_empty() {
+            return;
+        }
+        rlc_info!("Approximations: {}", approximations);
+        for kind in approximations.kinds() {
+            let mut fns: Vec<String> = approximations.sites(kind)
+                .iter()
+                .map(|(did, _)| self.tcx().def_path_str(*did))
+                .collect();
+            fns.sort();
+            fns.dedup();
+            rlc_info!("    {} in {}", kind, fns.join(", "));
+        }
+    }
+
+    pub fn sort_reports(&mut self) {
+        self.rcx_mut().reports_mut().sort_by_key(|report| (report.span().lo(), report.span().hi()));
+        // the repeated findings of the same allocation (e.g., in a loop) are collapsed into the first one with a count
+        let mut reports = std::mem::take(self.rcx_mut().reports_mut());
+        // the leaks of the heap items handed in by the parameters are left to the caller unless "-PARAM-OWNED"
+        if !self.rcx().config().param_owned() {
+            reports.retain(|report| !report.is_param_owned());
+        }
+        if let Some(min) = self.rcx().config().min_confidence() {
+            reports.retain(|report| report.confidence() <= min);
+        }
+        *self.rcx_mut().reports_mut() = dedup_reports(reports);
+    }
+
 }
 
 impl<'tcx, 'o, 'a> RcxMut<'tcx, 'o, 'a> for FlowAnalysis<'tcx, 'a> {
@@ -136,6 +730,7 @@ impl<'tcx, 'o, 'a> RcxMut<'tcx, 'o, 'a> for FlowAnalysis<'tcx, 'a> {
 pub struct NodeOrder<'tcx> {
     body: &'tcx Body<'tcx>,
     graph: Graph,
+    unwind: bool,
 }
 
 impl<'tcx> NodeOrder<'tcx> {
@@ -144,9 +739,15 @@ impl<'tcx> NodeOrder<'tcx> {
         Self {
             body,
             graph: Graph::new(len),
+            unwind: false,
         }
     }
 
+    pub fn with_unwind(mut self, unwind: bool) -> Self {
+        self.unwind = unwind;
+        self
+    }
+
     #[inline(always)]
     pub fn body(&self) -> &'tcx Body<'tcx> {
         self.body
@@ -208,6 +809,60 @@ struct IntroFlowAnalysis<'tcx, 'ctx, 'a> {
     ref_fn_unique: &'a mut Unique,
     elasped: Elapsed,
     taint_flag: bool,
+    // the span of the first call converting the owned instance into a pointer (the taint source)
+    taint_span: Option<Span>,
+    // the constraints that all vars are freed at each exit (return or resume) of the body
+    exits: Vec<ExitConstraints<'ctx>>,
+    reports: Vec<LeakReport>,
+    // the times each basic block has been visited during the fixpoint iteration
+    rounds: Vec<usize>,
+    // the index of each basic block in topo order, used to order the program points
+    topo_pos: Vec<usize>,
+    // the program point and span where each local is last written with a new value
+    write_spans: Vec<Option<TaintOrigin>>,
+    // the locals whose value flows into the return value or an out-pointer parameter
+    escaping: Vec<bool>,
+    // the span where the storage of each local is dead, the local still owning there is never dropped later
+    dead_spans: Vec<Option<Span>>,
+    // the param (index from 0) that each local is copied from, e.g., `_3 = copy _1` gives 0 for `_3`
+    param_alias: Vec<Option<usize>>,
+    // the params whose pointee ownership is taken back in this function, it goes to the summary
+    consumed_params: Vec<bool>,
+    // the ownership carried by the return value, it goes to the summary
+    ret_taint: Taint<'tcx>,
+    // the calls through trait objects whose callee is unknown, the findings of this function are approximated by them
+    virtual_calls: Vec<Span>,
+    // the constructs approximated in this function for the statistics, each site is recorded once
+    approximations: Vec<(Approximation, Span)>,
+    // the constructs approximated on the paths reaching the out state of each block, the findings there have a
+    // confidence lowered by them
+    path_approximations: Vec<Vec<(Approximation, Span)>>,
+    // the leaks depending on the type params of this generic function, they are resolved by the callers
+    generic_leaks: Vec<LeakReport>,
+    // the generic callees instantiated with concrete type args in this function
+    instantiated: Vec<DefId>,
+    // the counters of visited blocks and z3 queries for the statistics, and the time (ms) spent in z3 checks
+    blocks: usize,
+    queries: usize,
+    time_z3: i64,
+    // the solver frames pushed by the kept block visits, they are popped once the body is solved
+    frames: usize,
+    // the taint of the args at the call site if the function is inlined into the caller, the inlined analysis
+    // only computes the summary in this context and does not solve for the leaks
+    param_taints: Option<Vec<Taint<'tcx>>>,
+    // the fields (variant and index) of the generator state that hold an owned raw pointer across a suspension,
+    // each of them is reported once
+    suspended: Vec<(usize, usize)>,
+    // the identity of the allocation introduced at each location (block, statement) and the span of the location
+    alloc_ids: HashMap<(usize, usize), AllocId>,
+    alloc_sites: Vec<Span>,
+    // the function is the body of Drop::drop, and the raw pointer fields of the receiver read in it with the
+    // location (block, statement) of the first read of each field
+    drop_body: bool,
+    drop_fields: HashMap<usize, (usize, usize)>,
+    // the box whose heap item each reference (or raw pointer) points into, traced through the copies, casts and
+    // transmutes, e.g., `_3 = &(*_1)` with `_1: Box<T>` and then `_0 = transmute::<&T, &'static T>(move _3)`
+    ref_provenance: Vec<Option<Local>>,
 }
 
 impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
@@ -221,6 +876,10 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         let v_len = body.local_decls.len();
         let b_len = body.basic_blocks().len();
         let graph = rcx.mir_graph().get(&did).unwrap();
+        let mut topo_pos = vec![usize::MAX ; b_len];
+        for (pos, bidx) in graph.topo_order().enumerate() {
+            topo_pos[bidx] = pos;
+        }
 
         Self {
             rcx,
@@ -232,9 +891,45 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
             ref_fn_unique: unique,
             elasped: (0, 0),
             taint_flag: false,
+            taint_span: None,
+            exits: Vec::new(),
+            reports: Vec::new(),
+            rounds: vec![0 ; b_len],
+            topo_pos,
+            write_spans: vec![None ; v_len],
+            escaping: escaping_locals(body),
+            dead_spans: vec![None ; v_len],
+            param_alias: param_aliases(body),
+            consumed_params: vec![false ; body.arg_count],
+            ret_taint: Taint::default(),
+            virtual_calls: Vec::new(),
+            approximations: Vec::new(),
+            path_approximations: vec![Vec::new() ; b_len],
+            generic_leaks: Vec::new(),
+            instantiated: Vec::new(),
+            blocks: 0,
+            queries: 0,
+            time_z3: 0,
+            frames: 0,
+            param_taints: None,
+            suspended: Vec::new(),
+            alloc_ids: HashMap::default(),
+            alloc_sites: Vec::new(),
+            drop_body: is_drop_method(rcx.tcx(), did),
+            drop_fields: HashMap::default(),
+            ref_provenance: vec![None ; v_len],
         }
     }
 
+    pub fn with_param_taints(mut self, param_taints: Vec<Taint<'tcx>>) -> Self {
+        self.param_taints = Some(param_taints);
+        self
+    }
+
+    pub fn is_inlined(&self) -> bool {
+        self.param_taints.is_some()
+    }
+
     pub fn did(&self) -> DefId {
         self.did
     }
@@ -275,6 +970,44 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         self.elasped.1
     }
 
+    pub fn reports(&self) -> &Vec<LeakReport> {
+        &self.reports
+    }
+
+    pub fn blocks(&self) -> usize {
+        self.blocks
+    }
+
+    pub fn queries(&self) -> usize {
+        self.queries
+    }
+
+    pub fn time_z3(&self) -> i64 {
+        self.time_z3
+    }
+
+    pub fn take_reports(&mut self) -> Vec<LeakReport> {
+        std::mem::take(&mut self.reports)
+    }
+
+    pub fn add_approximation(&mut self, kind: Approximation, span: Span) {
+        if !self.approximations.contains(&(kind, span)) {
+            self.approximations.push((kind, span));
+        }
+    }
+
+    // the construct is approximated in the block, the paths going through the block carry it
+    pub fn add_path_approximation(&mut self, bidx: usize, kind: Approximation, span: Span) {
+        self.add_approximation(kind, span);
+        if !self.path_approximations[bidx].contains(&(kind, span)) {
+            self.path_approximations[bidx].push((kind, span));
+        }
+    }
+
+    pub fn path_approximations(&self, bidx: usize) -> &Vec<(Approximation, Span)> {
+        &self.path_approximations[bidx]
+    }
+
 }
 
 impl<'tcx, 'ctx, 'o, 'a> Rcx<'tcx, 'o, 'a> for IntroFlowAnalysis<'tcx, 'ctx, 'a> {
@@ -431,6 +1164,44 @@ impl<'tcx, 'ctx, 'icx> IntroFlowContext<'tcx, 'ctx> {
 
     }
 
+    // merge the out state of all pre nodes into the in state of the node `to`
+    // the taint is the union of all pre nodes, the var is the least upper bound (declared < init < unsupported)
+    // and the len, ty and layout must be consistent among all initialized pre nodes, or the var is unsupported
+    // return the index of vars that are initialized with different bit vectors which need a phi constraint
+    // the out states of the pre nodes are merged by reference, only the merged slice is allocated, thus a join
+    // of many arms (e.g., a big match) costs linear in the number of pre nodes
+    pub fn merge_from_predecessors(&mut self, to: usize, preds: &[usize]) -> Vec<usize> {
+        if preds.len() == 1 {
+            self.derive_from_pre_node(preds[0], to);
+            return Vec::new();
+        }
+
+        let v_len = self.var().get_g()[to].get_i().len();
+        let mut merged = IcxSliceFroBlock::new_for_block_0(v_len);
+        let mut diverged = vec![false ; v_len];
+        for pidx in preds {
+            let (phi, _) = merged.merge_ref(IcxSliceRef::out(self, *pidx));
+            for var_idx in phi {
+                diverged[var_idx] = true;
+            }
+        }
+
+        // the var collapsed by a later pre node needs no phi
+        let phi = (0..v_len)
+            .filter(|var_idx| diverged[*var_idx] && !merged.var()[*var_idx].is_unsupported())
+            .collect();
+        self.derive_in_from_icx_slice(merged, to);
+        phi
+    }
+
+    pub fn derive_in_from_icx_slice(&mut self, from: IcxSliceFroBlock<'tcx, 'ctx>, to: usize) {
+        *self.taint_mut().get_g_mut()[to].get_i_mut() = from.taint;
+        *self.var_mut().get_g_mut()[to].get_i_mut() = from.var;
+        *self.len_mut().get_g_mut()[to].get_i_mut() = from.len;
+        *self.ty_mut().get_g_mut()[to].get_i_mut() = from.ty;
+        *self.layout_mut().get_g_mut()[to].get_i_mut() = from.layout;
+    }
+
     pub fn derive_from_icx_slice(&mut self, from: IcxSliceFroBlock<'tcx, 'ctx>, to: usize) {
         *self.
             taint_mut()
@@ -523,9 +1294,22 @@ impl<T> IOPairForGraph<T>
     pub fn get_g_mut(&mut self) -> &mut Vec<InOutPair<T>> {
         &mut self.pair_graph
     }
+
+    pub fn num_blocks(&self) -> usize {
+        self.pair_graph.len()
+    }
+
+    // the checked accessors, none if the block is out of range
+    pub fn get(&self, bb: usize) -> Option<&InOutPair<T>> {
+        self.pair_graph.get(bb)
+    }
+
+    pub fn get_mut(&mut self, bb: usize) -> Option<&mut InOutPair<T>> {
+        self.pair_graph.get_mut(bb)
+    }
 }
 
-#[derive(Clone, Default)]
+#[derive(Clone, Default, Eq, PartialEq, Hash)]
 pub struct IcxSliceFroBlock<'tcx, 'ctx> {
     taint: Vec<Taint<'tcx>>,
     var: Vec<IntroVar<'ctx>>,
@@ -558,6 +1342,16 @@ impl<'tcx, 'ctx> IcxSliceFroBlock<'tcx, 'ctx> {
         }
     }
 
+    pub fn view(&self) -> IcxSliceRef<'_, 'tcx, 'ctx> {
+        IcxSliceRef {
+            taint: &self.taint,
+            var: &self.var,
+            len: &self.len,
+            ty: &self.ty,
+            layout: &self.layout,
+        }
+    }
+
     pub fn new_for_block_0(len: usize) -> Self {
         Self {
             taint: vec![ Taint::default() ; len ],
@@ -608,6 +1402,125 @@ impl<'tcx, 'ctx> IcxSliceFroBlock<'tcx, 'ctx> {
         &mut self.layout
     }
 
+    // check whether the slice reaches the same state as another one
+    // used by the fixpoint iteration to detect the convergence of a basic block
+    pub fn is_same_state(&self, another: &IcxSliceFroBlock<'tcx, 'ctx>) -> bool {
+        self.taint() == another.taint()
+            && self.len() == another.len()
+            && self.ty() == another.ty()
+            && self.layout() == another.layout()
+            && self.var().len() == another.var().len()
+            && self.var().iter().zip(another.var().iter()).all(|(a, b)| a.is_same_state(b))
+    }
+
+    // The widening of the in state of a block that is re-entered too many times in the fixpoint iteration.
+    // The taint is the union of both visits thus it only grows (and it is bounded by the types and spans).
+    // The var whose state changes since the last visit (or whose layout is longer than `max_len`) is
+    // collapsed into the top element (unsupported).
+    // Note: the widened var is no longer tracked by z3, thus the leak of it in the loop can not be found.
+    // Return the index of widened vars.
+    pub fn widen(&mut self, pre: &IcxSliceFroBlock<'tcx, 'ctx>, max_len: usize) -> Vec<usize> {
+        let mut widened = Vec::new();
+        for u in 0..self.var().len() {
+            let taint = pre.taint()[u].clone();
+            self.taint_mut()[u].merge(&taint);
+
+            if self.var()[u].is_unsupported() {
+                continue;
+            }
+            let changed = !self.var()[u].is_same_state(&pre.var()[u])
+                || self.len()[u] != pre.len()[u]
+                || self.ty()[u] != pre.ty()[u]
+                || self.layout()[u] != pre.layout()[u];
+            if !changed && self.len()[u] <= max_len {
+                continue;
+            }
+
+            self.var_mut()[u] = IntroVar::Unsupported;
+            self.len_mut()[u] = 0;
+            self.ty_mut()[u] = TyWithIndex::default();
+            self.layout_mut()[u] = OwnershipLayout::default();
+            widened.push(u);
+        }
+        widened
+    }
+
+    // Merge the slice of another pre node into this one at a join point, the slice to start with is the one
+    // for block 0 (all declared). The taint is the union of both, and the var takes the lub of
+    // Declared < Init < Unsupported together with its len, ty and layout. The len, ty and layout are merged by
+    // equality: the var whose len, ty or layout disagrees between both can not be merged bit by bit, so it is
+    // collapsed into unsupported with an unknown (0) len.
+    // Return the vars that are init in both with different bit vectors (the join needs a phi of them), and the
+    // vars collapsed by the disagreement (the join can be flagged by them).
+    pub fn merge(&mut self, another: &IcxSliceFroBlock<'tcx, 'ctx>) -> (Vec<usize>, Vec<usize>) {
+        self.merge_ref(another.view())
+    }
+
+    // the same as `merge`, but the other slice is borrowed from the icx instead of being cloned out of it
+    pub fn merge_ref(&mut self, another: IcxSliceRef<'_, 'tcx, 'ctx>) -> (Vec<usize>, Vec<usize>) {
+        let mut diverged = Vec::new();
+        let mut conflicted = Vec::new();
+        for u in 0..self.var().len() {
+            self.taint_mut()[u].merge(&another.taint[u]);
+
+            match (&self.var()[u], &another.var[u]) {
+                (IntroVar::Unsupported, _)
+                | (_, IntroVar::Declared) => continue,
+                (_, IntroVar::Unsupported) => (),
+                (IntroVar::Declared, IntroVar::Init(_)) => {
+                    self.var_mut()[u] = another.var[u].clone();
+                    self.len_mut()[u] = another.len[u];
+                    self.ty_mut()[u] = another.ty[u].clone();
+                    self.layout_mut()[u] = another.layout[u].clone();
+                    continue;
+                },
+                (IntroVar::Init(bv), IntroVar::Init(another_bv)) => {
+                    if self.len()[u] == another.len[u]
+                        && self.ty()[u] == another.ty[u]
+                        && self.layout()[u] == another.layout[u]
+                    {
+                        if bv != another_bv {
+                            diverged.push(u);
+                        }
+                        continue;
+                    }
+                    conflicted.push(u);
+                },
+            }
+
+            self.var_mut()[u] = IntroVar::Unsupported;
+            self.len_mut()[u] = 0;
+            self.ty_mut()[u] = TyWithIndex::default();
+            self.layout_mut()[u] = OwnershipLayout::default();
+        }
+        (diverged, conflicted)
+    }
+
+    // The diff-friendly text of the slice, one local per line in index order. The bit vectors (whose names
+    // depend on the rounds) are given by their states only, and the entries of the taint are sorted.
+    pub fn dump(&self) -> String {
+        let mut text = String::new();
+        for u in 0..self.var().len() {
+            let var = match &self.var()[u] {
+                IntroVar::Declared => "declared",
+                IntroVar::Init(_) => "init",
+                IntroVar::Unsupported => "unsupported",
+            };
+            let mut taint: Vec<String> = self.taint()[u].set().iter().map(|elem| format!("{:?}", elem)).collect();
+            taint.sort();
+            text.push_str(&format!(
+                "_{} var={} len={} layout={:?} ty={:?} taint=[{}]\n",
+                u,
+                var,
+                self.len()[u],
+                self.layout()[u],
+                self.ty()[u],
+                taint.join(", "),
+            ));
+        }
+        text
+    }
+
     pub fn taint_merge(&mut self, another: &IcxSliceFroBlock<'tcx, 'ctx>, u: usize) {
         if another.taint()[u].is_untainted() {
             return;
@@ -616,9 +1529,30 @@ impl<'tcx, 'ctx> IcxSliceFroBlock<'tcx, 'ctx> {
         if self.taint()[u].is_untainted() {
             self.taint_mut()[u] = another.taint()[u].clone();
         } else {
-            for elem in another.taint()[u].set().clone() {
-                self.taint_mut()[u].insert(elem);
-            }
+            let taint = another.taint()[u].clone();
+            self.taint_mut()[u].merge(&taint);
+        }
+    }
+}
+
+// The borrowed slice of a block, e.g., the out state of a pre node read in place at a join point.
+#[derive(Copy, Clone)]
+pub struct IcxSliceRef<'a, 'tcx, 'ctx> {
+    taint: &'a [Taint<'tcx>],
+    var: &'a [IntroVar<'ctx>],
+    len: &'a [usize],
+    ty: &'a [TyWithIndex<'tcx>],
+    layout: &'a [OwnershipLayout],
+}
+
+impl<'a, 'tcx, 'ctx> IcxSliceRef<'a, 'tcx, 'ctx> {
+    pub fn out(icx: &'a IntroFlowContext<'tcx, 'ctx>, idx: usize) -> Self {
+        Self {
+            taint: icx.taint().get_g()[idx].get_o(),
+            var: icx.var().get_g()[idx].get_o(),
+            len: icx.len().get_g()[idx].get_o(),
+            ty: icx.ty().get_g()[idx].get_o(),
+            layout: icx.layout().get_g()[idx].get_o(),
         }
     }
 }
@@ -637,28 +1571,207 @@ impl<'tcx, 'ctx> Debug for IcxSliceFroBlock<'tcx, 'ctx> {
     }
 }
 
-#[derive(Debug, Copy, Clone, Hash)]
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
 pub enum Z3GoalDisplay {
     Verbose,
     Disabled,
 }
 
-pub fn is_z3_goal_verbose() -> bool {
-    match env::var_os("Z3_GOAL") {
-        Some(_)  => true,
-        _ => false,
+impl Z3GoalDisplay {
+    pub fn is_verbose(&self) -> bool {
+        match *self {
+            Z3GoalDisplay::Verbose => true,
+            Z3GoalDisplay::Disabled => false,
+        }
     }
 }
 
-#[derive(Debug, Copy, Clone, Hash)]
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
 pub enum IcxSliceDisplay {
     Verbose,
     Disabled,
 }
 
-pub fn is_icx_slice_verbose() -> bool {
-    match env::var_os("ICX_SLICE") {
-        Some(_)  => true,
-        _ => false,
+impl IcxSliceDisplay {
+    pub fn is_verbose(&self) -> bool {
+        match *self {
+            IcxSliceDisplay::Verbose => true,
+            IcxSliceDisplay::Disabled => false,
+        }
+    }
+}
+
+// The assumption on the callee of a virtual call (through `dyn Trait`) that can not be resolved statically.
+// In both of them, the owned args passed by value are consumed by the callee and the return value is owned
+// by the caller. The optimistic one also assumes that the callee takes over the ownership of the raw pointers
+// passed to it, while the pessimistic one keeps the ownership in the caller.
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum VirtualCallMode {
+    Pessimistic,
+    Optimistic,
+}
+
+impl VirtualCallMode {
+    pub fn is_optimistic(&self) -> bool {
+        match *self {
+            VirtualCallMode::Optimistic => true,
+            VirtualCallMode::Pessimistic => false,
+        }
+    }
+}
+
+// The assumption on the ownership crossing the FFI boundary. The FFI-friendly one assumes that the raw pointers
+// returned by the exported functions (`#[no_mangle]` or `extern "C"`) are freed by the C side (escaping as usual),
+// and so are the tainted raw pointers passed to the foreign functions. The strict one reports the ownership handed
+// to C by the returned raw pointer and keeps the ownership of the pointers passed to C in the caller.
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum FfiMode {
+    Strict,
+    Friendly,
+}
+
+impl FfiMode {
+    pub fn is_friendly(&self) -> bool {
+        match *self {
+            FfiMode::Friendly => true,
+            FfiMode::Strict => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::flow_analysis::intro_visitor::timed_solver;
+    use z3::ast::Ast;
+
+    fn graph_with_edges(len: usize, edges: &[(usize, usize, EdgeKind)]) -> Graph {
+        let mut graph = Graph::new(len);
+        for (from, to, kind) in edges.iter() {
+            graph.get_edges_mut()[*from].push(*to);
+            graph.get_edge_kinds_mut()[*from].push(*kind);
+            graph.pre[*to].push(*from);
+        }
+        graph
+    }
+
+    #[test]
+    fn merge_duplicate_edges() {
+        let mut graph = graph_with_edges(3, &[
+            (0, 1, EdgeKind::SwitchArm),
+            (0, 2, EdgeKind::SwitchArm),
+            (0, 1, EdgeKind::SwitchArm),
+            (1, 2, EdgeKind::Goto),
+        ]);
+        graph.merge_parallel_edges();
+        assert_eq!(graph.get_edges(), &vec![vec![1, 2], vec![2], vec![]]);
+        assert_eq!(graph.get_edge_kinds()[0], vec![EdgeKind::SwitchArm, EdgeKind::SwitchArm]);
+        assert_eq!(graph.get_pre(), &vec![vec![], vec![0], vec![0, 1]]);
+        assert!(!(0..3).any(|block| graph.has_self_loop(block)));
+    }
+
+    #[test]
+    fn merge_self_edges() {
+        let mut graph = graph_with_edges(2, &[
+            (0, 1, EdgeKind::Goto),
+            (1, 1, EdgeKind::SwitchArm),
+            (1, 1, EdgeKind::SwitchArm),
+        ]);
+        graph.merge_parallel_edges();
+        assert_eq!(graph.get_edges(), &vec![vec![1], vec![1]]);
+        assert_eq!(graph.get_edge_kinds()[1], vec![EdgeKind::SwitchArm]);
+        assert_eq!(graph.get_pre(), &vec![vec![], vec![0, 1]]);
+        assert!(!graph.has_self_loop(0));
+        assert!(graph.has_self_loop(1));
+    }
+
+    #[test]
+    fn merge_normal_and_cleanup_edges() {
+        // the real successor of the drop and its unwind edge go to the same block, the real one is kept
+        let mut graph = graph_with_edges(3, &[
+            (0, 1, EdgeKind::DropSuccess),
+            (0, 1, EdgeKind::Cleanup),
+            (1, 2, EdgeKind::CallReturn),
+            (1, 2, EdgeKind::Cleanup),
+        ]);
+        graph.merge_parallel_edges();
+        assert_eq!(graph.get_edges(), &vec![vec![1], vec![2], vec![]]);
+        assert_eq!(graph.get_edge_kinds(), &vec![vec![EdgeKind::DropSuccess], vec![EdgeKind::CallReturn], vec![]]);
+        assert_eq!(graph.edge_kind(0, 1), Some(EdgeKind::DropSuccess));
+        assert_eq!(graph.get_pre(), &vec![vec![], vec![0], vec![1]]);
+    }
+
+    #[test]
+    fn dominators_of_diamond() {
+        let graph = Graph::from_text("0 -> 1,2; 1 -> 3; 2 -> 3").unwrap();
+        let dom = graph.dominators();
+        let idom: Vec<Option<usize>> = (0..4).map(|bidx| dom.immediate_dominator(bidx)).collect();
+        assert_eq!(idom, vec![None, Some(0), Some(0), Some(0)]);
+        assert!(dom.dominates(0, 3));
+        assert!(dom.dominates(3, 3));
+        assert!(!dom.dominates(1, 3));
+        assert!(!dom.dominates(2, 3));
+        assert!(!dom.dominates(1, 2));
+    }
+
+    #[test]
+    fn dominators_of_loop() {
+        // the loop header 1 with the latch 2 leaving to 3, and the block 4 unreachable from bb0
+        let graph = Graph::from_text("0 -> 1; 1 -> 2; 2 -> 1,3; 4 -> 3").unwrap();
+        let dom = graph.dominators();
+        let idom: Vec<Option<usize>> = (0..5).map(|bidx| dom.immediate_dominator(bidx)).collect();
+        assert_eq!(idom, vec![None, Some(0), Some(1), Some(2), None]);
+        assert!(dom.dominates(1, 2));
+        assert!(dom.dominates(1, 3));
+        assert!(!dom.dominates(2, 1));
+        assert!(!dom.is_reachable(4));
+        assert!(!dom.dominates(4, 3));
+    }
+
+    #[test]
+    fn merge_64_predecessors() {
+        // the join of the 64-arm match in PoC/BigMatch: the local 0 has a different bit vector in each arm, the
+        // local 1 has the same one in all arms and the local 2 is only initialized in the last arm
+        let ctx = z3::Context::new(&z3::Config::new());
+        let shared = ast::BV::from_u64(&ctx, 64, 8);
+        let preds: Vec<IcxSliceFroBlock> = (0..64).map(|arm| {
+            let mut slice = IcxSliceFroBlock::new_for_block_0(3);
+            slice.var[0] = IntroVar::Init(ast::BV::from_u64(&ctx, arm, 8));
+            slice.var[1] = IntroVar::Init(shared.clone());
+            if arm == 63 {
+                slice.var[2] = IntroVar::Init(shared.clone());
+            }
+            slice
+        }).collect();
+
+        let mut merged = IcxSliceFroBlock::new_for_block_0(3);
+        let mut phi = vec![0; 3];
+        for pred in preds.iter() {
+            let (diverged, conflicted) = merged.merge_ref(pred.view());
+            assert!(conflicted.is_empty());
+            for var_idx in diverged {
+                phi[var_idx] += 1;
+            }
+        }
+        // one merge per pre node, and only the local 0 diverges from the first arm in each of the other 63
+        assert_eq!(phi, vec![63, 0, 0]);
+        assert_eq!(merged.var()[0], IntroVar::Init(ast::BV::from_u64(&ctx, 0, 8)));
+        assert_eq!(merged.var()[1], IntroVar::Init(shared.clone()));
+        assert_eq!(merged.var()[2], IntroVar::Init(shared));
+    }
+
+    #[test]
+    fn z3_timeout_gives_unknown() {
+        // factoring the product of two 32-bit primes is far beyond 1 ms, the check gives up instead of going on
+        let ctx = z3::Context::new(&z3::Config::new());
+        let solver = timed_solver(&ctx, 1);
+        let x = ast::BV::new_const(&ctx, "x", 64);
+        let y = ast::BV::new_const(&ctx, "y", 64);
+        let one = ast::BV::from_u64(&ctx, 1, 64);
+        let n = ast::BV::from_u64(&ctx, 4294967291 * 4294967279, 128);
+        solver.assert(&x.zero_ext(64).bvmul(&y.zero_ext(64))._eq(&n));
+        solver.assert(&x.bvugt(&one));
+        solver.assert(&y.bvugt(&one));
+        assert_eq!(solver.check(), z3::SatResult::Unknown);
     }
-}
\ No newline at end of file
+}
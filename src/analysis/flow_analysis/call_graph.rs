@@ -0,0 +1,171 @@
+use rustc_middle::ty::{self, Instance, InstanceDef, TyCtxt};
+use rustc_middle::mir::{Operand, TerminatorKind};
+use rustc_span::def_id::DefId;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_target::spec::abi::Abi;
+
+use crate::analysis::flow_analysis::Graph;
+use crate::analysis::flow_analysis::inter_visitor::closure_def_id;
+use crate::analysis::type_analysis::type_visitor::mir_body;
+
+// The call graph of the functions in current crate, the edge goes from the caller to the callee.
+// The callees are resolved statically (including the trait methods with a known impl), and the calls
+// to the functions outside the graph (e.g., in std) are not recorded. The closures passed to a call are viewed as
+// its callees as well, e.g., the closure run by Option::map or Result::and_then, since the callee invokes them.
+#[derive(Debug, Clone, Default)]
+pub struct CallGraph {
+    nodes: Vec<DefId>,
+    index: FxHashMap<DefId, usize>,
+    graph: Graph,
+}
+
+impl CallGraph {
+    pub fn new(tcx: TyCtxt<'_>, dids: &[DefId]) -> Self {
+        let mut nodes = dids.to_vec();
+        nodes.sort_by_cached_key(|did| tcx.def_path_str(*did));
+        let index: FxHashMap<DefId, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, did)| (*did, idx))
+            .collect();
+
+        let mut graph = Graph::new(nodes.len());
+        for (caller, did) in nodes.iter().enumerate() {
+            let body = mir_body(tcx, *did);
+            let param_env = tcx.param_env(*did);
+            for data in body.basic_blocks().iter() {
+                let (func, args) = match &data.terminator().kind {
+                    TerminatorKind::Call { func: Operand::Constant(constant), args, .. } => (constant, args),
+                    _ => continue,
+                };
+                let (id, substs) = match constant_fn(func.literal.ty()) {
+                    Some(fn_def) => fn_def,
+                    None => continue,
+                };
+                // resolve the trait method to its impl if possible, otherwise keep the declaration
+                let callee = match Instance::resolve(tcx, param_env, id, substs) {
+                    Ok(Some(instance)) => instance.def_id(),
+                    _ => id,
+                };
+                let closures = args.iter().filter_map(|arg| closure_def_id(arg.ty(body, tcx)));
+                for callee in std::iter::once(callee).chain(closures) {
+                    if let Some(callee) = index.get(&callee) {
+                        if !graph.get_edges()[caller].contains(callee) {
+                            graph.get_edges_mut()[caller].push(*callee);
+                            graph.get_pre_mut()[*callee].push(caller);
+                        }
+                    }
+                }
+            }
+        }
+
+        Self {
+            nodes,
+            index,
+            graph,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn contains(&self, did: &DefId) -> bool {
+        self.index.contains_key(did)
+    }
+
+    pub fn callees(&self, did: &DefId) -> Vec<DefId> {
+        match self.index.get(did) {
+            Some(idx) => self.graph.successors(*idx).map(|callee| self.nodes[callee]).collect(),
+            None => Vec::default(),
+        }
+    }
+
+    pub fn callers(&self, did: &DefId) -> Vec<DefId> {
+        match self.index.get(did) {
+            Some(idx) => self.graph.predecessors(*idx).map(|caller| self.nodes[caller]).collect(),
+            None => Vec::default(),
+        }
+    }
+
+    // the function itself and all the functions called by it directly or transitively
+    pub fn reachable(&self, did: &DefId) -> Vec<DefId> {
+        let mut reached = Vec::new();
+        let mut worklist = vec![*did];
+        while let Some(did) = worklist.pop() {
+            if !self.contains(&did) || reached.contains(&did) {
+                continue;
+            }
+            reached.push(did);
+            worklist.extend(self.callees(&did));
+        }
+        reached
+    }
+
+    // the sccs of the call graph in reverse topo order of the condensed dag, i.e., the callees come before
+    // their callers, and a scc of mutually recursive functions is returned as one group
+    pub fn sccs(&self) -> Vec<Vec<DefId>> {
+        self.graph
+            .find_sccs()
+            .into_iter()
+            .map(|scc| scc.into_iter().map(|idx| self.nodes[idx]).collect())
+            .collect()
+    }
+
+    // the group needs a fixpoint iteration if it contains a cycle (including a self recursive function)
+    pub fn is_recursive(&self, scc: &[DefId]) -> bool {
+        match scc {
+            [did] => self.callees(did).contains(did),
+            _ => scc.len() > 1,
+        }
+    }
+}
+
+fn constant_fn(ty: ty::Ty<'_>) -> Option<(DefId, ty::subst::SubstsRef<'_>)> {
+    match ty.kind() {
+        ty::FnDef(id, substs) => Some((*id, substs)),
+        _ => None,
+    }
+}
+
+// the call through a trait object (e.g., `b.f()` for `b: Box<dyn Trait>`) resolves to the virtual instance,
+// its callee is chosen by the vtable at runtime thus no body (or summary) can be used for it
+pub(crate) fn is_virtual_call<'tcx>(tcx: TyCtxt<'tcx>, caller: DefId, func: &Operand<'tcx>) -> bool {
+    let (id, substs) = match func {
+        Operand::Constant(constant) => match constant_fn(constant.literal.ty()) {
+            Some(fn_def) => fn_def,
+            None => return false,
+        },
+        _ => return false,
+    };
+    matches!(
+        Instance::resolve(tcx, tcx.param_env(caller), id, substs),
+        Ok(Some(Instance { def: InstanceDef::Virtual(..), .. }))
+    )
+}
+
+// the function exported to C by `#[no_mangle]` (or `#[export_name]`) or declared with a non-Rust ABI
+pub(crate) fn is_ffi_exported(tcx: TyCtxt<'_>, did: DefId) -> bool {
+    if tcx.is_closure(did) {
+        return false;
+    }
+    if tcx.codegen_fn_attrs(did).contains_extern_indicator() {
+        return true;
+    }
+    !matches!(tcx.fn_sig(did).abi(), Abi::Rust | Abi::RustCall | Abi::RustIntrinsic | Abi::PlatformIntrinsic)
+}
+
+// the call to a function declared in an extern block, its body is in C
+pub(crate) fn is_foreign_call<'tcx>(tcx: TyCtxt<'tcx>, func: &Operand<'tcx>) -> bool {
+    match func {
+        Operand::Constant(constant) => match constant_fn(constant.literal.ty()) {
+            Some((id, _)) => tcx.is_foreign_item(id),
+            None => false,
+        },
+        _ => false,
+    }
+}
@@ -1,26 +1,48 @@
 use crate::analysis::RcxMut;
 use crate::analysis::flow_analysis::{FlowAnalysis, InterFlowAnalysis};
-use crate::analysis::type_analysis::type_visitor::mir_body;
+use crate::analysis::type_analysis::type_visitor::{is_analyzable_body, mir_body};
 use crate::analysis::type_analysis::Unique;
 
+use rustc_middle::ty::{Ty, TyKind};
+use rustc_span::def_id::DefId;
+
 use stopwatch::Stopwatch;
 
+// resolve the def id of the closure (or generator) body from the type of an operand,
+// the body is analyzed separately and its env (_1) is the closure itself
+pub(crate) fn closure_def_id(ty: Ty) -> Option<DefId> {
+    match ty.kind() {
+        TyKind::Closure(did, ..) | TyKind::Generator(did, ..) => Some(*did),
+        _ => None,
+    }
+}
+
+// the types of captured upvars, they map to the fields of the env in order
+pub(crate) fn upvar_tys(ty: Ty) -> Vec<Ty> {
+    match ty.kind() {
+        TyKind::Closure(_, substs) => substs.as_closure().upvar_tys().collect(),
+        TyKind::Generator(_, substs, ..) => substs.as_generator().upvar_tys().collect(),
+        _ => Vec::new(),
+    }
+}
+
 impl<'tcx, 'a> FlowAnalysis<'tcx, 'a> {
     pub fn inter_run(&mut self) {
         let tcx = self.tcx();
         let mir_keys = tcx.mir_keys(());
 
         for each_mir in mir_keys {
-            let mut unique = Unique::new();
+            let mut unique = Unique::default();
             let sw = Stopwatch::start_new();
             let def_id = each_mir.to_def_id();
+            if !is_analyzable_body(tcx, def_id) { continue; }
             let body = mir_body(tcx, def_id);
 
             if body.basic_blocks.is_cfg_cyclic() { continue; }
 
             let mut cfg = z3::Config::new();
             cfg.set_model_generation(true);
-            cfg.set_timeout_msec(1000);
+            cfg.set_timeout_msec(self.rcx().config().z3_timeout());
 
             let ctx = z3::Context::new(&cfg);
             let goal = z3::Goal::new(&ctx, true, false, false);
@@ -1,19 +1,28 @@
 use rustc_span::def_id::DefId;
-use rustc_middle::ty::{self, Ty, TyKind, TypeFoldable, TypeVisitable};
+use rustc_middle::ty::{self, EarlyBinder, Ty, TyKind, TypeFoldable, TypeVisitable};
+use rustc_middle::ty::subst::{Subst, SubstsRef};
 use rustc_middle::mir::{Body, BasicBlock, BasicBlockData, Statement, StatementKind,
                         Terminator, Place, Rvalue, Local, Operand, ProjectionElem,
-                        CastKind, TerminatorKind};
+                        CastKind, LocalInfo, TerminatorKind, UnOp, VarDebugInfoContents};
 use rustc_target::abi::VariantIdx;
+use rustc_target::spec::abi::Abi;
+use rustc_span::{sym, Span};
 
-use crate::{rlc_error, rlc_info};
+use crate::rlc_error;
 use crate::analysis::{Rcx, RcxMut, IcxMut, IcxSliceMut};
-use crate::analysis::type_analysis::ownership::{OwnershipLayoutResult, RawTypeOwner};
+use crate::analysis::type_analysis::ownership::{ContainerKind, ContainerOwner, OwnershipLayoutResult, RawTypeOwner};
 use crate::analysis::type_analysis::type_visitor::{mir_body, TyWithIndex};
-use crate::analysis::type_analysis::{DefaultOwnership, OwnershipLayout, RustBV, Unique};
-use crate::analysis::flow_analysis::{IntroFlowAnalysis, FlowAnalysis, IcxSliceFroBlock,
-                                     is_z3_goal_verbose, is_icx_slice_verbose};
-use crate::analysis::flow_analysis::ownership::IntroVar;
+use crate::analysis::type_analysis::{self, AllocFnKind, CapacityFnKind, DefaultOwnership, OwnershipLayout, RustBV, TypeAnalysis, Unique};
+use crate::analysis::flow_analysis::{IntroFlowAnalysis, FlowAnalysis, IcxSliceFroBlock};
+use crate::analysis::flow_analysis::ownership::{AllocId, IntroVar, Taint, TaintSource};
+use crate::analysis::flow_analysis::inter_visitor::{closure_def_id, upvar_tys};
+use crate::analysis::flow_analysis::summary::FnSummary;
+use crate::analysis::flow_analysis::call_graph::{is_ffi_exported, is_foreign_call, is_virtual_call};
+use crate::analysis::flow_analysis::order::dump_name;
 use crate::components::display::Display;
+use crate::components::fs::{rlc_append_file, rlc_create_dir, rlc_create_file, rlc_write};
+use crate::components::metrics::Approximation;
+use crate::components::report::{LeakReport, ReportKind, WitnessStep};
 
 use colorful::{Color, Colorful};
 use z3::ast::{self, Ast};
@@ -21,9 +30,25 @@ use z3::ast::{self, Ast};
 use std::ops::Add;
 use stopwatch::Stopwatch;
 use std::borrow::Borrow;
+use std::collections::VecDeque;
+use std::path::Path;
 // Fixme: arg.0
 // Fixme: arg enum
 
+// the maximum times a basic block could be re-visited in the fixpoint iteration
+// it guards the analysis against a lattice that fails to converge in loops
+const INTRO_FIXPOINT_CAP: usize = 8;
+// the times a basic block is re-visited before its in state is widened, it is less than the cap above
+// so that the widened state is able to converge before the iteration is stopped
+const INTRO_WIDENING_ROUND: usize = 3;
+// the maximum len of the layout to track after widening, the constants of z3 are built from u64
+const INTRO_WIDENING_LEN: usize = 64;
+// the maximum rounds to analyze a group of mutually recursive functions until their summaries are stable
+const INTRO_SUMMARY_CAP: usize = 4;
+
+// the state of a single local in the slice: taint, var, len, ty and layout
+type LocalState<'tcx, 'ctx> = (Taint<'tcx>, IntroVar<'ctx>, usize, TyWithIndex<'tcx>, OwnershipLayout);
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub enum AsgnKind {
     Assign,
@@ -32,42 +57,249 @@ pub enum AsgnKind {
     Cast,
 }
 
+// the kind of the exit where we check all variables are freed
+// the resume exit is only reachable if the clean-up blocks (unwinding) are analyzed
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ExitKind {
+    Return,
+    Unwind,
+}
+
+impl std::fmt::Display for ExitKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExitKind::Return => write!(f, "normal return"),
+            ExitKind::Unwind => write!(f, "unwind"),
+        }
+    }
+}
+
+// the result of analyzing one function, it is merged into the global context once the job is done
+#[derive(Debug, Clone)]
+pub struct IntroResult<'tcx> {
+    did: DefId,
+    summary: FnSummary<'tcx>,
+    reports: Vec<LeakReport>,
+    generic_leaks: Vec<LeakReport>,
+    instantiated: Vec<DefId>,
+    approximations: Vec<(Approximation, Span)>,
+    unique: Unique,
+    time_build: i64,
+    time_solve: i64,
+    blocks: usize,
+    queries: usize,
+    time_z3: i64,
+}
+
 impl<'tcx, 'a> FlowAnalysis<'tcx, 'a>{
+    // Each function is analyzed as a job returning its findings, summary and metrics in an IntroResult, and
+    // the results are merged afterwards. Only the merge is split out: the job still takes the context mutably,
+    // e.g., it loads the ownership of the adts met for the first time into rcx. The jobs are run sequentially,
+    // since TyCtxt (without the parallel compiler) and z3::Context are neither Send nor Sync either.
+    //
+    // The jobs are run bottom-up on the sccs of the call graph, thus the callers are able to consult the
+    // summaries of their callees. A group of mutually recursive functions is analyzed repeatedly until the
+    // summaries are stable, starting from no summary (the signature-based default) in the first round.
     pub fn intro_run(&mut self) {
         let tcx = self.tcx();
-        let mir_keys = tcx.mir_keys(());
-        let mut unique = Unique::new();
-
-        for each_mir in mir_keys {
+        let sccs = self.rcx().call_graph().sccs();
+        let scope = self.rcx().filter().only_scope(tcx, self.rcx().call_graph());
+        if let (Some(only), Some(scope)) = (self.rcx().filter().only(), &scope) {
+            if scope.is_empty() {
+                rlc_error!("RLC_ONLY={} matches no function in current crate", only);
+            }
+        }
+        let mut unique = Unique::default();
+        for scc in sccs {
+            // the functions excluded by the filter (or out of the scope of RLC_ONLY) are skipped
+            // before building their intro visitors
+            let (dids, denied): (Vec<DefId>, Vec<DefId>) = scc
+                .iter()
+                .partition(|did| {
+                    scope.as_ref().map_or(true, |scope| scope.contains(*did))
+                        && self.rcx().filter().is_analyzed(tcx, **did)
+                });
+            for did in denied {
+                self.rcx_mut().metrics_mut().add_denied();
+                if self.rcx().filter().is_skipped_unsafe(tcx, did) {
+                    let params = tcx.fn_sig(did).inputs().skip_binder().len();
+                    self.rcx_mut().summaries_mut().insert(did, FnSummary::opaque(params));
+                }
+            }
 
-            let mut sw = Stopwatch::start_new();
+            if !self.rcx().call_graph().is_recursive(&scc) {
+                for did in dids {
+                    let result = self.intro_job(did);
+                    self.merge_intro_result(result, &mut unique);
+                }
+                continue;
+            }
 
-            let def_id = each_mir.to_def_id();
-            let body = mir_body(tcx, def_id);
-            // for loop fee function analysis
-            if body.basic_blocks.is_cfg_cyclic() { continue; }
+            let mut results = Vec::with_capacity(dids.len());
+            for _ in 0..INTRO_SUMMARY_CAP {
+                results = dids.iter().map(|did| self.intro_job(*did)).collect();
+                let mut changed = false;
+                for result in results.iter() {
+                    if self.rcx().summaries().get(&result.did) != Some(&result.summary) {
+                        self.rcx_mut().summaries_mut().insert(result.did, result.summary.clone());
+                        changed = true;
+                    }
+                }
+                if !changed {
+                    break;
+                }
+            }
+            for result in results {
+                self.merge_intro_result(result, &mut unique);
+            }
+        }
+        self.flush_generic_leaks();
+    }
 
-            let mut cfg = z3::Config::new();
-            cfg.set_model_generation(true);
-            cfg.set_timeout_msec(1000);
-            let ctx = z3::Context::new(&cfg);
-            let goal = z3::Goal::new(&ctx, true, false, false);
-            let solver = z3::Solver::new(&ctx);
+    pub fn intro_run_body(&mut self, def_id: DefId, unique: &mut Unique) {
+        let result = self.intro_job(def_id);
+        self.merge_intro_result(result, unique);
+        self.flush_generic_leaks();
+    }
 
-            let mut intro_visitor = IntroFlowAnalysis::new(self.rcx, def_id, &mut unique);
-            intro_visitor.visit_body(&ctx, &goal, &solver, body, &sw);
+    // the generic leaks that are never instantiated by a call site in current crate are reported as they are
+    pub(crate) fn flush_generic_leaks(&mut self) {
+        let tcx = self.tcx();
+        let mut dids: Vec<DefId> = self
+            .rcx()
+            .generic_leaks()
+            .keys()
+            .filter(|did| !self.rcx().instantiated().contains(*did))
+            .copied()
+            .collect();
+        dids.sort_by_cached_key(|did| tcx.def_path_str(*did));
+        for did in dids {
+            let leaks = self.rcx_mut().generic_leaks_mut().remove(&did).unwrap_or_default();
+            self.rcx_mut().reports_mut().extend(leaks);
+        }
+        self.rcx_mut().generic_leaks_mut().clear();
+    }
 
-            let sec_build = intro_visitor.get_time_build();
-            let sec_solve = intro_visitor.get_time_solve();
+    pub(crate) fn merge_intro_result(&mut self, result: IntroResult<'tcx>, unique: &mut Unique) {
+        self.analyzed_mut().push(result.did);
+        self.rcx_mut().summaries_mut().insert(result.did, result.summary);
+        self.rcx_mut().add_time_build(result.time_build);
+        self.rcx_mut().add_time_solve(result.time_solve);
+        self.rcx_mut().reports_mut().extend(result.reports);
+        if !result.generic_leaks.is_empty() {
+            self.rcx_mut().generic_leaks_mut().insert(result.did, result.generic_leaks);
+        }
+        self.rcx_mut().instantiated_mut().extend(result.instantiated);
+        for (kind, span) in result.approximations {
+            self.rcx_mut().approximations_mut().add(kind, result.did, span);
+        }
+        self.rcx_mut().metrics_mut().add_analyzed();
+        self.rcx_mut().metrics_mut().add_blocks(result.blocks);
+        self.rcx_mut().metrics_mut().add_z3_queries(result.queries);
+        self.rcx_mut().metrics_mut().add_time_z3(result.time_z3);
+        unique.extend(result.unique);
+    }
 
-            self.rcx_mut().add_time_build(sec_build);
-            self.rcx_mut().add_time_solve(sec_solve);
+    pub(crate) fn intro_job(&mut self, def_id: DefId) -> IntroResult<'tcx> {
+        let sw = Stopwatch::start_new();
+        let mut unique = Unique::default();
+        // the fields are only evaluated if a subscriber is interested in the span
+        let _span = tracing::debug_span!("intro_job", def_path = %self.tcx().def_path_str(def_id)).entered();
+
+        let body = mir_body(self.tcx(), def_id);
+        // the adts of the dependencies met in the body for the first time get their ownership here
+        TypeAnalysis::new(self.rcx).load_adts(body);
+
+        let mut cfg = z3::Config::new();
+        cfg.set_model_generation(true);
+        cfg.set_timeout_msec(self.rcx().config().z3_timeout());
+        let ctx = z3::Context::new(&cfg);
+        let goal = z3::Goal::new(&ctx, true, false, false);
+        let solver = timed_solver(&ctx, self.rcx().config().z3_timeout());
+
+        let mut intro_visitor = IntroFlowAnalysis::new(self.rcx, def_id, &mut unique);
+        intro_visitor.visit_body(&ctx, &goal, &solver, body, &sw);
+        if let Some(dir) = self.rcx().config().icx_dump_path().cloned() {
+            intro_visitor.dump_icx(&dir);
+        }
 
+        let time_build = intro_visitor.get_time_build();
+        let time_solve = intro_visitor.get_time_solve();
+        let blocks = intro_visitor.blocks();
+        let queries = intro_visitor.queries();
+        let time_z3 = intro_visitor.time_z3();
+        let summary = intro_visitor.summary();
+        let reports = intro_visitor.take_reports();
+        let generic_leaks = std::mem::take(&mut intro_visitor.generic_leaks);
+        let instantiated = std::mem::take(&mut intro_visitor.instantiated);
+        let approximations = std::mem::take(&mut intro_visitor.approximations);
+
+        IntroResult {
+            did: def_id,
+            summary,
+            reports,
+            generic_leaks,
+            instantiated,
+            approximations,
+            unique,
+            time_build,
+            time_solve,
+            blocks,
+            queries,
+            time_z3,
         }
     }
 }
 
 impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
+    // write the in and out slices of each visited block in topo order into `<dir>/<fn>.icx`
+    pub(crate) fn dump_icx(&mut self, dir: &str) {
+        rlc_create_dir(dir, "Failed to create dir for icx dump files");
+
+        let mut text = String::new();
+        let topo: Vec<usize> = self.graph().topo_order().collect();
+        for bidx in topo {
+            if self.rounds[bidx] == 0 {
+                continue;
+            }
+            text.push_str(&format!("{:?} in\n", self.graph().mir_bb(bidx)));
+            text.push_str(&IcxSliceFroBlock::new_in(self.icx_mut(), bidx).dump());
+            text.push_str(&format!("{:?} out\n", self.graph().mir_bb(bidx)));
+            text.push_str(&IcxSliceFroBlock::new_out(self.icx_mut(), bidx).dump());
+        }
+
+        let path = Path::new(dir).join(format!("{}.icx", dump_name(self.tcx(), self.did())));
+        let file = rlc_create_file(&path, "Failed to create icx dump file");
+        rlc_write(file, text.as_bytes(), "Failed to write icx dump file");
+    }
+
+    // write the goal of this function into its own file if the path is a dir, or append it to the file
+    pub(crate) fn dump_goal(&self, path: &str, goal: &str) {
+        if path.ends_with('/') || Path::new(path).is_dir() {
+            rlc_create_dir(path, "Failed to create dir for z3 goal files");
+            let path = Path::new(path).join(format!("{}.z3", dump_name(self.tcx(), self.did())));
+            let file = rlc_create_file(&path, "Failed to create z3 goal file");
+            rlc_write(file, goal.as_bytes(), "Failed to write z3 goal file");
+        } else {
+            let file = rlc_append_file(path, "Failed to open z3 goal file");
+            let text = format!("; {}\n{}\n", self.tcx().def_path_str(self.did()), goal);
+            rlc_write(file, text.as_bytes(), "Failed to write z3 goal file");
+        }
+    }
+
+    // write the assertions of the exit query in SMT-LIB2 (followed by check-sat) into its own file, the query can be
+    // replayed by a standalone z3, the query of the unwind exit is suffixed by `.unwind`
+    pub(crate) fn dump_smt2(&self, dir: &str, kind: ExitKind, solver: &z3::Solver<'ctx>) {
+        rlc_create_dir(dir, "Failed to create dir for smt2 files");
+        let name = match kind {
+            ExitKind::Return => format!("{}.smt2", dump_name(self.tcx(), self.did())),
+            ExitKind::Unwind => format!("{}.unwind.smt2", dump_name(self.tcx(), self.did())),
+        };
+        let text = format!("; {}\n{}(check-sat)\n", self.tcx().def_path_str(self.did()), solver);
+        let file = rlc_create_file(&Path::new(dir).join(name), "Failed to create smt2 file");
+        rlc_write(file, text.as_bytes(), "Failed to write smt2 file");
+    }
+
     pub(crate) fn type_layout_prep(&mut self) {
         let locals = &self.body().local_decls;
         let mut tys:Vec<Ty> = Vec::default();
@@ -85,15 +317,68 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         body: &'tcx Body<'tcx>,
         sw: &Stopwatch,
     ) {
-        let topo:Vec<usize> = self.graph().get_topo().iter().map(|id| *id).collect();
-        for bidx in topo
-        {
+        // the worklist starts with the topo order of the condensed graph, and every successor of a block whose
+        // state changed is re-visited, e.g., the blocks after a loop see the state of its last iteration
+        let b_len = body.basic_blocks().len();
+        debug_assert!(
+            self.graph().num_blocks() == b_len && self.icx().var().num_blocks() == b_len,
+            "the graph ({} blocks) and icx ({} blocks) do not match the {} blocks of {:?}",
+            self.graph().num_blocks(),
+            self.icx().var().num_blocks(),
+            b_len,
+            self.did()
+        );
+        let mut worklist:VecDeque<usize> = self.graph().topo_order().collect();
+        let mut in_list = vec![false ; b_len];
+        for bidx in worklist.iter() {
+            in_list[*bidx] = true;
+        }
+
+        let mut capped = Vec::new();
+        while let Some(bidx) = worklist.pop_front() {
+            self.graph().check_block(self.did(), bidx);
+            in_list[bidx] = false;
+
+            // the block not converging keeps its last state, the other blocks in the list are still visited
+            if self.rounds[bidx] >= INTRO_FIXPOINT_CAP {
+                if !capped.contains(&bidx) {
+                    rlc_error!("The fixpoint iteration does not converge in {:?} {:?}, stop iterating it.", self.did(), self.graph().mir_bb(bidx));
+                    capped.push(bidx);
+                }
+                continue;
+            }
+
             let data = &body.basic_blocks()[BasicBlock::from(bidx)];
-            self.visit_block_data(ctx, goal, solver, data, sw, bidx);
+            if !self.visit_block_data(ctx, goal, solver, data, sw, bidx) {
+                continue;
+            }
+
+            for next in self.graph().successors(bidx) {
+                if !in_list[next] {
+                    in_list[next] = true;
+                    worklist.push_back(next);
+                }
+            }
+        }
+
+        // the paths through the capped block carry its stale state, the findings on them are approximated
+        for bidx in capped {
+            let span = self.graph().block_span(bidx).unwrap_or(body.basic_blocks()[BasicBlock::from(bidx)].terminator().source_info.span);
+            for reachable in self.graph().reachable_from(bidx) {
+                self.add_path_approximation(reachable, Approximation::FixpointCap, span);
+            }
         }
 
+        if !self.is_inlined() {
+            self.solve_for_body(goal, solver, sw);
+        }
+        // every frame pushed by a block visit is popped, the discarded ones right away and the kept ones here
+        solver.pop(self.frames as u32);
+        self.frames = 0;
     }
 
+    // return true if the block is visited and its out state may change
+    // return false if the in state of the re-visited block is the same as the last visit
     pub(crate) fn visit_block_data(
         &mut self,
         ctx: &'ctx z3::Context,
@@ -102,9 +387,46 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         data: &'tcx BasicBlockData<'tcx>,
         sw: &Stopwatch,
         bidx: usize,
-    ) {
+    ) -> bool {
+        let pre_in = IcxSliceFroBlock::new_in(self.icx_mut(), bidx);
+
+        // the assertions of each block are scoped in its own solver frame, the frame is kept for the
+        // following blocks in topo order and the exits once the block is visited, and it is popped if the
+        // merged state is discarded, otherwise the phi constants of the same round would be asserted twice.
+        // The frame is on top of the stack at the discard since nothing else is pushed by the merge.
+        solver.push();
         self.preprocess_for_basic_block(ctx, goal, solver, sw, bidx);
 
+        // the approximations on the paths through the pre nodes are carried into this block
+        let mut approximations = self.inherited_approximations(bidx);
+        if self.rounds[bidx] >= INTRO_WIDENING_ROUND {
+            let widened = self.icx_slice_mut().widen(&pre_in, INTRO_WIDENING_LEN);
+            if !widened.is_empty() && self.rcx().config().icx_slice_display().is_verbose() {
+                println!("Widening in {:?} bb{}: {:?}\n", self.did(), bidx, widened);
+            }
+            if !widened.is_empty() {
+                let span = self.graph().block_span(bidx).unwrap_or(data.terminator().source_info.span);
+                self.add_approximation(Approximation::WidenedLoop, span);
+                if !approximations.contains(&(Approximation::WidenedLoop, span)) {
+                    approximations.push((Approximation::WidenedLoop, span));
+                }
+            }
+        }
+
+        if self.rounds[bidx] > 0 && self.icx_slice().is_same_state(&pre_in) {
+            solver.pop(1);
+            self.icx_slice = IcxSliceFroBlock::default();
+            return false;
+        }
+        self.frames += 1;
+        self.path_approximations[bidx] = approximations;
+        let icx_slice = self.icx_slice().clone();
+        self.icx_mut().derive_in_from_icx_slice(icx_slice, bidx);
+        self.blocks += 1;
+
+        // the statements are visited in mir order and the state is updated at each of them, there is no bulk
+        // update at the end of block. The drop glue is always a terminator (one local per block), thus the
+        // drops of several locals are visited one by one in the order of their blocks.
         for (sidx, stmt) in data.statements.iter().enumerate() {
             self.visit_statement(ctx, goal, solver, data, stmt, bidx, sidx);
         }
@@ -113,6 +435,25 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
 
         self.reprocess_for_basic_block(bidx);
 
+        self.rounds[bidx] += 1;
+
+        true
+    }
+
+    // the union of the approximations on the paths reaching the visited pre nodes of the block
+    fn inherited_approximations(&self, bidx: usize) -> Vec<(Approximation, Span)> {
+        let mut approximations: Vec<(Approximation, Span)> = Vec::new();
+        for pidx in self.graph().predecessors(bidx) {
+            if self.rounds[pidx] == 0 {
+                continue;
+            }
+            for approximation in self.path_approximations(pidx) {
+                if !approximations.contains(approximation) {
+                    approximations.push(*approximation);
+                }
+            }
+        }
+        approximations
     }
 
     pub(crate) fn preprocess_for_basic_block(
@@ -138,16 +479,33 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
                     continue;
                 }
 
+                // the tainted raw pointer passed by the caller of the inlined function owns the heap item
+                let param_taint = match &self.param_taints {
+                    Some(taints) => taints.get(arg_idx).cloned().unwrap_or_default(),
+                    None => Taint::default(),
+                };
+                let owned_ptr = ty.is_unsafe_ptr() && param_taint.is_tainted();
+
                 let default_layout = self.extract_default_ty_layout(ty, None);
-                if !default_layout.is_owned() {
+                if !default_layout.is_owned() && !owned_ptr {
                     icx_slice.len_mut()[idx] = 0;
                     icx_slice.var_mut()[idx] = IntroVar::Unsupported;
                     icx_slice.ty_mut()[idx] = TyWithIndex(None);
                     continue;
                 }
-                let int = rustbv_to_int(&ownership_layout_to_rustbv(default_layout.layout()));
+                let int = if owned_ptr {
+                    rustbv_to_int(&rustbv_merge(
+                        &ownership_layout_to_rustbv(default_layout.layout()),
+                        &self.generate_ptr_layout(ty, None)
+                    ))
+                } else {
+                    rustbv_to_int(&ownership_layout_to_rustbv(default_layout.layout()))
+                };
+                if default_layout.layout().is_empty() {
+                    continue;
+                }
 
-                let name = new_local_name(idx, 0, 0).add("_arg_init");
+                let name = self.local_name(idx, 0, 0).add("_arg_init");
                 let len = default_layout.layout().len();
 
                 let new_bv = ast::BV::new_const(ctx, name, len as u32);
@@ -161,6 +519,10 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
                 icx_slice.len_mut()[idx] = len;
                 icx_slice.var_mut()[idx] = IntroVar::Init(new_bv);
                 icx_slice.ty_mut()[idx] = ty_with_index;
+                if owned_ptr {
+                    icx_slice.layout_mut()[idx] = default_layout.layout().clone();
+                }
+                icx_slice.taint_mut()[idx] = param_taint;
             }
 
             *self.icx_slice_mut() = icx_slice.clone();
@@ -168,91 +530,302 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
             return;
         }
 
-        let pre = &self.graph.pre[bidx];
+        // the dead pre nodes (unreachable from bb0) are never visited and have no out state to merge
+        self.graph.check_block(self.did(), bidx);
+        let pre: Vec<usize> = self.graph.pre[bidx]
+            .iter()
+            .filter(|pidx| self.topo_pos[**pidx] != usize::MAX)
+            .copied()
+            .collect();
+        if pre.len() == 0 { rlc_error!("The pre node is empty, check the logic is safe to launch."); }
+
+        // merge the out state of all pre nodes into the in state of this block
+        let phi_vars = self.icx_mut().merge_from_predecessors(bidx, &pre);
+        self.icx_slice = IcxSliceFroBlock::new_in(self.icx_mut(), bidx);
+
+        if self.rcx().config().divergent_ownership() {
+            self.check_divergent_ownership(ctx, solver, bidx, &pre, &phi_vars);
+        }
+
+        // the var holding different bit vectors among pre nodes are merged by a new phi constraint
+        // the ownership of the phi is the and of all branches, e.g., phi = a & b
+        for var_idx in phi_vars {
+            let len = self.icx_slice().len()[var_idx];
 
-        if pre.len() > 1 {
-            // collect all pre nodes and generate their icx slice into a vector
-            let mut v_pre_collect:Vec<IcxSliceFroBlock> = Vec::default();
-            for idx in pre {
-                v_pre_collect.push(IcxSliceFroBlock::new_out(self.icx_mut(), *idx));
+            let mut using_for_and_bv:Option<ast::BV> = None;
+            for idx in pre.iter() {
+                let var = &self.icx().var().get_g()[*idx].get_o()[var_idx];
+                if !var.is_init() {
+                    continue;
+                }
+                let var_bv = var.extract();
+                using_for_and_bv = match using_for_and_bv {
+                    Some(bv) => Some(bv.bvand(&var_bv)),
+                    None => Some(var_bv),
+                };
             }
 
-            // the result icx slice for updating the icx
-            let mut ans_icx_slice = v_pre_collect[0].clone();
-            let var_len = v_pre_collect[0].len().len();
+            let name = self.local_name(var_idx, bidx, 0).add("_phi");
+            let phi_bv = ast::BV::new_const(ctx, name, len as u32);
+            let constraint_phi = phi_bv._eq(&using_for_and_bv.unwrap());
 
-            // for all variables
-            for var_idx in 0..var_len {
+            goal.assert(&constraint_phi);
+            solver.assert(&constraint_phi);
 
-                // the bv and len is using to generate new constrain
-                // the ty is to check the consistency among the branches
-                let mut using_for_and_bv:Option<ast::BV> = None;
-                let mut ty = TyWithIndex::default();
-                let mut len = 0;
+            self.icx_slice_mut().var_mut()[var_idx] = IntroVar::Init(phi_bv);
+        }
 
-                let mut unsupported = false;
-                // for one variable in all pre basic blocks
-                for idx in 0..v_pre_collect.len() {
-                    // merge: ty = ty, len = len
-                    let var = &v_pre_collect[idx].var()[var_idx];
-                    if var.is_declared() {
-                        continue;
-                    }
-                    if var.is_unsupported() {
-                        unsupported = true;
-                        ans_icx_slice.len_mut()[var_idx] = 0;
-                        ans_icx_slice.var_mut()[var_idx] = IntroVar::Unsupported;
-                        break;
-                    }
+        self.specialize_for_match_arm(ctx, goal, solver, bidx);
+        self.specialize_for_guard_arm(bidx);
 
-                    // for now the len must not be zero and the var must not be decl/un..
-                    let var_bv = var.extract();
-                    if ty == TyWithIndex(None) {
-                        ty = v_pre_collect[idx].ty()[var_idx].clone();
-                        len = v_pre_collect[idx].len()[var_idx];
+        // println!("{:?} in {}", self.icx_slice(), bidx);
+    }
 
-                        ans_icx_slice.ty_mut()[var_idx] = ty.clone();
-                        ans_icx_slice.len_mut()[var_idx] = len;
+    // find the enum local and the variant if the block is an arm of match, i.e., the only pre node ends with
+    // `switchInt(move _d)` where `_d = discriminant(_x)`, and the block is the target of a variant of _x
+    pub(crate) fn match_arm_variant(&self, bidx: usize) -> Option<(usize, VariantIdx)> {
+        let pre = &self.graph().get_pre()[bidx];
+        if pre.len() != 1 {
+            return None;
+        }
 
-                        using_for_and_bv = Some(var_bv.clone());
-                    }
+        let pre_data = &self.body().basic_blocks()[BasicBlock::from(pre[0])];
+        let (discr, targets) = match &pre_data.terminator().kind {
+            TerminatorKind::SwitchInt { discr, targets, .. } => (discr, targets),
+            _ => return None,
+        };
+        let discr_place = match discr {
+            Operand::Move(place) | Operand::Copy(place) => place,
+            _ => return None,
+        };
 
-                    if ty != v_pre_collect[idx].ty()[var_idx] {
-                        unsupported = true;
-                        ans_icx_slice.len_mut()[var_idx] = 0;
-                        ans_icx_slice.var_mut()[var_idx] = IntroVar::Unsupported;
-                        break;
-                    }
+        let enum_place = pre_data.statements.iter().rev().find_map(|stmt| match &stmt.kind {
+            StatementKind::Assign(box(place, Rvalue::Discriminant(enum_place))) if place == discr_place => Some(*enum_place),
+            _ => None,
+        })?;
+        if has_projection(&enum_place) {
+            return None;
+        }
 
-                    // use bv and to generate new bv
-                    let bv_and = using_for_and_bv.unwrap().bvand(&var_bv);
-                    using_for_and_bv = Some(bv_and);
-                    ans_icx_slice.taint_merge(&v_pre_collect[idx], var_idx);
-                }
+        // the otherwise target and the target shared by several values can not be specialized
+        let mut values = targets.iter().filter(|(_, target)| target.as_usize() == bidx);
+        let value = match (values.next(), values.next()) {
+            (Some((value, _)), None) => value,
+            _ => return None,
+        };
 
-                if unsupported || using_for_and_bv.is_none() {
-                    *self.icx_slice_mut() = ans_icx_slice.clone();
-                    continue;
-                }
+        let ty = self.body().local_decls[enum_place.local].ty;
+        match ty.kind() {
+            TyKind::Adt(adtdef, ..) if adtdef.is_enum() => {
+                adtdef
+                    .discriminants(self.tcx())
+                    .find(|(_, discr)| discr.val == value)
+                    .map(|(vidx, _)| (enum_place.local.as_usize(), vidx))
+            },
+            _ => None,
+        }
+    }
+
+    // the arm of a variant that owns no heap item starts with the enum local not owning anything,
+    // e.g., the arm of None for Option<Box<T>>, even if the local is constructed as Some in the pre node
+    pub(crate) fn specialize_for_match_arm(
+        &mut self,
+        ctx: &'ctx z3::Context,
+        goal: &'ctx z3::Goal<'ctx>,
+        solver: &'ctx z3::Solver<'ctx>,
+        bidx: usize,
+    ) {
+        let (u, vidx) = match self.match_arm_variant(bidx) {
+            Some(arm) => arm,
+            None => return,
+        };
+
+        let len = self.icx_slice().len()[u];
+        if len == 0 || !self.icx_slice().var()[u].is_init() {
+            return;
+        }
+
+        let ty = self.body().local_decls[Local::from_usize(u)].ty;
+        if self.extract_variant_layouts(ty)[vidx.as_usize()].is_owned() {
+            return;
+        }
+
+        let name = self.local_name(u, bidx, 0).add("_arm");
+        let arm_bv = ast::BV::new_const(ctx, name, len as u32);
+        let zero_const = ast::BV::from_u64(ctx, 0, len as u32);
+        let constraint_arm = arm_bv._eq(&zero_const);
 
-                let name = new_local_name(var_idx, bidx, 0).add("_phi");
-                let phi_bv = ast::BV::new_const(ctx, name, len as u32);
-                let constraint_phi = phi_bv._eq(&using_for_and_bv.unwrap());
+        goal.assert(&constraint_arm);
+        solver.assert(&constraint_arm);
 
-                goal.assert(&constraint_phi);
-                solver.assert(&constraint_phi);
+        self.icx_slice_mut().var_mut()[u] = IntroVar::Init(arm_bv);
+    }
+
+    // the local of the guard and the value disarming its drop if the place is the flag of the guard, e.g., `g.disarmed`
+    pub(crate) fn guard_flag(&self, place: &Place<'tcx>) -> Option<(usize, bool)> {
+        let field = match place.projection.as_ref() {
+            [ProjectionElem::Field(field, ty)] if ty.is_bool() => field.index(),
+            _ => return None,
+        };
+        let adt_did = match self.body().local_decls[place.local].ty.kind() {
+            TyKind::Adt(adtdef, ..) if self.rcx().custom_drop().contains(&adtdef.did()) => adtdef.did(),
+            _ => return None,
+        };
+        match type_analysis::drop_guard_flag(self.tcx(), adt_did) {
+            Some((flag, disarm)) if flag == field => Some((place.local.as_usize(), disarm)),
+            _ => None,
+        }
+    }
+
+    // the flag of the guard is known in the branch switched on it, i.e., the only pre node ends with
+    // `switchInt(move _c)` where `_c = g.disarmed` (or the negation of it), the guard is disarmed or armed
+    // in the branch according to the value
+    pub(crate) fn specialize_for_guard_arm(&mut self, bidx: usize) {
+        let pre = &self.graph().get_pre()[bidx];
+        if pre.len() != 1 {
+            return;
+        }
 
-                ans_icx_slice.var_mut()[var_idx] = IntroVar::Init(phi_bv);
+        let pre_data = &self.body().basic_blocks()[BasicBlock::from(pre[0])];
+        let (discr, targets) = match &pre_data.terminator().kind {
+            TerminatorKind::SwitchInt { discr: Operand::Move(place) | Operand::Copy(place), targets, .. } => (place, targets),
+            _ => return,
+        };
 
-                *self.icx_slice_mut() = ans_icx_slice.clone();
+        let mut place = *discr;
+        let mut negated = false;
+        for stmt in pre_data.statements.iter().rev() {
+            if self.guard_flag(&place).is_some() {
+                break;
+            }
+            match &stmt.kind {
+                StatementKind::Assign(box(lplace, Rvalue::UnaryOp(UnOp::Not, Operand::Move(rplace) | Operand::Copy(rplace))))
+                    if *lplace == place => {
+                    negated = !negated;
+                    place = *rplace;
+                },
+                StatementKind::Assign(box(lplace, Rvalue::Use(Operand::Move(rplace) | Operand::Copy(rplace))))
+                    if *lplace == place => place = *rplace,
+                _ => (),
             }
+        }
+        let (u, disarm) = match self.guard_flag(&place) {
+            Some(flag) => flag,
+            None => return,
+        };
+
+        // the bool switch has the target of false and the otherwise target of true
+        let target = BasicBlock::from(bidx);
+        let switched = if targets.otherwise() == target {
+            true
+        } else if targets.target_for_value(0) == target {
+            false
         } else {
-            if pre.len() == 0 { rlc_error!("The pre node is empty, check the logic is safe to launch."); }
-            self.icx_mut().derive_from_pre_node(pre[0], bidx);
-            self.icx_slice = IcxSliceFroBlock::new_in(self.icx_mut(), bidx);
+            return;
+        };
+        let span = pre_data.terminator().source_info.span;
+        let disarmed = if switched ^ negated == disarm { Some(span) } else { None };
+        self.icx_slice_mut().taint_mut()[u].set_disarmed(disarmed);
+    }
+
+    // In the body of Drop::drop, the raw pointer fields of the receiver point to the heap items owned by the value
+    // being dropped, e.g., `_2 = copy ((*_1).0: *mut u8)`. The local reading a field first owns the heap item as if
+    // it is allocated there, thus the destructor leaks it unless the pointer is freed (by Box::from_raw or dealloc).
+    // Only the `*mut` fields are taken as owning, the `*const` ones are usually borrowed (e.g., a back pointer).
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn handle_drop_field(
+        &mut self,
+        ctx: &'ctx z3::Context,
+        goal: &'ctx z3::Goal<'ctx>,
+        solver: &'ctx z3::Solver<'ctx>,
+        place: &Place<'tcx>,
+        rvalue: &Rvalue<'tcx>,
+        bidx: usize,
+        sidx: usize,
+        span: Span,
+    ) {
+        if !self.drop_body || !place.projection.is_empty() {
+            return;
+        }
+        let (field, ty) = match rvalue {
+            Rvalue::Use(Operand::Move(rplace) | Operand::Copy(rplace)) if rplace.local.as_usize() == 1 => {
+                match rplace.projection.as_ref() {
+                    [ProjectionElem::Deref, ProjectionElem::Field(field, ty)] if ty.is_mutable_ptr() && ty.is_unsafe_ptr() => {
+                        (field.index(), *ty)
+                    },
+                    _ => return,
+                }
+            },
+            _ => return,
+        };
+        // the field is owned once, by its first read
+        if *self.drop_fields.entry(field).or_insert((bidx, sidx)) != (bidx, sidx) {
+            return;
         }
 
-        // println!("{:?} in {}", self.icx_slice(), bidx);
+        let default_layout = self.extract_default_ty_layout(ty, None);
+        if default_layout.layout().is_empty() {
+            return;
+        }
+        let int = rustbv_to_int(&rustbv_merge(
+            &ownership_layout_to_rustbv(default_layout.layout()),
+            &self.generate_ptr_layout(ty, None)
+        ));
+
+        let lu = place.local.as_usize();
+        let name = self.local_name(lu, bidx, sidx).add("_drop_field");
+        let len = default_layout.layout().len();
+
+        let new_bv = ast::BV::new_const(ctx, name, len as u32);
+        let init_const = ast::BV::from_u64(ctx, int, len as u32);
+
+        let constraint_drop_field = new_bv._eq(&init_const);
+
+        goal.assert(&constraint_drop_field);
+        solver.assert(&constraint_drop_field);
+
+        self.taint_flag = true;
+        let origin = (self.program_point(bidx, sidx), span);
+        let alloc = self.alloc_id(bidx, sidx, span);
+        self.icx_slice_mut().len_mut()[lu] = len;
+        self.icx_slice_mut().var_mut()[lu] = IntroVar::Init(new_bv);
+        self.icx_slice_mut().ty_mut()[lu] = TyWithIndex::new(ty, None);
+        self.icx_slice_mut().layout_mut()[lu] = default_layout.layout().clone();
+        self.icx_slice_mut().taint_mut()[lu].insert_with_origin(TyWithIndex::new(ty, None), origin, alloc, TaintSource::Local);
+    }
+
+    // the guard is disarmed by writing the disarming value to its flag,
+    // e.g., `g.disarmed = const true` or the construction of the guard
+    pub(crate) fn handle_guard_flag(&mut self, place: &Place<'tcx>, rvalue: &Rvalue<'tcx>, span: Span) {
+        let (u, disarm, op) = match rvalue {
+            Rvalue::Use(op) => match self.guard_flag(place) {
+                Some((u, disarm)) => (u, disarm, op),
+                None => return,
+            },
+            Rvalue::Aggregate(_, ops) if place.projection.is_empty() => {
+                let field = match self.body().local_decls[place.local].ty.kind() {
+                    TyKind::Adt(adtdef, ..) if self.rcx().custom_drop().contains(&adtdef.did()) => {
+                        type_analysis::drop_guard_flag(self.tcx(), adtdef.did())
+                    },
+                    _ => None,
+                };
+                match field {
+                    Some((field, disarm)) if field < ops.len() => (place.local.as_usize(), disarm, &ops[field]),
+                    _ => return,
+                }
+            },
+            _ => return,
+        };
+
+        // the flag written by a non-constant value is unknown, e.g., `g.disarmed = c`, the guard keeps its state
+        let value = match op {
+            Operand::Constant(constant) => constant.literal.try_to_bool(),
+            _ => None,
+        };
+        if let Some(value) = value {
+            let disarmed = if value == disarm { Some(span) } else { None };
+            self.icx_slice_mut().taint_mut()[u].set_disarmed(disarmed);
+        }
     }
 
     pub(crate) fn reprocess_for_basic_block(
@@ -311,9 +884,38 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
                 //     };
                 // }
 
+                if self.rcx().config().use_after_free() {
+                    self.check_use_after_free(place, rvalue, stmt.source_info.span);
+                    // the pointer assigned as a whole refers to a new allocation unless it is copied from the rvalue
+                    if place.projection.is_empty() {
+                        let taint = &mut self.icx_slice_mut().taint_mut()[l_local.as_usize()];
+                        taint.allocs_mut().clear();
+                        taint.set_freed(None);
+                    }
+                }
+
+                let pre_var = self.icx_slice().var()[l_local.as_usize()].clone();
+                let saved_owner = match rvalue {
+                    Rvalue::Use(Operand::Move(rplace) | Operand::Copy(rplace))
+                    | Rvalue::Cast(_, Operand::Move(rplace) | Operand::Copy(rplace), _) => {
+                        self.icx_slice().taint()[rplace.local.as_usize()].is_tainted()
+                    },
+                    _ => false,
+                };
+                let stored_owner = self.static_ref(place).is_some() && (saved_owner || self.is_moving_owner(rvalue));
                 self.visit_assign(ctx, goal, solver, place, rvalue, disc, bidx, sidx);
+                self.handle_generator_save(place, saved_owner, stmt.source_info.span);
+                self.handle_static_store(place, stored_owner, stmt.source_info.span);
+                self.handle_guard_flag(place, rvalue, stmt.source_info.span);
+                self.handle_drop_field(ctx, goal, solver, place, rvalue, bidx, sidx, stmt.source_info.span);
+                self.handle_ref_provenance(place, rvalue);
+                self.record_write(l_local.as_usize(), &pre_var, (bidx, sidx), stmt.source_info.span);
+                // the local holds a fresh value after being assigned as a whole
+                if place.projection.is_empty() {
+                    self.icx_slice_mut().taint_mut()[l_local.as_usize()].set_consumed(None);
+                }
 
-                if is_icx_slice_verbose() {
+                if self.rcx().config().icx_slice_display().is_verbose() {
                     println!("IcxSlice in Assign: {} {}: {:?}\n{:?}\n", bidx, sidx, stmt.kind, self.icx_slice());
                 }
             },
@@ -321,7 +923,7 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
                 self.handle_stmt_live(local, bidx);
             },
             StatementKind::StorageDead(local) => {
-                self.handle_stmt_dead(local, bidx);
+                self.handle_stmt_dead(local, bidx, stmt.source_info.span);
             },
             _ => (),
         }
@@ -341,18 +943,70 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
 
         match &term.kind {
             TerminatorKind::Drop { place, .. } => {
+                if self.rcx().config().double_free() {
+                    self.check_double_free(place.local, term.source_info.span);
+                }
+                if self.rcx().config().use_after_free() {
+                    self.mark_freed(place.local, term.source_info.span);
+                    self.mark_box_freed(place, term.source_info.span);
+                }
+                self.handle_drop(ctx, goal, solver, place, bidx, false);
+            },
+            // only in the built and analysis MIR (e.g., `*p = Box::new(1)`), the old value is dropped and then
+            // the place is assigned the new one, it is split into the two by the drop elaboration
+            TerminatorKind::DropAndReplace { place, value, .. } => {
+                if self.rcx().config().double_free() {
+                    self.check_double_free(place.local, term.source_info.span);
+                }
+                if self.rcx().config().use_after_free() {
+                    self.mark_freed(place.local, term.source_info.span);
+                    self.mark_box_freed(place, term.source_info.span);
+                }
                 self.handle_drop(ctx, goal, solver, place, bidx, false);
+                let lu = place.local.as_usize();
+                let pre_var = self.icx_slice().var()[lu].clone();
+                let rvalue = Rvalue::Use(value.clone());
+                self.visit_assign(ctx, goal, solver, place, &rvalue, None, bidx, usize::MAX);
+                self.record_write(lu, &pre_var, (bidx, usize::MAX), term.source_info.span);
+                if place.projection.is_empty() {
+                    self.icx_slice_mut().taint_mut()[lu].set_consumed(None);
+                }
             },
-            TerminatorKind::Call { func, args, destination, .. } => {
-                self.handle_call(ctx, goal, solver, &func, &args, &destination, bidx);
+            TerminatorKind::Call { func, args, destination, target, .. } => {
+                let lu = destination.local.as_usize();
+                let pre_var = self.icx_slice().var()[lu].clone();
+                let saved_owner = self.saved_field(destination).is_some() && self.check_fn_source(args, destination);
+                self.handle_ptr_write(ctx, solver, func, args, bidx, term.source_info.span);
+                let drained = self.drained_container(func, args);
+                self.handle_call(ctx, goal, solver, func, args, destination, bidx, term.source_info.span);
+                self.handle_into_iter(drained, destination);
+                self.handle_generator_save(destination, saved_owner, term.source_info.span);
+                if target.is_none() && self.is_process_exit_call(func) {
+                    self.handle_process_exit(ctx, goal, solver, bidx);
+                }
+                self.handle_replace(func, args, destination, bidx);
+                self.handle_transmute_provenance(func, args, destination);
+                self.record_write(lu, &pre_var, (bidx, usize::MAX), term.source_info.span);
+                if destination.projection.is_empty() {
+                    self.icx_slice_mut().taint_mut()[lu].set_consumed(None);
+                }
+                if self.taint_flag && self.taint_span.is_none() {
+                    self.taint_span = Some(term.source_info.span);
+                }
             },
             TerminatorKind::Return => {
-                self.handle_return(ctx, goal, solver, sw, bidx);
-            }
+                self.handle_return(ctx, goal, solver, bidx, ExitKind::Return);
+            },
+            TerminatorKind::Resume => {
+                self.handle_return(ctx, goal, solver, bidx, ExitKind::Unwind);
+            },
+            TerminatorKind::InlineAsm { .. } => {
+                self.add_path_approximation(bidx, Approximation::InlineAsm, term.source_info.span);
+            },
             _ => (),
         }
 
-        if is_icx_slice_verbose() {
+        if self.rcx().config().icx_slice_display().is_verbose() {
             println!("IcxSlice in Terminator: {}: {:?}\n{:?}\n", bidx, term.kind, self.icx_slice());
         }
     }
@@ -534,11 +1188,11 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
 
         // produce the name of lvalue and rvalue in this program point
         let l_name = if is_ctor {
-            new_local_name(lu, bidx, sidx).add("_ctor_asgn")
+            self.local_name(lu, bidx, sidx).add("_ctor_asgn")
         } else {
-            new_local_name(lu, bidx, sidx)
+            self.local_name(lu, bidx, sidx)
         };
-        let r_name = new_local_name(ru, bidx, sidx);
+        let r_name = self.local_name(ru, bidx, sidx);
 
         // generate new bit vectors for variables
         let l_new_bv = ast::BV::new_const(ctx, l_name, llen as u32);
@@ -665,11 +1319,11 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
 
         // produce the name of lvalue and rvalue in this program point
         let l_name = if is_ctor {
-            new_local_name(lu, bidx, sidx).add("_ctor_asgn")
+            self.local_name(lu, bidx, sidx).add("_ctor_asgn")
         } else {
-            new_local_name(lu, bidx, sidx)
+            self.local_name(lu, bidx, sidx)
         };
-        let r_name = new_local_name(ru, bidx, sidx);
+        let r_name = self.local_name(ru, bidx, sidx);
 
         // generate new bit vectors for variables
         let l_new_bv = ast::BV::new_const(ctx, l_name, llen as u32);
@@ -804,11 +1458,11 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
 
         // produce the name of lvalue and rvalue in this program point
         let l_name = if is_ctor {
-            new_local_name(lu, bidx, sidx).add("_ctor_asgn")
+            self.local_name(lu, bidx, sidx).add("_ctor_asgn")
         } else {
-            new_local_name(lu, bidx, sidx)
+            self.local_name(lu, bidx, sidx)
         };
-        let r_name = new_local_name(ru, bidx, sidx);
+        let r_name = self.local_name(ru, bidx, sidx);
 
         // generate new bit vectors for variables
         let l_new_bv = ast::BV::new_const(ctx, l_name, llen as u32);
@@ -865,7 +1519,7 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         // update the intro var value in current basic block (exactly, the statement)
         self.icx_slice_mut().var_mut()[lu] = IntroVar::Init(l_new_bv);
         self.icx_slice_mut().var_mut()[ru] = IntroVar::Init(r_new_bv);
-        self.handle_taint(lu, ru);
+        self.handle_field_taint(lu, None, ru, Some(index_needed), false);
 
     }
 
@@ -983,11 +1637,11 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
 
         // produce the name of lvalue and rvalue in this program point
         let l_name = if is_ctor {
-            new_local_name(lu, bidx, sidx).add("_ctor_asgn")
+            self.local_name(lu, bidx, sidx).add("_ctor_asgn")
         } else {
-            new_local_name(lu, bidx, sidx)
+            self.local_name(lu, bidx, sidx)
         };
-        let r_name = new_local_name(ru, bidx, sidx);
+        let r_name = self.local_name(ru, bidx, sidx);
 
         // generate new bit vectors for variables
         let l_new_bv = ast::BV::new_const(ctx, l_name, llen as u32);
@@ -1032,7 +1686,7 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         // update the intro var value in current basic block (exactly, the statement)
         self.icx_slice_mut().var_mut()[lu] = IntroVar::Init(l_new_bv);
         self.icx_slice_mut().var_mut()[ru] = IntroVar::Init(r_new_bv);
-        self.handle_taint(lu, ru);
+        self.handle_field_taint(lu, None, ru, Some(index_needed), true);
 
     }
 
@@ -1141,7 +1795,7 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         } else {
             // this branch means that the assignment is the constructor of the lvalue (either l and l.f)
             // this constraint promise before the struct is [0;field]
-            let l_ori_name_ctor = new_local_name(lu, bidx, sidx).add("_ctor_asgn");
+            let l_ori_name_ctor = self.local_name(lu, bidx, sidx).add("_ctor_asgn");
             let l_ori_bv_ctor = ast::BV::new_const(ctx, l_ori_name_ctor, llen as u32);
             let l_ori_zero = ast::BV::from_u64(ctx, 0, llen as u32);
             let constraint_l_ctor_zero = l_ori_bv_ctor._safe_eq(&l_ori_zero).unwrap();
@@ -1157,8 +1811,8 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         // self.icx_slice_mut().len_mut()[lu] = llen;
 
         // produce the name of lvalue and rvalue in this program point
-        let l_name = new_local_name(lu, bidx, sidx);
-        let r_name = new_local_name(ru, bidx, sidx);
+        let l_name = self.local_name(lu, bidx, sidx);
+        let r_name = self.local_name(ru, bidx, sidx);
 
         // generate new bit vectors for variables
         let l_new_bv = ast::BV::new_const(ctx, l_name, llen as u32);
@@ -1219,7 +1873,7 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         // update the intro var value in current basic block (exactly, the statement)
         self.icx_slice_mut().var_mut()[lu] = IntroVar::Init(l_new_bv);
         self.icx_slice_mut().var_mut()[ru] = IntroVar::Init(r_new_bv);
-        self.handle_taint(lu, ru);
+        self.handle_field_taint(lu, Some(index_needed), ru, None, false);
 
     }
 
@@ -1329,7 +1983,7 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         } else {
             // this branch means that the assignment is the constructor of the lvalue (either l and l.f)
             // this constraint promise before the struct is [0;field]
-            let l_ori_name_ctor = new_local_name(lu, bidx, sidx).add("_ctor_asgn");
+            let l_ori_name_ctor = self.local_name(lu, bidx, sidx).add("_ctor_asgn");
             let l_ori_bv_ctor = ast::BV::new_const(ctx, l_ori_name_ctor, llen as u32);
             let l_ori_zero = ast::BV::from_u64(ctx, 0, llen as u32);
             let constraint_l_ctor_zero = l_ori_bv_ctor._safe_eq(&l_ori_zero).unwrap();
@@ -1345,8 +1999,8 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         // self.icx_slice_mut().len_mut()[lu] = llen;
 
         // produce the name of lvalue and rvalue in this program point
-        let l_name = new_local_name(lu, bidx, sidx);
-        let r_name = new_local_name(ru, bidx, sidx);
+        let l_name = self.local_name(lu, bidx, sidx);
+        let r_name = self.local_name(ru, bidx, sidx);
 
         // generate new bit vectors for variables
         let l_new_bv = ast::BV::new_const(ctx, l_name, llen as u32);
@@ -1386,7 +2040,7 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         // update the intro var value in current basic block (exactly, the statement)
         self.icx_slice_mut().var_mut()[lu] = IntroVar::Init(l_new_bv);
         self.icx_slice_mut().var_mut()[ru] = IntroVar::Init(r_new_bv);
-        self.handle_taint(lu, ru);
+        self.handle_field_taint(lu, Some(index_needed), ru, None, true);
 
     }
 
@@ -1498,7 +2152,7 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         } else {
             // this branch means that the assignment is the constructor of the lvalue (either l and l.f)
             // this constraint promise before the struct is [0;field]
-            let l_ori_name_ctor = new_local_name(lu, bidx, sidx).add("_ctor_asgn");
+            let l_ori_name_ctor = self.local_name(lu, bidx, sidx).add("_ctor_asgn");
             let l_ori_bv_ctor = ast::BV::new_const(ctx, l_ori_name_ctor, llen as u32);
             let l_ori_zero = ast::BV::from_u64(ctx, 0, llen as u32);
             let constraint_l_ctor_zero = l_ori_bv_ctor._safe_eq(&l_ori_zero).unwrap();
@@ -1510,8 +2164,8 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         }
 
         // produce the name of lvalue and rvalue in this program point
-        let l_name = new_local_name(lu, bidx, sidx);
-        let r_name = new_local_name(ru, bidx, sidx);
+        let l_name = self.local_name(lu, bidx, sidx);
+        let r_name = self.local_name(ru, bidx, sidx);
 
         // generate new bit vectors for variables
         let l_new_bv = ast::BV::new_const(ctx, l_name, llen as u32);
@@ -1575,7 +2229,7 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         // update the intro var value in current basic block (exactly, the statement)
         self.icx_slice_mut().var_mut()[lu] = IntroVar::Init(l_new_bv);
         self.icx_slice_mut().var_mut()[ru] = IntroVar::Init(r_new_bv);
-        self.handle_taint(lu, ru);
+        self.handle_field_taint(lu, Some(l_index_needed), ru, Some(r_index_needed), false);
 
     }
 
@@ -1686,7 +2340,7 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         } else {
             // this branch means that the assignment is the constructor of the lvalue (either l and l.f)
             // this constraint promise before the struct is [0;field]
-            let l_ori_name_ctor = new_local_name(lu, bidx, sidx).add("_ctor_asgn");
+            let l_ori_name_ctor = self.local_name(lu, bidx, sidx).add("_ctor_asgn");
             let l_ori_bv_ctor = ast::BV::new_const(ctx, l_ori_name_ctor, llen as u32);
             let l_ori_zero = ast::BV::from_u64(ctx, 0, llen as u32);
             let constraint_l_ctor_zero = l_ori_bv_ctor._safe_eq(&l_ori_zero).unwrap();
@@ -1698,8 +2352,8 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         }
 
         // produce the name of lvalue and rvalue in this program point
-        let l_name = new_local_name(lu, bidx, sidx);
-        let r_name = new_local_name(ru, bidx, sidx);
+        let l_name = self.local_name(lu, bidx, sidx);
+        let r_name = self.local_name(ru, bidx, sidx);
 
         // generate new bit vectors for variables
         let l_new_bv = ast::BV::new_const(ctx, l_name, llen as u32);
@@ -1744,7 +2398,7 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         // update the intro var value in current basic block (exactly, the statement)
         self.icx_slice_mut().var_mut()[lu] = IntroVar::Init(l_new_bv);
         self.icx_slice_mut().var_mut()[ru] = IntroVar::Init(r_new_bv);
-        self.handle_taint(lu, ru);
+        self.handle_field_taint(lu, Some(l_index_needed), ru, Some(r_index_needed), true);
 
     }
 
@@ -1760,16 +2414,121 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         //     = Storage::Declared;
     }
 
+    // the storage dead does not free the heap item, the local keeps its ownership bits after it, thus
+    // the local going dead without a drop (e.g., a forgotten raw pointer owner) is still caught at the exit
     pub(crate) fn handle_stmt_dead(
         &mut self,
         local: &Local,
-        bidx: usize
+        bidx: usize,
+        span: Span,
     ) {
-        // self
-        //     .icx_slice_mut()
-        //     .storage_mut()
-        //     [local.index()]
-        //     = Storage::Dead;
+        // StorageDead only ends the storage of the local and it never consumes the ownership, only the Drop
+        // terminators (and the drop glue) do. The moved-out local holds nothing here, and the Copy value except
+        // the raw pointer is irrelevant to the ownership. The local still owning here is never dropped later,
+        // and it is the leak signal checked at the exit, the span is kept to locate the leak.
+        let u = local.as_usize();
+        if self.icx_slice().len()[u] == 0 || !self.icx_slice().var()[u].is_init() {
+            return;
+        }
+        let ty = self.body().local_decls[*local].ty;
+        let param_env = self.tcx().param_env(self.did());
+        if !ty.is_unsafe_ptr() && ty.is_copy_modulo_regions(self.tcx().at(span), param_env) {
+            return;
+        }
+        self.dead_spans[u] = Some(span);
+    }
+
+    // the variant and field of the generator state if the place is a local saved across a suspension point, it is
+    // a field of the suspended variant behind the first arg of the resume fn, e.g., `((*(_1.0)) as variant#3).0`
+    pub(crate) fn saved_field(&self, place: &Place<'tcx>) -> Option<(usize, usize)> {
+        self.body().generator_layout()?;
+        if place.local.as_usize() != 1 {
+            return None;
+        }
+        match place.projection.as_ref() {
+            [.., ProjectionElem::Downcast(_, vidx), ProjectionElem::Field(field, _)] => Some((vidx.as_usize(), field.index())),
+            _ => None,
+        }
+    }
+
+    // The async fn (or generator) is analyzed by the body of its resume fn, where the locals living across a
+    // suspension point (an `.await`) are the fields of the state and have no StorageDead of their own. If the future
+    // is dropped before completion, the drop glue of the state only drops the owned saved locals. The heap item
+    // owned by a saved raw pointer leaks then, even if the pointer is turned back into a Box after the resume.
+    pub(crate) fn handle_generator_save(&mut self, place: &Place<'tcx>, owning: bool, span: Span) {
+        if !owning {
+            return;
+        }
+        let (vidx, field) = match self.saved_field(place) {
+            Some(saved) => saved,
+            None => return,
+        };
+        if !place.ty(self.body(), self.tcx()).ty.is_unsafe_ptr() || self.suspended.contains(&(vidx, field)) {
+            return;
+        }
+        self.suspended.push((vidx, field));
+
+        let suspend = self
+            .body()
+            .generator_layout()
+            .and_then(|layout| layout.variant_source_info.get(VariantIdx::from_usize(vidx)))
+            .map_or(span, |info| info.span);
+        let message = format!(
+            "The heap item owned by a raw pointer is held across the suspension point of `{}`, it leaks if the future is dropped before completion",
+            self.tcx().def_path_str(self.did()),
+        );
+        self.reports.push(
+            LeakReport::new(self.did(), suspend, None, OwnershipLayout::default(), message)
+                .with_origin(Some(span))
+        );
+    }
+
+    // the static behind the place if it is written through the temporary referring to the static, e.g., `(*_2) = move _1`
+    // where `_2 = const {alloc1: *mut Option<Box<i32>>}` is the access of `static mut S`
+    pub(crate) fn static_ref(&self, place: &Place<'tcx>) -> Option<DefId> {
+        if place.projection.first() != Some(&ProjectionElem::Deref) {
+            return None;
+        }
+        match self.body().local_decls[place.local].local_info {
+            Some(box LocalInfo::StaticRef { def_id, .. }) => Some(def_id),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn is_moving_owner(&mut self, rvalue: &Rvalue<'tcx>) -> bool {
+        match rvalue {
+            Rvalue::Use(Operand::Move(rplace)) => {
+                let ty = rplace.ty(self.body(), self.tcx());
+                self.extract_default_ty_layout(ty.ty, ty.variant_index).is_owned()
+            },
+            _ => false,
+        }
+    }
+
+    // The owned value (or the tainted raw pointer) stored into a static lives until the program exits, it escapes from
+    // the function rather than leaks (see `escaping_locals`). It is only reported by `-STATIC` as stored in static.
+    pub(crate) fn handle_static_store(&mut self, place: &Place<'tcx>, owning: bool, span: Span) {
+        if !owning || !self.rcx().config().static_store() {
+            return;
+        }
+        let def_id = match self.static_ref(place) {
+            Some(def_id) => def_id,
+            None => return,
+        };
+        // the block may be re-visited in the fixpoint iteration
+        if self.reports.iter().any(|r| r.kind() == ReportKind::StoredInStatic && r.span() == span) {
+            return;
+        }
+
+        let message = format!(
+            "The heap item is stored in the static `{}` by `{}`, it is never freed unless the static is reset",
+            self.tcx().def_path_str(def_id),
+            self.tcx().def_path_str(self.did()),
+        );
+        self.reports.push(
+            LeakReport::new(self.did(), span, None, OwnershipLayout::default(), message)
+                .with_kind(ReportKind::StoredInStatic)
+        );
     }
 
     pub(crate) fn check_fn_source(
@@ -1851,44 +2610,233 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         args: &Vec<Operand<'tcx>>,
         dest: &Place<'tcx>,
         bidx: usize,
+        span: Span,
     ) {
 
-        match func {
-            Operand::Constant(constant) => {
-                match constant.literal.ty().kind() {
-                    ty::FnDef(id, ..) => {
-                        match id.index.as_usize() {
-                            2171 => {
-                                // this for calling std::mem::drop(TY)
-                                match args[0] {
-                                    Operand::Move(aplace) => {
-                                        let a_place_ty = dest.ty(&self.body().local_decls, self.tcx());
-                                        let a_ty = a_place_ty.ty;
-                                        if a_ty.is_adt() {
-                                            self.handle_drop(ctx, goal, solver, &aplace, bidx, false);
-                                            return;
-                                        }
-                                    }, _ => (),
-                                }
+        if let Operand::Constant(constant) = func {
+            if let ty::FnDef(id, ..) = constant.literal.ty().kind() {
+                // the intrinsics and foreign functions are opaque calls approximated by their signatures
+                if self.tcx().is_foreign_item(*id) {
+                    self.add_path_approximation(bidx, Approximation::ForeignCall, span);
+                } else if matches!(self.tcx().fn_sig(*id).abi(), Abi::RustIntrinsic | Abi::PlatformIntrinsic) {
+                    self.add_path_approximation(bidx, Approximation::Intrinsic, span);
+                }
 
-                            }, _ => (),
+                // the value passed to mem::forget, ManuallyDrop::new or Box::leak is leaked deliberately,
+                // so it is viewed as freed and its taint is cleared to avoid the finding
+                if type_analysis::is_intentional_leak_fn(self.tcx(), *id) {
+                    if let Some(Operand::Move(aplace)) = args.get(0) {
+                        // forgetting the container whose buffer (allocated by with_capacity or reserve) is
+                        // never handed out leaks the buffer, thus it keeps the ownership until the exit
+                        if self.tcx().is_diagnostic_item(sym::mem_forget, *id)
+                            && aplace.projection.is_empty()
+                            && self.icx_slice().taint()[aplace.local.as_usize()].capacity().is_some() {
+                            return;
                         }
-                    }, _ => (),
+                        self.icx_slice_mut().taint_mut()[aplace.local.as_usize()] = Taint::default();
+                        self.handle_drop(ctx, goal, solver, aplace, bidx, false);
+                    }
+                    return;
                 }
-            }, _ => (),
-        }
 
-        // for return value
-        let llocal = dest.local;
+                // ptr::drop_in_place runs the drop glue of the pointee without moving it out
+                // the pointee owns nothing then until it is written again, e.g., by ptr::write
+                if self.tcx().lang_items().drop_in_place_fn() == Some(*id) {
+                    if let Some(Operand::Move(aplace) | Operand::Copy(aplace)) = args.get(0) {
+                        if let Some(local) = self.pointee_local(bidx, aplace) {
+                            if self.rcx().config().double_free() {
+                                self.check_double_free(local, span);
+                            }
+                            self.handle_drop(ctx, goal, solver, &Place::from(local), bidx, false);
+                        }
+                    }
+                }
+
+                // std::mem::drop(TY) runs the drop glue of the adt moved into it
+                if self.tcx().is_diagnostic_item(sym::mem_drop, *id) {
+                    if let Some(Operand::Move(aplace)) = args.get(0) {
+                        let a_ty = aplace.ty(&self.body().local_decls, self.tcx()).ty;
+                        if a_ty.is_adt() {
+                            if self.rcx().config().use_after_free() {
+                                self.mark_freed(aplace.local, span);
+                            }
+                            self.handle_drop(ctx, goal, solver, aplace, bidx, false);
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+
+        // for return value
+        let llocal = dest.local;
         let lu:usize = llocal.as_usize();
 
+        // the allocator functions manage the heap by hand, the pointer returned by alloc owns the allocation
+        // until it is given back to dealloc, and realloc does both of them
+        let alloc_fn = match func {
+            Operand::Constant(constant) => match constant.literal.ty().kind() {
+                ty::FnDef(id, ..) => type_analysis::alloc_fn_kind(self.tcx(), *id),
+                _ => None,
+            },
+            _ => None,
+        };
+
         // the source flag is for fn(self) -> */&
         // we will tag the lvalue as tainted and change the default ctor to modified one
-        let source_flag = self.check_fn_source(args, dest);
+        let mut source_flag = self.check_fn_source(args, dest)
+            || matches!(alloc_fn, Some((AllocFnKind::Alloc | AllocFnKind::Realloc, _)));
         // the recovery flag is for fn(*) -> Self
         // the return value should have the same layout as tainted one
         // we will take the ownership of the args if the arg is a pointer
-        let recovery_flag = self.check_fn_recovery(args, dest);
+        let mut recovery_flag = self.check_fn_recovery(args, dest);
+
+        // the summary of the analyzed callee tells which pointers it takes the ownership from,
+        // and whether the returned pointer carries the ownership to the caller
+        // the small leaf callee is analyzed again in the context of this call site if inlining is enabled
+        let inlined = self.inline_summary(func, args);
+        let analyzed = match func {
+            Operand::Constant(constant) => match constant.literal.ty().kind() {
+                ty::FnDef(id, ..) => inlined.clone().or_else(|| self.rcx().summaries().get(id).cloned()),
+                _ => None,
+            },
+            _ => None,
+        };
+        // the callee without mir (e.g., a non-generic std function or a foreign one) falls back to the known
+        // summary given by RLC_SUMMARIES or the default ones
+        let known = match (&analyzed, func) {
+            (None, Operand::Constant(constant)) => match constant.literal.ty().kind() {
+                ty::FnDef(id, ..) => self.rcx().known_summary(*id),
+                _ => None,
+            },
+            _ => None,
+        };
+        let summary = analyzed.or_else(|| known.clone());
+        // the tainted raw pointer flowing into the returned pointer of the inlined callee gives its ownership
+        // to the return value, e.g., `fn pass(p: *mut T) -> *mut T { p }`
+        if let Some(inlined) = &inlined {
+            for arg in args {
+                let au = match arg.place() {
+                    Some(aplace) if aplace.projection.is_empty() => aplace.local.as_usize(),
+                    _ => continue,
+                };
+                let taint = &self.icx_slice().taint()[au];
+                if taint.is_untainted() || !taint.set().iter().any(|elem| inlined.ret_taint().contains(elem)) {
+                    continue;
+                }
+                source_flag = true;
+                self.taint_flag = true;
+                if !recovery_flag.1.contains(&au) {
+                    recovery_flag.0 = true;
+                    recovery_flag.1.push(au);
+                }
+            }
+        }
+        self.record_consumed_params(args, dest, summary.as_ref());
+        if let Operand::Constant(constant) = func {
+            if let ty::FnDef(id, substs) = constant.literal.ty().kind() {
+                self.instantiate_generic_leaks(*id, substs, span);
+            }
+        }
+        if let Some(summary) = &summary {
+            for (k, arg) in args.iter().enumerate() {
+                match arg.place() {
+                    Some(aplace) if aplace.projection.is_empty() && summary.takes_ownership(k) => {
+                        let au = aplace.local.as_usize();
+                        if !recovery_flag.1.contains(&au) {
+                            recovery_flag.0 = true;
+                            recovery_flag.1.push(au);
+                        }
+                    },
+                    _ => (),
+                }
+            }
+            if summary.ret_taint().is_tainted() && is_place_containing_ptr(&dest.ty(&self.body().local_decls, self.tcx()).ty) {
+                self.taint_flag = true;
+                let origin = (self.program_point(bidx, usize::MAX), span);
+                let alloc = self.alloc_id(bidx, usize::MAX, span);
+                for elem in summary.ret_taint().set().iter() {
+                    self.icx_slice_mut().taint_mut()[lu].insert_with_origin(elem.clone(), origin, alloc, TaintSource::CalleeReturn);
+                }
+            }
+        }
+        // the raw pointer returned by the known callee owns the heap item it allocates, e.g., `libc::malloc`
+        let dest_ty = dest.ty(&self.body().local_decls, self.tcx());
+        if known.as_ref().map_or(false, |known| known.is_owned_return()) && is_place_containing_ptr(&dest_ty.ty) {
+            source_flag = true;
+            self.taint_flag = true;
+            let origin = (self.program_point(bidx, usize::MAX), span);
+            let alloc = self.alloc_id(bidx, usize::MAX, span);
+            self.icx_slice_mut().taint_mut()[lu].insert_with_origin(
+                TyWithIndex::new(dest_ty.ty, dest_ty.variant_index),
+                origin,
+                alloc,
+                TaintSource::CalleeReturn
+            );
+        }
+
+        // the recovered instance owns the allocations the pointers refer to (e.g., Box::from_raw(p))
+        if recovery_flag.0 {
+            for au in recovery_flag.1.iter() {
+                let allocs = self.icx_slice().taint()[*au].allocs().clone();
+                self.icx_slice_mut().taint_mut()[lu].allocs_mut().extend(allocs);
+            }
+        }
+
+        // the callee of a virtual call is unknown, the owned args passed by value are consumed by the moves below
+        // as usual and the return value is owned by the caller, the optimistic mode also gives the tainted
+        // raw pointers away to the callee
+        if is_virtual_call(self.tcx(), self.did(), func) {
+            if !self.virtual_calls.contains(&span) {
+                self.virtual_calls.push(span);
+            }
+            self.add_path_approximation(bidx, Approximation::VirtualCall, span);
+            if self.rcx().config().virtual_call().is_optimistic() {
+                self.give_away_tainted_args(args, &mut recovery_flag);
+            }
+        }
+
+        // the C side may free the raw pointers passed to a foreign function in the FFI-friendly mode
+        if self.rcx().config().ffi().is_friendly() && is_foreign_call(self.tcx(), func) {
+            self.give_away_tainted_args(args, &mut recovery_flag);
+        }
+
+        if let Some((kind, ptr_idx)) = alloc_fn {
+            // the pointer passed to dealloc or realloc gives the ownership of the old allocation back
+            if kind != AllocFnKind::Alloc {
+                match args.get(ptr_idx).and_then(|arg| arg.place()) {
+                    Some(aplace) if aplace.projection.is_empty() => {
+                        let au = aplace.local.as_usize();
+                        if !recovery_flag.1.contains(&au) {
+                            recovery_flag.0 = true;
+                            recovery_flag.1.push(au);
+                        }
+                        if let Some(param) = self.param_alias[au] {
+                            self.consumed_params[param] = true;
+                        }
+                        if self.rcx().config().use_after_free() {
+                            self.mark_freed(aplace.local, span);
+                        }
+                    },
+                    _ => (),
+                }
+            }
+            // the returned pointer owns the new allocation
+            if kind != AllocFnKind::Dealloc && is_place_containing_ptr(&dest.ty(&self.body().local_decls, self.tcx()).ty) {
+                self.taint_flag = true;
+                let origin = (self.program_point(bidx, usize::MAX), span);
+                let alloc = self.alloc_id(bidx, usize::MAX, span);
+                let dest_ty = dest.ty(&self.body().local_decls, self.tcx());
+                self.icx_slice_mut().taint_mut()[lu].insert_with_origin(
+                    TyWithIndex::new(dest_ty.ty, dest_ty.variant_index),
+                    origin,
+                    alloc,
+                    TaintSource::Local
+                );
+            }
+        }
+
+        self.handle_capacity(func, args, dest, bidx, span);
 
         for arg in args {
             match arg {
@@ -1916,11 +2864,17 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
                     let alen = self.icx_slice().len()[au];
 
                     if source_flag {
-                        self.icx_slice_mut().taint_mut()[lu].insert(
+                        let origin = (self.program_point(bidx, usize::MAX), span);
+                        let alloc = self.alloc_id(bidx, usize::MAX, span);
+                        let source = self.taint_source(au);
+                        self.icx_slice_mut().taint_mut()[lu].insert_with_origin(
                             TyWithIndex::new(
                                 a_place_ty.ty,
                                 a_place_ty.variant_index
-                            )
+                            ),
+                            origin,
+                            alloc,
+                            source
                         );
                     }
 
@@ -1940,7 +2894,7 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
                                 let a_ori_non_owing = a_ori_bv._safe_eq(&a_zero_const).unwrap();
 
                                 // this is for a'=a
-                                let a_name = new_local_name(au, bidx, 0).add("_param_pass");
+                                let a_name = self.local_name(au, bidx, 0).add("_param_pass");
                                 let a_new_bv = ast::BV::new_const(ctx, a_name, alen as u32);
                                 let update_a = a_new_bv._safe_eq(&a_ori_bv).unwrap();
 
@@ -1965,7 +2919,7 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
                                 // if the aplace in field is a pointer (move a.f (ptr) => still hold)
                                 // the exact constraint is a'=a
                                 // this is for a'=a
-                                let a_name = new_local_name(au, bidx, 0).add("_param_pass");
+                                let a_name = self.local_name(au, bidx, 0).add("_param_pass");
                                 let a_new_bv = ast::BV::new_const(ctx, a_name, alen as u32);
                                 let update_a = a_new_bv._safe_eq(&a_ori_bv).unwrap();
 
@@ -2022,7 +2976,7 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
                                 let a_ori_non_owing = a_ori_bv._safe_eq(&a_zero_const).unwrap();
 
                                 // this is for a'=a
-                                let a_name = new_local_name(au, bidx, 0).add("_param_pass");
+                                let a_name = self.local_name(au, bidx, 0).add("_param_pass");
                                 let a_new_bv = ast::BV::new_const(ctx, a_name, alen as u32);
                                 let update_a = a_new_bv._safe_eq(&a_ori_bv).unwrap();
 
@@ -2044,7 +2998,7 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
                                     }
                                 }
 
-                                let a_name = new_local_name(au, bidx, 0).add("_param_pass");
+                                let a_name = self.local_name(au, bidx, 0).add("_param_pass");
                                 let a_new_bv = ast::BV::new_const(ctx, a_name, alen as u32);
                                 let update_a = a_new_bv._safe_eq(&a_ori_bv).unwrap();
 
@@ -2054,7 +3008,7 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
                         },
                         1 => {
                             // this indicates that the operand is move without projection
-                            let a_name = new_local_name(au, bidx, 0).add("_param_pass");
+                            let a_name = self.local_name(au, bidx, 0).add("_param_pass");
                             let a_new_bv = ast::BV::new_const(ctx, a_name, alen as u32);
                             let update_a = a_new_bv._safe_eq(&a_ori_bv).unwrap();
 
@@ -2142,9 +3096,9 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
                 llen = return_value_layout.layout().len();
 
                 let l_name = if is_ctor {
-                    new_local_name(lu, bidx, 0).add("_ctor_fn")
+                    self.local_name(lu, bidx, 0).add("_ctor_fn")
                 } else {
-                    new_local_name(lu, bidx, 0).add("_cover_fn")
+                    self.local_name(lu, bidx, 0).add("_cover_fn")
                 };
 
                 let l_layout_bv = ast::BV::from_u64(ctx, int_for_gen, llen as u32);
@@ -2182,7 +3136,7 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
                     goal.assert(&constraint_l_f_ori_zero);
                     solver.assert(&constraint_l_f_ori_zero);
                 } else {
-                    let l_ori_name_ctor = new_local_name(lu, bidx, 0).add("_ctor_fn");
+                    let l_ori_name_ctor = self.local_name(lu, bidx, 0).add("_ctor_fn");
                     let l_ori_bv_ctor = ast::BV::new_const(ctx, l_ori_name_ctor, llen as u32);
                     let l_ori_zero = ast::BV::from_u64(ctx, 0, llen as u32);
                     let constraint_l_ctor_zero = l_ori_bv_ctor._safe_eq(&l_ori_zero).unwrap();
@@ -2195,7 +3149,7 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
                     self.icx_slice_mut().layout_mut()[lu] = return_value_layout.layout().clone();
                 }
 
-                let l_name = new_local_name(lu, bidx, 0);
+                let l_name = self.local_name(lu, bidx, 0);
                 let l_new_bv = ast::BV::new_const(ctx, l_name, llen as u32);
 
                 let update_field = if source_flag {
@@ -2234,36 +3188,222 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         }
     }
 
+    // the container owns the buffer allocated by with_capacity or reserve even if it holds no element, it is
+    // tainted to keep track of the buffer, while a raw pointer taken from it (e.g., as_mut_ptr) hands the buffer out
+    // reserve and shrink_to_fit reallocate the buffer, the container owns the new allocation in place of the old one
+    fn handle_capacity(&mut self, func: &Operand<'tcx>, args: &Vec<Operand<'tcx>>, dest: &Place<'tcx>, bidx: usize, span: Span) {
+        let kind = match func {
+            Operand::Constant(constant) => match constant.literal.ty().kind() {
+                ty::FnDef(id, ..) => type_analysis::capacity_fn_kind(self.tcx(), *id),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        let owner = match kind {
+            Some(CapacityFnKind::WithCapacity) if dest.projection.is_empty() => Some(dest.local),
+            Some(CapacityFnKind::Reserve | CapacityFnKind::Shrink) => match args.get(0).and_then(|arg| arg.place()) {
+                Some(aplace) => self.pointee_local(bidx, &aplace),
+                None => None,
+            },
+            _ => None,
+        };
+        if let Some(owner) = owner {
+            let ou = owner.as_usize();
+            let owner_ty = self.body().local_decls[owner].ty;
+            let origin = (self.program_point(bidx, usize::MAX), span);
+            let alloc = self.alloc_id(bidx, usize::MAX, span);
+            let source = self.taint_source(ou);
+            // the old allocation is freed by the method, the pointers into it are dangling
+            if kind != Some(CapacityFnKind::WithCapacity) {
+                if self.rcx().config().use_after_free() {
+                    self.mark_freed(owner, span);
+                }
+                let taint = &mut self.icx_slice_mut().taint_mut()[ou];
+                taint.allocs_mut().clear();
+                taint.set_freed(None);
+            }
+            self.taint_flag = true;
+            self.icx_slice_mut().taint_mut()[ou].insert_with_origin(TyWithIndex::new(owner_ty, None), origin, alloc, source);
+            // the shrunk buffer is owned as long as there is any element, it is not allocated ahead of them
+            if kind != Some(CapacityFnKind::Shrink) {
+                self.icx_slice_mut().taint_mut()[ou].set_capacity(Some(span));
+            }
+            return;
+        }
+
+        if !dest.ty(&self.body().local_decls, self.tcx()).ty.is_unsafe_ptr() {
+            return;
+        }
+        for arg in args {
+            let local = match arg.place() {
+                Some(aplace) => self.pointee_local(bidx, &aplace),
+                None => None,
+            };
+            if let Some(local) = local {
+                self.icx_slice_mut().taint_mut()[local.as_usize()].set_capacity(None);
+            }
+        }
+    }
+
+    // analyze the leaf callee with at most `inline_threshold` blocks again, seeded by the taint of the args at
+    // this call site, it returns the summary in this context, e.g., the returned pointer carries the taint of
+    // the arg flowing into it, while the summary computed in isolation only knows the taint inside the callee
+    fn inline_summary(&self, func: &Operand<'tcx>, args: &[Operand<'tcx>]) -> Option<FnSummary<'tcx>> {
+        let threshold = self.rcx().config().inline_threshold();
+        if threshold == 0 || self.is_inlined() {
+            return None;
+        }
+        let callee = match func {
+            Operand::Constant(constant) => match constant.literal.ty().kind() {
+                ty::FnDef(id, ..) => *id,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        if callee == self.did()
+            || !self.rcx().mir_graph().contains_key(&callee)
+            || !self.rcx().call_graph().contains(&callee)
+            || !self.rcx().call_graph().callees(&callee).is_empty() {
+            return None;
+        }
+        let body = mir_body(self.tcx(), callee);
+        if body.basic_blocks().len() > threshold || body.arg_count != args.len() {
+            return None;
+        }
+
+        let param_taints: Vec<Taint<'tcx>> = args
+            .iter()
+            .map(|arg| match arg.place() {
+                Some(aplace) if aplace.projection.is_empty() => self.icx_slice().taint()[aplace.local.as_usize()].clone(),
+                _ => Taint::default(),
+            })
+            .collect();
+        if param_taints.iter().all(|taint| taint.is_untainted()) {
+            return None;
+        }
+
+        let sw = Stopwatch::start_new();
+        let mut unique = Unique::default();
+        let mut cfg = z3::Config::new();
+        cfg.set_timeout_msec(self.rcx().config().z3_timeout());
+        let ctx = z3::Context::new(&cfg);
+        let goal = z3::Goal::new(&ctx, true, false, false);
+        let solver = z3::Solver::new(&ctx);
+
+        let mut inline_visitor = IntroFlowAnalysis::new(self.rcx(), callee, &mut unique)
+            .with_param_taints(param_taints);
+        inline_visitor.visit_body(&ctx, &goal, &solver, body, &sw);
+        Some(inline_visitor.summary())
+    }
+
+    // the unknown callee is assumed to take over the ownership of the tainted raw pointers passed to it
+    fn give_away_tainted_args(&mut self, args: &[Operand<'tcx>], recovery_flag: &mut (bool, Vec<usize>)) {
+        for arg in args {
+            match arg.place() {
+                Some(aplace) if aplace.projection.is_empty() => {
+                    let au = aplace.local.as_usize();
+                    if self.icx_slice().taint()[au].is_untainted() || recovery_flag.1.contains(&au) {
+                        continue;
+                    }
+                    recovery_flag.0 = true;
+                    recovery_flag.1.push(au);
+                    if let Some(param) = self.param_alias[au] {
+                        self.consumed_params[param] = true;
+                    }
+                },
+                _ => (),
+            }
+        }
+    }
+
+    fn is_process_exit_call(&self, func: &Operand<'tcx>) -> bool {
+        match func {
+            Operand::Constant(constant) => match constant.literal.ty().kind() {
+                ty::FnDef(id, ..) => type_analysis::is_process_exit_fn(self.tcx(), *id),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    // the process is terminated by exit or abort without running any destructor, the ownership held by
+    // the locals live at the call is given up deliberately, thus they are viewed as freed and untainted
+    // to avoid the findings on the unwinding path after it
+    pub(crate) fn handle_process_exit(
+        &mut self,
+        ctx: &'ctx z3::Context,
+        goal: &'ctx z3::Goal<'ctx>,
+        solver: &'ctx z3::Solver<'ctx>,
+        bidx: usize,
+    ) {
+        for iidx in 0..self.icx_slice().len().len() {
+            let place = Place::from(Local::from_usize(iidx));
+            self.handle_drop(ctx, goal, solver, &place, bidx, true);
+            self.icx_slice_mut().taint_mut()[iidx] = Taint::default();
+        }
+    }
+
     pub(crate) fn handle_return(
         &mut self,
         ctx: &'ctx z3::Context,
         goal: &'ctx z3::Goal<'ctx>,
         solver: &'ctx z3::Solver<'ctx>,
-        sw: &Stopwatch,
         bidx: usize,
+        kind: ExitKind,
     ) {
 
-        let place_0 = Place::from(Local::from_usize(0));
-        self.handle_drop(
-            ctx,
-            goal,
-            solver,
-            &place_0,
-            bidx,
-            false,
-        );
+        // the return value is not produced if the function is unwinding
+        if kind == ExitKind::Return {
+            if self.rcx().config().use_after_free() {
+                self.check_returned_ref();
+            }
+            let place_0 = Place::from(Local::from_usize(0));
+            self.handle_drop(
+                ctx,
+                goal,
+                solver,
+                &place_0,
+                bidx,
+                false,
+            );
+        }
+
+        let mut constraints = Vec::new();
+
+        // the env of a closure taken by value owns the captured upvars, which should be freed in the closure
+        let env_owned = self.body().arg_count > 0 && closure_def_id(self.body().local_decls[Local::from_usize(1)].ty) == Some(self.did());
+        // the ownership handed to C by the raw pointer returned from an exported function is reported in the strict
+        // ffi mode, thus the return value and the locals flowing into it are checked as usual
+        let ffi_strict = kind == ExitKind::Return
+            && !self.rcx().config().ffi().is_friendly()
+            && self.body().local_decls[Local::from_usize(0)].ty.is_unsafe_ptr()
+            && is_ffi_exported(self.tcx(), self.did());
+
+        // the ownership held by the escaping locals leaves the function with the return value,
+        // while nothing escapes if the function is unwinding
+        if kind == ExitKind::Return {
+            let taint = self.icx_slice().taint()[0].clone();
+            self.ret_taint.merge(&taint);
+            for (iidx, escaping) in self.escaping.clone().into_iter().enumerate() {
+                if escaping && !ffi_strict {
+                    self.icx_slice_mut().taint_mut()[iidx] = Taint::default();
+                }
+            }
+        }
 
         // when whole function return => we need to check every variable is freed
         for (iidx, var) in self.icx_slice().var.iter().enumerate() {
             let len = self.icx_slice().len()[iidx];
             if len == 0 { continue; }
-            if iidx <= self.body().arg_count { continue; }
+            if iidx <= self.body().arg_count && !(iidx == 1 && env_owned) && !(iidx == 0 && ffi_strict) { continue; }
+            if kind == ExitKind::Return && self.escaping[iidx] && !ffi_strict { continue; }
 
             if var.is_init() {
 
                 let var_ori_bv = var.extract();
 
-                let return_name = new_local_name(iidx, bidx, 0).add("_return");
+                let return_name = self.local_name(iidx, bidx, 0).add("_return");
                 let var_return_bv = ast::BV::new_const(ctx, return_name, len as u32);
 
                 let zero_const = ast::BV::from_u64(ctx, 0, len as u32);
@@ -2275,39 +3415,862 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
                 let constraint_return = ast::Bool::and(ctx, args);
 
                 goal.assert(&constraint_return);
-                solver.assert(&constraint_return);
+                constraints.push((iidx, constraint_return));
             }
         }
 
+        // the constraints are not asserted to the solver here
+        // each kind of exit is checked separately to tell the leak on unwind from the one on return
+        self.exits.push((kind, bidx, constraints));
+
+    }
+
+    // the constraints of all exit blocks (in every round) are collected
+    // and they are checked only once after the fixpoint iteration of the body converges
+    pub(crate) fn solve_for_body(
+        &mut self,
+        goal: &'ctx z3::Goal<'ctx>,
+        solver: &'ctx z3::Solver<'ctx>,
+        sw: &Stopwatch,
+    ) {
         let sec_build = sw.elapsed_ms();
 
-        let result = solver.check();
-        let model = solver.get_model();
+        for kind in [ExitKind::Return, ExitKind::Unwind] {
+            let exit_block = match self.exits.iter().find(|(k, ..)| *k == kind) {
+                Some((_, bidx, _)) => *bidx,
+                None => continue,
+            };
+
+            solver.push();
+            for (.., constraints) in self.exits.iter().filter(|(k, ..)| *k == kind) {
+                for (_, constraint) in constraints {
+                    solver.assert(constraint);
+                }
+            }
+            if let Some(dir) = self.rcx().config().smt2_path() {
+                self.dump_smt2(dir, kind, solver);
+            }
+            let result = self.check_sat(solver);
+            solver.pop(1);
+
+            if !self.taint_flag {
+                continue;
+            }
+
+            match result {
+                z3::SatResult::Unsat => {
+                    self.report_leak(solver, kind, exit_block);
+                },
+                z3::SatResult::Unknown => {
+                    // the solver gives up (e.g., timeout), it is neither safe nor a leak
+                    let reason = solver.get_reason_unknown().unwrap_or_else(|| "unknown".to_string());
+                    self.add_approximation(Approximation::Z3Unknown, self.body().span);
+                    self.report_unknown(kind, reason);
+                },
+                z3::SatResult::Sat => (),
+            }
+        }
 
         let sec_solve = sw.elapsed_ms() - sec_build;
 
         self.add_time_build(sec_build);
         self.add_time_solve(sec_solve);
 
-        // if is_z3_goal_verbose() {
-        //     let g = format!("{}", goal);
-        //     println!("{}\n", g.color(Color::LightGray).bold());
-        //     if model.is_some() {
-        //         println!("{}", format!("{}", model.unwrap()).color(Color::LightCyan).bold());
-        //     }
-        // }
+        if self.rcx().config().z3_goal_display().is_verbose() {
+            let g = format!("{}", goal);
+            match self.rcx().config().z3_goal_path() {
+                Some(path) => self.dump_goal(path, &g),
+                None => println!("{}\n", g.color(Color::LightGray).bold()),
+            }
+        }
 
         // println!("{}", self.body().local_decls.display());
         // println!("{}", self.body().basic_blocks().display());
-        // let g = format!("{}", goal);
-        // println!("{}\n", g.color(Color::LightGray).bold());
+    }
+
+    // the ownership summary of this function for its callers
+    pub(crate) fn summary(&mut self) -> FnSummary<'tcx> {
+        let arg_count = self.body().arg_count;
+        let escaping = self.escaping[1..=arg_count].to_vec();
+        let ret_ty = self.body().local_decls[Local::from_usize(0)].ty;
+        let owned_return = self.extract_default_ty_layout(ret_ty, None).is_owned();
+        FnSummary::new(self.consumed_params.clone(), escaping, owned_return)
+            .with_ret_taint(self.ret_taint.clone())
+    }
+
+    // record the params whose pointee ownership is taken back by the call, i.e., the pointer is passed to
+    // a fn(*) -> Owned (like Box::from_raw) or to a callee taking over the ownership by its summary
+    fn record_consumed_params(&mut self, args: &[Operand<'tcx>], dest: &Place<'tcx>, summary: Option<&FnSummary<'tcx>>) {
+        let dest_ty = dest.ty(&self.body().local_decls, self.tcx());
+        let dest_owned = !dest_ty.ty.is_unsafe_ptr() && self.extract_default_ty_layout(dest_ty.ty, dest_ty.variant_index).is_owned();
+        for (k, arg) in args.iter().enumerate() {
+            let local = match arg.place() {
+                Some(place) if place.projection.is_empty() => place.local,
+                _ => continue,
+            };
+            let param = match self.param_alias[local.as_usize()] {
+                Some(param) => param,
+                None => continue,
+            };
+            if !self.body().local_decls[local].ty.is_unsafe_ptr() {
+                continue;
+            }
+            if dest_owned || summary.map_or(false, |s| s.takes_ownership(k)) {
+                self.consumed_params[param] = true;
+            }
+        }
+    }
+
+    // mark the local as consumed by the drop, or report it if it has been consumed on this path
+    pub(crate) fn check_double_free(&mut self, local: Local, span: Span) {
+        let u = local.as_usize();
+        if self.icx_slice().len()[u] == 0 || !self.icx_slice().var()[u].is_init() {
+            return;
+        }
+
+        let first = match self.icx_slice().taint()[u].consumed() {
+            Some(first) => first,
+            None => {
+                self.icx_slice_mut().taint_mut()[u].set_consumed(Some(span));
+                return;
+            },
+        };
+
+        // the block may be re-visited in the fixpoint iteration
+        if self.reports.iter().any(|r| r.kind() == ReportKind::DoubleFree && r.span() == span && r.local() == Some(local)) {
+            return;
+        }
+
+        let fn_name = self.tcx().def_path_str(self.did());
+        let message = format!(
+            "The heap item held by `{}` is dropped twice in `{}`",
+            local_debug_name(self.body(), local),
+            fn_name,
+        );
+        let layout = self.icx_slice().layout()[u].clone();
+        self.reports.push(
+            LeakReport::new(self.did(), span, Some(local), layout, message)
+                .with_kind(ReportKind::DoubleFree)
+                .with_origin(Some(first))
+        );
+    }
+
+    // report the tainted local that must own the heap item from one pre node and must not from another one
+    // (e.g., the ownership is recovered and dropped only in one branch), the join loses track of it on a path
+    pub(crate) fn check_divergent_ownership(
+        &mut self,
+        ctx: &'ctx z3::Context,
+        solver: &'ctx z3::Solver<'ctx>,
+        bidx: usize,
+        pre: &[usize],
+        phi_vars: &[usize],
+    ) {
+        let data = &self.body().basic_blocks()[BasicBlock::from(bidx)];
+        let span = data.statements.first().map_or(data.terminator().source_info.span, |stmt| stmt.source_info.span);
+
+        for u in phi_vars.iter().copied() {
+            if self.icx_slice().taint()[u].is_untainted() {
+                continue;
+            }
+            let local = Local::from_usize(u);
+            // the block may be re-visited in the fixpoint iteration
+            if self.reports.iter().any(|r| r.kind() == ReportKind::DivergentOwnership && r.span() == span && r.local() == Some(local)) {
+                continue;
+            }
+
+            let len = self.icx_slice().len()[u];
+            let zero_const = ast::BV::from_u64(ctx, 0, len as u32);
+            let mut owned = None;
+            let mut unowned = None;
+            for pidx in pre.iter().copied() {
+                let var = &self.icx().var().get_g()[pidx].get_o()[u];
+                if !var.is_init() {
+                    continue;
+                }
+                let is_zero = var.extract()._safe_eq(&zero_const).unwrap();
+                if owned.is_none() && self.is_valid(solver, &is_zero.not()) {
+                    owned = Some(pidx);
+                } else if unowned.is_none() && self.is_valid(solver, &is_zero) {
+                    unowned = Some(pidx);
+                }
+            }
+            let (owned, unowned) = match (owned, unowned) {
+                (Some(owned), Some(unowned)) => (owned, unowned),
+                _ => continue,
+            };
+
+            let message = format!(
+                "The ownership of `{}` diverges at {:?} in `{}`: it is owned from {:?} but not from {:?}",
+                local_debug_name(self.body(), local),
+                self.graph().mir_bb(bidx),
+                self.tcx().def_path_str(self.did()),
+                self.graph().mir_bb(owned),
+                self.graph().mir_bb(unowned),
+            );
+            let layout = self.icx_slice().layout()[u].clone();
+            let origin = self.icx_slice().taint()[u].origin();
+            self.reports.push(
+                LeakReport::new(self.did(), span, Some(local), layout, message)
+                    .with_kind(ReportKind::DivergentOwnership)
+                    .with_origin(origin)
+                    .with_approximations(self.inherited_approximations(bidx))
+            );
+        }
+    }
+
+    // the heap item taken over from the local is handed in by the caller if the local is (copied from) a param
+    fn taint_source(&self, local: usize) -> TaintSource {
+        match self.param_alias[local] {
+            Some(_) => TaintSource::Param,
+            None => TaintSource::Local,
+        }
+    }
+
+    // each query of z3 is counted and timed for the statistics, and traced in its own span
+    fn check_sat(&mut self, solver: &'ctx z3::Solver<'ctx>) -> z3::SatResult {
+        let _span = tracing::trace_span!("z3_check").entered();
+        let sw = Stopwatch::start_new();
+        let result = solver.check();
+        self.time_z3 += sw.elapsed_ms();
+        self.queries += 1;
+        result
+    }
+
+    // the condition holds in every model of the current constraints
+    fn is_valid(&mut self, solver: &'ctx z3::Solver<'ctx>, cond: &ast::Bool<'ctx>) -> bool {
+        solver.push();
+        solver.assert(&cond.not());
+        let result = self.check_sat(solver);
+        solver.pop(1);
+        result == z3::SatResult::Unsat
+    }
+
+    // the allocations owned by the dropped local are freed, thus all the pointers to them are dangling
+    pub(crate) fn mark_freed(&mut self, local: Local, span: Span) {
+        let allocs = self.icx_slice().taint()[local.as_usize()].allocs().clone();
+        if allocs.is_empty() {
+            return;
+        }
+        for taint in self.icx_slice_mut().taint_mut().iter_mut() {
+            if taint.freed().is_none() && !taint.allocs().is_disjoint(&allocs) {
+                taint.set_freed(Some(span));
+            }
+        }
+    }
+
+    // the box whose heap item the place is in, e.g., `(*_1).0` with `_1: Box<T>`, or the box a reference
+    // points into if the place is reborrowed through the reference, e.g., `(*_3)` with `_3 = &(*_1)`
+    fn place_provenance(&self, place: &Place<'tcx>) -> Option<Local> {
+        if place.projection.first() != Some(&ProjectionElem::Deref) {
+            return None;
+        }
+        if self.body().local_decls[place.local].ty.is_box() {
+            Some(place.local)
+        } else {
+            self.ref_provenance[place.local.as_usize()]
+        }
+    }
+
+    // trace the reference (or raw pointer) assigned as a whole back to the box it points into
+    pub(crate) fn handle_ref_provenance(&mut self, place: &Place<'tcx>, rvalue: &Rvalue<'tcx>) {
+        if !self.rcx().config().use_after_free() || !place.projection.is_empty() {
+            return;
+        }
+        let provenance = match rvalue {
+            Rvalue::Ref(_, _, rplace) | Rvalue::AddressOf(_, rplace) => self.place_provenance(rplace),
+            Rvalue::Use(Operand::Move(rplace) | Operand::Copy(rplace))
+            | Rvalue::Cast(_, Operand::Move(rplace) | Operand::Copy(rplace), _) if rplace.projection.is_empty() => {
+                self.ref_provenance[rplace.local.as_usize()]
+            },
+            _ => None,
+        };
+        self.ref_provenance[place.local.as_usize()] = provenance;
+    }
+
+    // the reference laundered by transmute (e.g., to extend its lifetime) still points into the same box
+    pub(crate) fn handle_transmute_provenance(&mut self, func: &Operand<'tcx>, args: &[Operand<'tcx>], dest: &Place<'tcx>) {
+        if !self.rcx().config().use_after_free() || !dest.projection.is_empty() {
+            return;
+        }
+        let transmute = match func {
+            Operand::Constant(constant) => match constant.literal.ty().kind() {
+                ty::FnDef(id, ..) => self.tcx().is_diagnostic_item(sym::transmute, *id),
+                _ => false,
+            },
+            _ => false,
+        };
+        let provenance = match args.get(0) {
+            Some(Operand::Move(aplace) | Operand::Copy(aplace)) if transmute && aplace.projection.is_empty() => {
+                self.ref_provenance[aplace.local.as_usize()]
+            },
+            _ => None,
+        };
+        self.ref_provenance[dest.local.as_usize()] = provenance;
+    }
+
+    // the heap item of the dropped box is freed on this path, it is recorded only if a reference points into it
+    pub(crate) fn mark_box_freed(&mut self, place: &Place<'tcx>, span: Span) {
+        if !place.projection.is_empty() || !self.ref_provenance.contains(&Some(place.local)) {
+            return;
+        }
+        let taint = &mut self.icx_slice_mut().taint_mut()[place.local.as_usize()];
+        if taint.freed().is_none() {
+            taint.set_freed(Some(span));
+        }
+    }
+
+    // report the returned reference pointing into a box that is dropped before the return on this path, the
+    // borrow checker rejects it unless the lifetime of the reference is laundered, e.g., by transmute
+    pub(crate) fn check_returned_ref(&mut self) {
+        if !self.body().local_decls[Local::from_usize(0)].ty.is_ref() {
+            return;
+        }
+        let boxed = match self.ref_provenance[0] {
+            Some(boxed) => boxed,
+            None => return,
+        };
+        let freed = match self.icx_slice().taint()[boxed.as_usize()].freed() {
+            Some(freed) => freed,
+            None => return,
+        };
+        let span = self.body().span;
+        let local = Local::from_usize(0);
+        // the block may be re-visited in the fixpoint iteration
+        if self.reports.iter().any(|r| r.kind() == ReportKind::UseAfterFree && r.span() == span && r.local() == Some(local)) {
+            return;
+        }
+
+        let message = format!(
+            "The reference returned by `{}` points into the heap item of `{}` that is freed before the return",
+            self.tcx().def_path_str(self.did()),
+            local_debug_name(self.body(), boxed),
+        );
+        self.reports.push(
+            LeakReport::new(self.did(), span, Some(local), OwnershipLayout::default(), message)
+                .with_kind(ReportKind::UseAfterFree)
+                .with_origin(Some(freed))
+        );
+    }
+
+    // report the dereference of a raw pointer whose allocation is freed on this path
+    pub(crate) fn check_use_after_free(&mut self, lplace: &Place<'tcx>, rvalue: &Rvalue<'tcx>, span: Span) {
+        let mut places: Vec<Place<'tcx>> = vec![ *lplace ];
+        match rvalue {
+            Rvalue::Use(op)
+            | Rvalue::Repeat(op, _)
+            | Rvalue::Cast(_, op, _)
+            | Rvalue::UnaryOp(_, op)
+            | Rvalue::ShallowInitBox(op, _) => places.extend(op.place()),
+            Rvalue::BinaryOp(_, box(op1, op2))
+            | Rvalue::CheckedBinaryOp(_, box(op1, op2)) => {
+                places.extend(op1.place());
+                places.extend(op2.place());
+            },
+            Rvalue::Aggregate(_, ops) => places.extend(ops.iter().filter_map(|op| op.place())),
+            Rvalue::Ref(_, _, place)
+            | Rvalue::Len(place)
+            | Rvalue::Discriminant(place)
+            | Rvalue::CopyForDeref(place) => places.push(*place),
+            Rvalue::ThreadLocalRef(..)
+            | Rvalue::AddressOf(..)
+            | Rvalue::NullaryOp(..) => (),
+        }
 
+        for place in places {
+            if place.projection.first() != Some(&ProjectionElem::Deref) {
+                continue;
+            }
+            let local = place.local;
+            if !self.body().local_decls[local].ty.is_unsafe_ptr() {
+                continue;
+            }
+            let freed = match self.icx_slice().taint()[local.as_usize()].freed() {
+                Some(freed) => freed,
+                None => continue,
+            };
+            // the block may be re-visited in the fixpoint iteration
+            if self.reports.iter().any(|r| r.kind() == ReportKind::UseAfterFree && r.span() == span && r.local() == Some(local)) {
+                continue;
+            }
+
+            let message = format!(
+                "The raw pointer `{}` is dereferenced after its heap item is freed in `{}`",
+                local_debug_name(self.body(), local),
+                self.tcx().def_path_str(self.did()),
+            );
+            self.reports.push(
+                LeakReport::new(self.did(), span, Some(local), OwnershipLayout::default(), message)
+                    .with_kind(ReportKind::UseAfterFree)
+                    .with_origin(Some(freed))
+            );
+        }
+    }
+
+    // mem::replace(&mut a, b), mem::take(&mut a) and Option::take(&mut a) move the old value of `a` out,
+    // thus the ownership held by the raw pointers in `a` belongs to the returned value now, and it is a leak
+    // if the returned value is ignored without recovering the ownership. The `a` holds the new value `b`,
+    // or nothing tracked for the default (or None) value.
+    fn handle_replace(&mut self, func: &Operand<'tcx>, args: &[Operand<'tcx>], dest: &Place<'tcx>, bidx: usize) {
+        let id = match func {
+            Operand::Constant(constant) => match constant.literal.ty().kind() {
+                ty::FnDef(id, ..) => *id,
+                _ => return,
+            },
+            _ => return,
+        };
+        if !dest.projection.is_empty() || !type_analysis::is_replace_fn(self.tcx(), id) {
+            return;
+        }
+
+        let target = match args.get(0) {
+            Some(Operand::Move(aplace) | Operand::Copy(aplace)) => self.pointee_local(bidx, aplace),
+            _ => None,
+        };
+        let tu = match target {
+            Some(local) => local.as_usize(),
+            None => return,
+        };
+        let target_ty = self.body().local_decls[Local::from_usize(tu)].ty;
+        if !is_place_containing_ptr(&target_ty) || self.icx_slice().taint()[tu].is_untainted() {
+            return;
+        }
+
+        let lu = dest.local.as_usize();
+        self.copy_local_state(tu, lu);
+
+        match args.get(1) {
+            Some(Operand::Move(aplace) | Operand::Copy(aplace)) if aplace.projection.is_empty() => {
+                self.copy_local_state(aplace.local.as_usize(), tu);
+            },
+            _ => {
+                self.icx_slice_mut().taint_mut()[tu] = Taint::default();
+                self.icx_slice_mut().var_mut()[tu] = IntroVar::Declared;
+                self.icx_slice_mut().len_mut()[tu] = 0;
+                self.icx_slice_mut().ty_mut()[tu] = TyWithIndex::default();
+                self.icx_slice_mut().layout_mut()[tu] = OwnershipLayout::default();
+            },
+        }
+    }
+
+    // The pointee overwritten by ptr::write is not dropped, the heap item it may still own leaks, e.g.,
+    // `ptr::write(&mut b, Box::new(2))` where b owns a Box. The pointee turns to hold the written value.
+    // The MaybeUninit (or ManuallyDrop) pointee owns nothing and the pointee dropped in place is zeroed before.
+    pub(crate) fn handle_ptr_write(
+        &mut self,
+        ctx: &'ctx z3::Context,
+        solver: &'ctx z3::Solver<'ctx>,
+        func: &Operand<'tcx>,
+        args: &[Operand<'tcx>],
+        bidx: usize,
+        span: Span,
+    ) {
+        let id = match func {
+            Operand::Constant(constant) => match constant.literal.ty().kind() {
+                ty::FnDef(id, ..) => *id,
+                _ => return,
+            },
+            _ => return,
+        };
+        if !type_analysis::is_ptr_write_fn(self.tcx(), id) {
+            return;
+        }
+
+        let target = match args.get(0) {
+            Some(Operand::Move(aplace) | Operand::Copy(aplace)) => self.pointee_local(bidx, aplace),
+            _ => None,
+        };
+        let local = match target {
+            Some(local) => local,
+            None => return,
+        };
+        let tu = local.as_usize();
+
+        let len = self.icx_slice().len()[tu];
+        if len != 0 && self.icx_slice().var()[tu].is_init() {
+            let zero_const = ast::BV::from_u64(ctx, 0, len as u32);
+            let is_zero = self.icx_slice().var()[tu].extract()._safe_eq(&zero_const).unwrap();
+            let reported = self.reports.iter().any(|r| r.span() == span && r.local() == Some(local));
+            if !reported && !self.is_valid(solver, &is_zero) {
+                let message = format!(
+                    "The heap item owned by `{}` is overwritten by ptr::write without being dropped in `{}`",
+                    local_debug_name(self.body(), local),
+                    self.tcx().def_path_str(self.did()),
+                );
+                let layout = self.icx_slice().layout()[tu].clone();
+                self.reports.push(
+                    LeakReport::new(self.did(), span, Some(local), layout, message)
+                        .with_approximations(self.path_approximations(bidx).clone())
+                );
+            }
+        }
+
+        match args.get(1) {
+            Some(Operand::Move(aplace) | Operand::Copy(aplace)) if aplace.projection.is_empty() => {
+                self.copy_local_state(aplace.local.as_usize(), tu);
+            },
+            _ => (),
+        }
+    }
+
+    // `for b in v` desugars to `IntoIterator::into_iter(move v)` and `Iterator::next(&mut iter)` in the loop.
+    // The iterator takes over the elements of the owned container (the container itself is consumed by the move),
+    // thus it owns what the container owned until it is drained by `next` (the element in Some is owned by the
+    // caller) and dropped. The iteration by reference (e.g., `for b in &v` over slice::Iter) moves nothing.
+    // Return the state of the container before the call consumes it.
+    fn drained_container(&self, func: &Operand<'tcx>, args: &[Operand<'tcx>]) -> Option<LocalState<'tcx, 'ctx>> {
+        let id = match func {
+            Operand::Constant(constant) => match constant.literal.ty().kind() {
+                ty::FnDef(id, ..) => *id,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        if self.tcx().lang_items().into_iter_fn() != Some(id) {
+            return None;
+        }
+
+        let aplace = match args.get(0) {
+            Some(Operand::Move(aplace)) if aplace.projection.is_empty() => aplace,
+            _ => return None,
+        };
+        let au = aplace.local.as_usize();
+        let ty = self.body().local_decls[aplace.local].ty;
+        if ContainerKind::from_ty(self.tcx(), ty).is_none() || !self.icx_slice().var()[au].is_init() {
+            return None;
+        }
+        Some(self.local_state(au))
+    }
+
+    // the iterator returned by into_iter owns the elements of the drained container
+    fn handle_into_iter(&mut self, drained: Option<LocalState<'tcx, 'ctx>>, dest: &Place<'tcx>) {
+        if let Some(state) = drained {
+            if dest.projection.is_empty() {
+                self.set_local_state(dest.local.as_usize(), state);
+            }
+        }
+    }
+
+    fn local_state(&self, u: usize) -> LocalState<'tcx, 'ctx> {
+        (
+            self.icx_slice().taint()[u].clone(),
+            self.icx_slice().var()[u].clone(),
+            self.icx_slice().len()[u],
+            self.icx_slice().ty()[u].clone(),
+            self.icx_slice().layout()[u].clone(),
+        )
+    }
+
+    fn set_local_state(&mut self, u: usize, state: LocalState<'tcx, 'ctx>) {
+        let (taint, var, len, ty, layout) = state;
+        self.icx_slice_mut().taint_mut()[u] = taint;
+        self.icx_slice_mut().var_mut()[u] = var;
+        self.icx_slice_mut().len_mut()[u] = len;
+        self.icx_slice_mut().ty_mut()[u] = ty;
+        self.icx_slice_mut().layout_mut()[u] = layout;
+    }
+
+    // copy the whole state of a local to another, e.g., the ownership moved between them
+    fn copy_local_state(&mut self, from: usize, to: usize) {
+        let state = self.local_state(from);
+        self.set_local_state(to, state);
+    }
+
+    // find the local that the pointer refers to by the statements of this block,
+    // e.g., `_3 = &mut _1; _2 = &raw mut (*_3);` gives `_1` for `_2`
+    fn pointee_local(&self, bidx: usize, ptr: &Place<'tcx>) -> Option<Local> {
+        if !ptr.projection.is_empty() {
+            return None;
+        }
+        let mut target = ptr.local;
+        let data = &self.body().basic_blocks()[BasicBlock::from(bidx)];
+        for stmt in data.statements.iter().rev() {
+            let (lplace, rvalue) = match &stmt.kind {
+                StatementKind::Assign(box(lplace, rvalue)) => (lplace, rvalue),
+                _ => continue,
+            };
+            if lplace.local != target || !lplace.projection.is_empty() {
+                continue;
+            }
+            let rplace = match rvalue {
+                Rvalue::Ref(_, _, rplace) | Rvalue::AddressOf(_, rplace) => rplace,
+                Rvalue::Use(Operand::Move(rplace) | Operand::Copy(rplace))
+                | Rvalue::Cast(_, Operand::Move(rplace) | Operand::Copy(rplace), _) => {
+                    if !rplace.projection.is_empty() {
+                        return None;
+                    }
+                    target = rplace.local;
+                    continue;
+                },
+                _ => return None,
+            };
+            match rplace.as_ref().projection {
+                [] => return Some(rplace.local),
+                [ProjectionElem::Deref] => target = rplace.local,
+                _ => return None,
+            }
+        }
+        None
+    }
+
+    // the union of the approximations on the paths reaching the exits of the kind
+    fn exit_approximations(&self, kind: ExitKind) -> Vec<(Approximation, Span)> {
+        let mut approximations: Vec<(Approximation, Span)> = Vec::new();
+        for (_, bidx, _) in self.exits.iter().filter(|(k, ..)| *k == kind) {
+            for approximation in self.path_approximations(*bidx) {
+                if !approximations.contains(approximation) {
+                    approximations.push(*approximation);
+                }
+            }
+        }
+        approximations
+    }
+
+    pub(crate) fn report_unknown(&mut self, kind: ExitKind, reason: String) {
+        let span = match self.taint_span {
+            Some(span) => span,
+            None => self.body().span,
+        };
+        let message = format!(
+            "Could not prove or disprove the leak on {} of `{}` (z3: {})",
+            kind,
+            self.tcx().def_path_str(self.did()),
+            reason,
+        );
+        self.reports.push(
+            LeakReport::new(self.did(), span, None, OwnershipLayout::default(), message)
+                .with_kind(ReportKind::Unknown)
+                .with_unwind(kind == ExitKind::Unwind)
+                .with_virtual_calls(self.virtual_calls.clone())
+                .with_approximations(self.exit_approximations(kind))
+        );
+    }
+
+    // find the locals that can not be freed at the exit by checking their constraints one by one
+    // and generate the leak report for each of them
+    pub(crate) fn report_leak(
+        &mut self,
+        solver: &'ctx z3::Solver<'ctx>,
+        kind: ExitKind,
+        exit_block: usize,
+    ) {
+        let mut leaked:Vec<(usize, usize)> = Vec::new();
+        let exits: Vec<_> = self.exits.iter().filter(|(k, ..)| *k == kind).map(|(_, bidx, c)| (*bidx, c.clone())).collect();
+        for (bidx, constraints) in exits.iter() {
+            for (local, constraint) in constraints {
+                if leaked.iter().any(|(_, l)| l == local) {
+                    continue;
+                }
+                solver.push();
+                solver.assert(constraint);
+                if self.check_sat(solver) == z3::SatResult::Unsat {
+                    leaked.push((*bidx, *local));
+                }
+                solver.pop(1);
+            }
+        }
+
+        // the leak is located at the taint source, or the exit if the source is unknown
+        let span = match self.taint_span {
+            Some(span) => span,
+            None => self.body().basic_blocks()[BasicBlock::from(exit_block)].terminator().source_info.span,
+        };
+        let fn_name = self.tcx().def_path_str(self.did());
+
+        // the leak is caused by the combination of several locals that none of them can be blamed alone
+        if leaked.is_empty() {
+            let message = format!(
+                "The heap item converted into a raw pointer is leaked on {} of `{}`",
+                kind,
+                fn_name,
+            );
+            self.reports.push(
+                LeakReport::new(self.did(), span, None, OwnershipLayout::default(), message)
+                    .with_unwind(kind == ExitKind::Unwind)
+                    .with_virtual_calls(self.virtual_calls.clone())
+                    .with_approximations(self.exit_approximations(kind))
+            );
+            return;
+        }
+
+        for (bidx, local) in leaked {
+            let local = Local::from_usize(local);
+            // the leak of a local is located at its last write (e.g., `let x = Box::new(..)`)
+            let local_span = self.write_spans[local.as_usize()].map_or(span, |(_, s)| s);
+            let origin = self.icx().taint().get_g()[bidx].get_o()[local.as_usize()].origin();
+            let source = self.icx().taint().get_g()[bidx].get_o()[local.as_usize()].source();
+            let layout = self.icx().layout().get_g()[bidx].get_o()[local.as_usize()].clone();
+            let container = self.rcx().container_owner().get(&self.body().local_decls[local].ty).copied();
+            let local_ty = self.body().local_decls[local].ty;
+            let truncated = self.extract_default_ty_layout(local_ty, None).is_truncated();
+            let message = self.local_leak_message(self.body(), local, kind, &fn_name, container, truncated);
+            let leaked_bytes = self.leaked_bytes(local_ty);
+            // the ownership of a local typed by the type params (e.g., `x: T` or `v: Vec<T>`) depends on
+            // the instance, the leak is resolved at the call sites with concrete type args
+            let generic = local_ty.needs_subst();
+            let report = LeakReport::new(self.did(), local_span, Some(local), layout, message)
+                .with_layout_display(Some(self.display_layout(local_ty)))
+                .with_dead(self.dead_spans[local.as_usize()])
+                .with_leaked_bytes(leaked_bytes)
+                .with_truncated(truncated)
+                .with_container(container)
+                .with_origin(origin)
+                .with_source(source)
+                .with_unwind(kind == ExitKind::Unwind)
+                .with_virtual_calls(self.virtual_calls.clone())
+                .with_approximations(self.path_approximations(bidx).clone())
+                .with_witness(self.witness_path(bidx, local.as_usize()))
+                .with_generic(generic);
+            if generic {
+                self.generic_leaks.push(report);
+            } else {
+                self.reports.push(report);
+            }
+        }
+    }
+
+    // The witness path of the leaking local, reconstructed backwards from the exit: the taint arrives at each block
+    // through a visited pre node whose out state still has the local tainted, the earliest one in topo order is taken
+    // (so the back edges of loops are avoided). The path starts at the block where the local is tainted.
+    fn witness_path(&self, exit: usize, local: usize) -> Vec<WitnessStep> {
+        let mut path = vec![exit];
+        let mut cur = exit;
+        loop {
+            let arrival = self.graph()
+                .predecessors(cur)
+                .filter(|pidx| self.rounds[*pidx] > 0 && !path.contains(pidx))
+                .filter(|pidx| self.icx().taint().get_g()[*pidx].get_o()[local].is_tainted())
+                .min_by_key(|pidx| self.topo_pos[*pidx]);
+            match arrival {
+                Some(pidx) => {
+                    path.push(pidx);
+                    cur = pidx;
+                },
+                None => break,
+            }
+        }
+        path.reverse();
+
+        path.iter()
+            .enumerate()
+            .map(|(pos, bidx)| {
+                let branch = path.get(pos + 1).and_then(|next| self.branch_decision(*bidx, *next));
+                WitnessStep::new(self.graph().mir_bb(*bidx), self.graph().block_span(*bidx), branch)
+            })
+            .collect()
+    }
+
+    // the branch taken from the block to its successor, none if the block has a single successor
+    fn branch_decision(&self, from: usize, to: usize) -> Option<String> {
+        if self.graph().successors(from).count() < 2 {
+            return None;
+        }
+        if self.graph().edge_kind(from, to).map_or(false, |kind| kind.is_cleanup()) {
+            return Some("unwind".to_string());
+        }
+        let to_bb = self.graph().mir_bb(to);
+        match &self.body().basic_blocks()[self.graph().mir_bb(from)].terminator().kind {
+            TerminatorKind::SwitchInt { discr, targets, .. } => {
+                let value = targets
+                    .iter()
+                    .find(|(_, target)| *target == to_bb)
+                    .map_or("otherwise".to_string(), |(value, _)| value.to_string());
+                Some(format!("{:?} = {}", discr, value))
+            },
+            _ => None,
+        }
+    }
+
+    fn local_leak_message(
+        &self,
+        body: &Body<'tcx>,
+        local: Local,
+        kind: ExitKind,
+        fn_name: &str,
+        container: Option<ContainerOwner>,
+        truncated: bool,
+    ) -> String {
+        let mut message = format!(
+            "The heap item held by `{}` is leaked on {} of `{}`",
+            local_debug_name(body, local),
+            kind,
+            fn_name,
+        );
+        if let Some(container) = container.filter(|c| c.element().is_owned()) {
+            message.push_str(&format!(", including the owned elements of the {}", container.kind()));
+        }
+        if truncated {
+            message.push_str(&format!(
+                " (approximated, the ownership layout is truncated at depth {})",
+                self.rcx().config().max_layout_depth(),
+            ));
+        }
+        message
+    }
+
+    // resolve the leaks of the generic callee by the concrete type args of this call, the leaked local is
+    // skipped if it owns nothing in the instance (e.g., `x: T` with `T = i32`)
+    fn instantiate_generic_leaks(&mut self, callee: DefId, substs: SubstsRef<'tcx>, span: Span) {
+        if substs.needs_subst() {
+            return;
+        }
+        let leaks = match self.rcx().generic_leaks().get(&callee) {
+            Some(leaks) => leaks.clone(),
+            None => return,
+        };
+        if !self.instantiated.contains(&callee) {
+            self.instantiated.push(callee);
+        }
 
-        // if result == z3::SatResult::Unsat && self.taint_flag {
-        //     println!("{} {:?} {:?}", format!("{:?}", result).color(Color::LightCyan).bold(), self.did(), self.body().span);
-        //
-        // }
+        let tcx = self.tcx();
+        let callee_body = mir_body(tcx, callee);
+        let instance = tcx.def_path_str_with_substs(callee, substs);
+        for report in leaks {
+            let local = match report.local() {
+                Some(local) => local,
+                None => continue,
+            };
+            let ty = EarlyBinder(callee_body.local_decls[local].ty).subst(tcx, substs);
+            let layout = self.extract_default_ty_layout(ty, None);
+            if !ty.is_unsafe_ptr() && !layout.is_owned() {
+                continue;
+            }
 
+            let container = self.concrete_container_owner(ty);
+            let kind = if report.is_unwind() { ExitKind::Unwind } else { ExitKind::Return };
+            let message = self.local_leak_message(callee_body, local, kind, &instance, container, layout.is_truncated());
+            let reported = self.reports
+                .iter()
+                .chain(self.rcx().reports().iter())
+                .any(|r| r.did() == report.did() && r.span() == report.span() && *r.message() == message);
+            if reported {
+                continue;
+            }
+            let leaked_bytes = self.leaked_bytes(ty);
+            self.reports.push(
+                report
+                    .with_message(message)
+                    .with_layout_display(Some(self.display_layout(ty)))
+                    .with_leaked_bytes(leaked_bytes)
+                    .with_truncated(layout.is_truncated())
+                    .with_container(container)
+                    .with_instance(Some((instance.clone(), span)))
+            );
+        }
+    }
+
+    // the container owner of a concrete type, which may be absent in the types collected by type analysis
+    fn concrete_container_owner(&self, ty: Ty<'tcx>) -> Option<ContainerOwner> {
+        if let Some(container) = self.rcx().container_owner().get(&ty) {
+            return Some(*container);
+        }
+        let (kind, elems) = ContainerKind::from_ty(self.tcx(), ty)?;
+        let owned = elems.iter().any(|elem_ty| {
+            let mut default_ownership = self.default_ownership();
+            elem_ty.visit_with(&mut default_ownership);
+            default_ownership.is_owning_true()
+        });
+        let element = if owned { RawTypeOwner::Owned } else { RawTypeOwner::Unowned };
+        Some(ContainerOwner::new(kind, element))
     }
 
     pub(crate) fn handle_drop(
@@ -2340,13 +4303,22 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
             return;
         }
 
+        // the hand-written drop frees the heap data through the raw pointers it holds
+        // the disarmed guard frees nothing, its heap item is forgotten as if it is a plain struct
+        let custom_drop = match dest.ty(self.body(), self.tcx()).ty.kind() {
+            TyKind::Adt(adtdef, ..) => {
+                self.rcx().custom_drop().contains(&adtdef.did()) && !self.icx_slice().taint()[u].is_disarmed()
+            },
+            _ => false,
+        };
+
         match f.has_field() {
             false => {
                 // drop the entire owning item
                 // reverse the ownership layout and using and operator
-                if recovery {
+                if recovery || custom_drop {
                     // recovery for pointer, clear all
-                    let name = new_local_name(u, bidx, 0).add("_drop_recovery");
+                    let name = self.local_name(u, bidx, 0).add("_drop_recovery");
                     let new_bv = ast::BV::new_const(ctx, name, len as u32);
                     let zero_bv = ast::BV::from_u64(ctx, 0, len as u32);
 
@@ -2360,7 +4332,7 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
                     self.icx_slice_mut().var_mut()[u] = IntroVar::Init(new_bv);
                 } else {
                     // is not recovery for pointer, just normal drop
-                    let name = new_local_name(u, bidx, 0).add("_drop_all");
+                    let name = self.local_name(u, bidx, 0).add("_drop_all");
                     let new_bv = ast::BV::new_const(ctx, name, len as u32);
                     let int_for_rust_bv = rustbv_to_int(&rust_bv);
                     let int_bv_const = ast::BV::from_u64(ctx, int_for_rust_bv, len as u32);
@@ -2382,9 +4354,9 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
                 if index_needed >= rust_bv.len() { return; }
 
                 let name = if recovery {
-                    new_local_name(u, bidx, 0).add("_drop_f_recovery")
+                    self.local_name(u, bidx, 0).add("_drop_f_recovery")
                 } else {
-                    new_local_name(u, bidx, 0).add("_drop_f")
+                    self.local_name(u, bidx, 0).add("_drop_f")
                 };
                 let new_bv = ast::BV::new_const(ctx, name, len as u32);
 
@@ -2417,6 +4389,10 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
                     solver.assert(&constraint_free_f);
 
                     self.icx_slice_mut().var_mut()[u] = IntroVar::Init(new_bv);
+                    // the taint carried by the dropped field leaves the local with it
+                    if !recovery {
+                        self.icx_slice_mut().taint_mut()[u].remove_field(index_needed);
+                    }
                 }
 
             },
@@ -2449,13 +4425,62 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         if self.icx_slice().taint()[l].is_untainted() {
             self.icx_slice_mut().taint_mut()[l] = self.icx_slice().taint()[r].clone();
         } else {
-            for elem in self.icx_slice().taint()[r].set().clone() {
-                self.icx_slice_mut().taint_mut()[l].insert(elem);
-            }
+            let taint = self.icx_slice().taint()[r].clone();
+            self.icx_slice_mut().taint_mut()[l].merge(&taint);
         }
 
     }
 
+    // the taint flows between the places with a field projection, e.g., `s.f = move b` or `y = move s.f`
+    // the field of lvalue records the entries it carries, and the entries of the field in rvalue are moved out
+    // only if the taint of rvalue is field precise, otherwise the whole taint of rvalue flows as handle_taint
+    pub(crate) fn handle_field_taint(&mut self, l: usize, lf: Option<usize>, r: usize, rf: Option<usize>, moved: bool) {
+        let mut taint = match rf.and_then(|f| self.icx_slice().taint()[r].field_taint(f)) {
+            Some(taint) => {
+                if moved {
+                    self.icx_slice_mut().taint_mut()[r].remove_field(rf.unwrap());
+                }
+                taint
+            },
+            None => self.icx_slice().taint()[r].clone(),
+        };
+        if taint.is_untainted() {
+            return;
+        }
+
+        // the fields of rvalue are indexed in another layout than the lvalue
+        taint.clear_fields();
+        if let Some(f) = lf {
+            self.icx_slice_mut().taint_mut()[l].insert_field(f, taint.set());
+        }
+        self.icx_slice_mut().taint_mut()[l].merge(&taint);
+    }
+
+    // the size of the heap item that the leaking local points to, i.e., the pointee of a raw pointer or a box
+    // the size of an array is the element size times its len, and the unsized pointee (e.g., [T]) is unknown
+    fn leaked_bytes(&self, ty: Ty<'tcx>) -> Option<u64> {
+        let ty = type_analysis::unwrap_pin(self.tcx(), ty);
+        let pointee = match ty.kind() {
+            TyKind::RawPtr(ty_and_mut) => ty_and_mut.ty,
+            TyKind::Adt(..) if ty.is_box() => ty.boxed_ty(),
+            _ => return None,
+        };
+        let param_env = self.tcx().param_env(self.did());
+        match self.tcx().layout_of(param_env.and(pointee)) {
+            Ok(layout) if !layout.is_unsized() => Some(layout.size.bytes()),
+            _ => None,
+        }
+    }
+
+    // the default ownership visitor bounded by the max layout depth in config
+    fn display_layout(&self, ty: Ty<'tcx>) -> String {
+        type_analysis::display_layout(self.tcx(), self.owner(), self.rcx().config().max_layout_depth(), ty)
+    }
+
+    fn default_ownership(&self) -> DefaultOwnership<'tcx, '_> {
+        DefaultOwnership::new(self.tcx(), self.owner()).with_max_depth(self.rcx().config().max_layout_depth())
+    }
+
     pub(crate) fn extract_default_ty_layout(
         &mut self,
         ty: Ty<'tcx>,
@@ -2464,7 +4489,7 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         match ty.kind() {
             TyKind::Array( .. ) => {
                 let mut res = OwnershipLayoutResult::new();
-                let mut default_ownership = DefaultOwnership::new(self.tcx(), self.owner());
+                let mut default_ownership = self.default_ownership();
 
                 ty.visit_with(&mut default_ownership);
                 res.update_from_default_ownership_visitor(&mut default_ownership);
@@ -2475,7 +4500,7 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
                 let mut res = OwnershipLayoutResult::new();
 
                 for tuple_ty in tuple_ty_list.iter() {
-                    let mut default_ownership = DefaultOwnership::new(self.tcx(), self.owner());
+                    let mut default_ownership = self.default_ownership();
 
                     tuple_ty.visit_with(&mut default_ownership);
                     res.update_from_default_ownership_visitor(&mut default_ownership);
@@ -2483,6 +4508,19 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
 
                 res
             },
+            TyKind::Closure( .. )
+            | TyKind::Generator( .. ) => {
+                let mut res = OwnershipLayoutResult::new();
+
+                for upvar_ty in upvar_tys(ty) {
+                    let mut default_ownership = self.default_ownership();
+
+                    upvar_ty.visit_with(&mut default_ownership);
+                    res.update_from_default_ownership_visitor(&mut default_ownership);
+                }
+
+                res
+            },
             TyKind::Adt( adtdef, substs ) => {
                 // check the ty is or is not an enum and the variant of this enum is or is not given
                 if adtdef.is_enum() && variant.is_none() {
@@ -2491,12 +4529,20 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
 
                 let mut res = OwnershipLayoutResult::new();
 
+                // the fields of ManuallyDrop are caller-managed and never owned by the wrapper
+                if type_analysis::is_manually_drop(self.tcx(), adtdef.did()) {
+                    for _ in adtdef.all_fields() {
+                        res.layout_mut().push(RawTypeOwner::Unowned);
+                    }
+                    return res;
+                }
+
                 // check the ty if it is a struct or union
                 if adtdef.is_struct() || adtdef.is_union() {
                     for field in adtdef.all_fields() {
                         let field_ty = field.ty(self.tcx(), substs);
 
-                        let mut default_ownership = DefaultOwnership::new(self.tcx(), self.owner());
+                        let mut default_ownership = self.default_ownership();
 
                         field_ty.visit_with(&mut default_ownership);
                         res.update_from_default_ownership_visitor(&mut default_ownership);
@@ -2505,11 +4551,14 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
                 // check the ty which is an enum with a exact variant idx
                 else if adtdef.is_enum() {
                     let vidx = variant.unwrap();
+                    // the field of Cow::Owned is the projection `<B as ToOwned>::Owned` rather than the owned type
+                    let cow_owned_ty = type_analysis::cow_owned_ty(self.tcx(), ty)
+                        .filter(|_| adtdef.variants()[vidx].name.as_str() == "Owned");
 
                     for field in &adtdef.variants()[vidx].fields {
-                        let field_ty = field.ty(self.tcx(), substs);
+                        let field_ty = cow_owned_ty.unwrap_or_else(|| field.ty(self.tcx(), substs));
 
-                        let mut default_ownership = DefaultOwnership::new(self.tcx(), self.owner());
+                        let mut default_ownership = self.default_ownership();
 
                         field_ty.visit_with(&mut default_ownership);
                         res.update_from_default_ownership_visitor(&mut default_ownership);
@@ -2543,6 +4592,21 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
         }
     }
 
+    // the layout of each variant of the enum, the ownership of an enum is not a single layout
+    // e.g., enum E { Owned(Box<T>), Empty } only owns the heap item in variant Owned
+    pub(crate) fn extract_variant_layouts(&mut self, ty: Ty<'tcx>) -> Vec<OwnershipLayoutResult> {
+        match ty.kind() {
+            TyKind::Adt(adtdef, ..) if adtdef.is_enum() => {
+                adtdef
+                    .variants()
+                    .indices()
+                    .map(|vidx| self.extract_default_ty_layout(ty, Some(vidx)))
+                    .collect()
+            },
+            _ => vec![self.extract_default_ty_layout(ty, None)],
+        }
+    }
+
     pub(crate) fn generate_ptr_layout(
         &mut self,
         ty: Ty<'tcx>,
@@ -2566,6 +4630,18 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
 
                 res
             },
+            TyKind::Closure( .. )
+            | TyKind::Generator( .. ) => {
+                for upvar_ty in upvar_tys(ty) {
+                    if upvar_ty.is_region_ptr() || upvar_ty.is_unsafe_ptr() {
+                        res.push(true);
+                    } else {
+                        res.push(false);
+                    }
+                }
+
+                res
+            },
             TyKind::Adt( adtdef, substs ) => {
                 // check the ty is or is not an enum and the variant of this enum is or is not given
                 if adtdef.is_enum() && variant.is_none() {
@@ -2608,6 +4684,61 @@ impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
     }
 }
 
+impl<'tcx, 'ctx, 'a> IntroFlowAnalysis<'tcx, 'ctx, 'a> {
+    // the program point ordered by the topo order of blocks and then the statements (terminator is the last)
+    fn program_point(&self, bidx: usize, sidx: usize) -> (usize, usize) {
+        (self.topo_pos[bidx], sidx)
+    }
+
+    // the identity of the allocation introduced at the location, the location visited again keeps its identity
+    pub(crate) fn alloc_id(&mut self, bidx: usize, sidx: usize, span: Span) -> AllocId {
+        let next = AllocId::new(self.alloc_sites.len());
+        let id = *self.alloc_ids.entry((bidx, sidx)).or_insert(next);
+        if id == next {
+            self.alloc_sites.push(span);
+        }
+        id
+    }
+
+    // the span where the ownership of the allocation is introduced
+    pub(crate) fn alloc_site(&self, id: AllocId) -> Span {
+        self.alloc_sites[id.index()]
+    }
+
+    // record the span if the local is assigned with a new value at this program point
+    fn record_write(&mut self, local: usize, pre_var: &IntroVar<'ctx>, point: (usize, usize), span: Span) {
+        let var = &self.icx_slice().var()[local];
+        if !var.is_init() || var == pre_var {
+            return;
+        }
+        let point = self.program_point(point.0, point.1);
+        match self.write_spans[local] {
+            Some((old, _)) if old > point => (),
+            _ => self.write_spans[local] = Some((point, span)),
+        }
+    }
+
+    // the name of the local in this program point
+    // a re-visited block appends its round to generate fresh constants rather than reusing them
+    fn local_name(&self, local: usize, bidx: usize, sidx: usize) -> String {
+        let name = new_local_name(local, bidx, sidx);
+        match self.rounds[bidx] {
+            0 => name,
+            round => name.add("_r").add(&round.to_string()),
+        }
+    }
+}
+
+// The timeout in config is only the default of context, the solver needs its own params. The timeout (ms)
+// bounds each check of the solver, and the check giving up returns unknown.
+pub(crate) fn timed_solver(ctx: &z3::Context, timeout: u64) -> z3::Solver<'_> {
+    let solver = z3::Solver::new(ctx);
+    let mut params = z3::Params::new(ctx);
+    params.set_u32("timeout", timeout.min(u32::MAX as u64) as u32);
+    solver.set_params(&params);
+    solver
+}
+
 fn new_local_name(local: usize, bidx: usize, sidx: usize) -> String {
     let s = bidx.to_string()
         .add("_")
@@ -2617,6 +4748,117 @@ fn new_local_name(local: usize, bidx: usize, sidx: usize) -> String {
     s
 }
 
+// Find the param that each local is copied (or cast) from, the params are indexed from 0.
+pub(crate) fn param_aliases(body: &Body) -> Vec<Option<usize>> {
+    let mut alias = vec![None ; body.local_decls.len()];
+    for param in 0..body.arg_count {
+        alias[param + 1] = Some(param);
+    }
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for data in body.basic_blocks().iter() {
+            for stmt in data.statements.iter() {
+                let (lplace, op) = match &stmt.kind {
+                    StatementKind::Assign(box(lplace, Rvalue::Use(op) | Rvalue::Cast(_, op, _))) => (lplace, op),
+                    _ => continue,
+                };
+                let rplace = match op.place() {
+                    Some(rplace) if rplace.projection.is_empty() && lplace.projection.is_empty() => rplace,
+                    _ => continue,
+                };
+                let lu = lplace.local.as_usize();
+                if alias[lu].is_none() && alias[rplace.local.as_usize()].is_some() {
+                    alias[lu] = alias[rplace.local.as_usize()];
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    alias
+}
+
+// Find the locals whose value escapes the function: the ones flowing into the return value `_0`
+// (e.g., `let p = Box::into_raw(b); p`) or stored behind an out-pointer parameter (e.g., `*out = p`).
+// It is a flow-insensitive closure over the assignments and calls of the body.
+pub(crate) fn escaping_locals(body: &Body) -> Vec<bool> {
+    let mut escaping = vec![false ; body.local_decls.len()];
+    escaping[0] = true;
+    // the values written through the reference to a static (or a thread-local) live until the program exits
+    for (local, decl) in body.local_decls.iter_enumerated() {
+        if decl.is_ref_to_static() {
+            escaping[local.as_usize()] = true;
+        }
+    }
+
+    let is_target = |escaping: &Vec<bool>, place: &Place| -> bool {
+        escaping[place.local.as_usize()]
+            || (place.projection.first() == Some(&ProjectionElem::Deref)
+                && place.local.as_usize() >= 1
+                && place.local.as_usize() <= body.arg_count)
+    };
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        let mut sources: Vec<Local> = Vec::new();
+        for data in body.basic_blocks().iter() {
+            for stmt in data.statements.iter() {
+                let (lplace, rvalue) = match &stmt.kind {
+                    StatementKind::Assign(box(lplace, rvalue)) => (lplace, rvalue),
+                    _ => continue,
+                };
+                if !is_target(&escaping, lplace) {
+                    continue;
+                }
+                match rvalue {
+                    Rvalue::Use(op)
+                    | Rvalue::Repeat(op, _)
+                    | Rvalue::Cast(_, op, _)
+                    | Rvalue::ShallowInitBox(op, _) => sources.extend(op.place().map(|p| p.local)),
+                    Rvalue::Aggregate(_, ops) => sources.extend(ops.iter().filter_map(|op| op.place().map(|p| p.local))),
+                    // the reborrow through a pointer, e.g., `&mut *p`
+                    Rvalue::Ref(_, _, place)
+                    | Rvalue::AddressOf(_, place)
+                    | Rvalue::CopyForDeref(place) => {
+                        if place.projection.first() == Some(&ProjectionElem::Deref) {
+                            sources.push(place.local);
+                        }
+                    },
+                    _ => (),
+                }
+            }
+            if let TerminatorKind::Call { args, destination, .. } = &data.terminator().kind {
+                if is_target(&escaping, destination) {
+                    sources.extend(args.iter().filter_map(|op| op.place().map(|p| p.local)));
+                }
+            }
+        }
+        for local in sources {
+            if !escaping[local.as_usize()] {
+                escaping[local.as_usize()] = true;
+                changed = true;
+            }
+        }
+    }
+
+    escaping
+}
+
+// the name of the local in the source code if it exists, e.g., `x` rather than `_1`
+pub(crate) fn local_debug_name(body: &Body, local: Local) -> String {
+    for info in body.var_debug_info.iter() {
+        if let VarDebugInfoContents::Place(place) = info.value {
+            if place.local == local && place.projection.is_empty() {
+                return info.name.to_string();
+            }
+        }
+    }
+    format!("{:?}", local)
+}
+
 fn is_place_containing_ptr(ty: &Ty) -> bool {
     match ty.kind() {
         TyKind::Tuple( tuple_ty_list ) => {
@@ -2636,6 +4878,9 @@ fn is_place_containing_ptr(ty: &Ty) -> bool {
 #[derive(Debug)]
 struct ProjectionSupport<'tcx>  {
     pf_vec: Vec<(usize, Ty<'tcx>)>,
+    // the element of an array, all the elements share the single entry of the array in the layout,
+    // e.g., `a` in `let [a, b] = arr` or `arr[i]`
+    elem: bool,
     deref: bool,
     downcast: Disc,
     unsupport: bool,
@@ -2645,6 +4890,7 @@ impl<'tcx> Default for ProjectionSupport<'tcx> {
     fn default() -> Self {
         Self {
             pf_vec: Vec::default(),
+            elem: false,
             deref: false,
             downcast: None,
             unsupport: false,
@@ -2666,7 +4912,7 @@ impl<'tcx> ProjectionSupport<'tcx> {
     }
 
     pub fn has_field(&self) -> bool {
-        self.pf_vec.len() > 0
+        !self.pf_vec.is_empty() || self.elem
     }
 
     pub fn has_downcast(&self) -> bool {
@@ -2682,6 +4928,9 @@ impl<'tcx> ProjectionSupport<'tcx> {
     }
 
     pub fn index_needed(&self) -> usize {
+        if self.elem {
+            return 0;
+        }
         self.pf_vec[0].0
     }
 
@@ -2700,7 +4949,7 @@ fn extract_projection<'tcx>(place: &Place<'tcx>) -> ProjectionSupport<'tcx> {
         match each_pj {
             ProjectionElem::Field(field, ty) => {
                 ans.pf_push(field.index(), ty);
-                if ans.pf_vec.len() > 1 { ans.unsupport = true; break; }
+                if ans.pf_vec.len() > 1 || ans.elem { ans.unsupport = true; break; }
                 if ans.deref { ans.unsupport = true; break; }
             },
             ProjectionElem::Deref => {
@@ -2711,9 +4960,13 @@ fn extract_projection<'tcx>(place: &Place<'tcx>) -> ProjectionSupport<'tcx> {
                 ans.downcast = Some(*vidx);
                 if idx > 0 { ans.unsupport = true; break; }
             },
+            // the element of the array local itself, the array behind a pointer (a slice) or in a field is not tracked
             ProjectionElem::ConstantIndex { .. }
-            | ProjectionElem::Subslice { .. }
             | ProjectionElem::Index ( .. ) => {
+                ans.elem = true;
+                if idx > 0 { ans.unsupport = true; break; }
+            },
+            ProjectionElem::Subslice { .. } => {
                 { ans.unsupport = true; break; }
             }
         }
@@ -2727,6 +4980,9 @@ fn projection_field_to_string(local: usize, pf: &ProjectionSupport) -> String {
         s.push_str(".");
         s.push_str(field.0.to_string().as_str());
     }
+    if pf.elem {
+        s.push_str(".elem");
+    }
     s
 }
 
@@ -1,10 +1,16 @@
-use rustc_middle::mir::TerminatorKind;
+use rustc_middle::mir::{BasicBlock, Body, TerminatorKind};
+use rustc_middle::ty::TyCtxt;
+use rustc_target::spec::PanicStrategy;
+use rustc_span::def_id::DefId;
 
 use crate::analysis::RcxMut;
-use crate::analysis::flow_analysis::{FlowAnalysis, NodeOrder};
-use crate::analysis::type_analysis::type_visitor::mir_body;
+use crate::analysis::flow_analysis::{BlockMeta, EdgeKind, Edges, FlowAnalysis, Graph, NodeOrder, ToPo};
+use crate::analysis::flow_analysis::call_graph::CallGraph;
+use crate::analysis::type_analysis::type_visitor::{is_analyzable_body, mir_body};
+use crate::components::fs::{rlc_create_dir, rlc_create_file, rlc_write};
 
-use std::collections::BinaryHeap;
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
 use stopwatch::Stopwatch;
 
 impl<'tcx, 'a> FlowAnalysis<'tcx, 'a>{
@@ -20,35 +26,206 @@ impl<'tcx, 'a> FlowAnalysis<'tcx, 'a>{
         for each_mir in mir_keys {
             // Get the defid of current crate and get mir Body through this id
             let def_id = each_mir.to_def_id();
-            let body = mir_body(tcx, def_id);
-
-            let mut path = NodeOrder::new(body);
-            let mut lev:Vec<usize> = vec![0 ; body.basic_blocks().len()];
-
-            path.collect_edges(&mut lev);
-            path.topo_order(&mut lev);
-            self.rcx_mut().mir_graph_mut().insert(def_id, path.graph_mut().clone());
+            // the function without mir is skipped rather than aborting the whole analysis
+            if !is_analyzable_body(tcx, def_id) {
+                self.rcx_mut().metrics_mut().add_skipped();
+                continue;
+            }
+            self.order_body(def_id);
         }
 
+        // the call graph is built on the functions ordered above, it decides the order of analyzing them
+        let dids: Vec<DefId> = self.rcx().mir_graph().keys().copied().collect();
+        *self.rcx_mut().call_graph_mut() = CallGraph::new(tcx, &dids);
+
+        let functions = self.rcx().mir_graph().len();
+        self.rcx_mut().metrics_mut().set_functions(functions);
+        self.rcx_mut().metrics_mut().add_time_order(sw.elapsed_ms());
         self.rcx_mut().add_time_build(sw.elapsed_ms());
         sw.stop();
     }
+
+    pub fn order_body(&mut self, def_id: DefId) {
+        let tcx = self.tcx();
+        // the unwind edges are dead if the crate aborts on panic
+        let unwind = self.unwind() && tcx.sess.panic_strategy() == PanicStrategy::Unwind;
+        let body = mir_body(tcx, def_id);
+
+        let mut path = NodeOrder::new(body).with_unwind(unwind);
+
+        path.collect_edges();
+        path.topo_order();
+        self.rcx_mut().mir_graph_mut().insert(def_id, path.graph_mut().clone());
+    }
+}
+
+impl<'tcx, 'a> FlowAnalysis<'tcx, 'a>{
+    // write one dot file for each function in the mir graph into the given dir
+    pub fn dump_dot(&self, dir: &str) {
+        rlc_create_dir(dir, "Failed to create dir for dot files");
+
+        let tcx = self.tcx();
+        let mut dids: Vec<&DefId> = self.mir_graph().keys().collect();
+        dids.sort_by_cached_key(|did| tcx.def_path_str(**did));
+        for def_id in dids {
+            let graph = &self.mir_graph()[def_id];
+            let body = mir_body(tcx, *def_id);
+
+            let path = Path::new(dir).join(format!("{}.dot", dump_name(tcx, *def_id)));
+            let file = rlc_create_file(&path, "Failed to create dot file");
+            rlc_write(file, graph.to_dot(body).as_bytes(), "Failed to write dot file");
+        }
+    }
+}
+
+// the file name (without extension) of the debugging dump of a function, it is unique in the crate
+pub(crate) fn dump_name(tcx: TyCtxt<'_>, def_id: DefId) -> String {
+    let name: String = tcx
+        .def_path_str(def_id)
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("{}_{}", name, def_id.index.as_usize())
 }
 
 impl<'tcx> NodeOrder<'tcx> {
 
-    /// !Note: this function does not collect the edges that belongs to unwind paths.
-    pub(crate) fn collect_edges(&mut self, lev: &mut Vec<usize>) {
-        let bbs = self.body().basic_blocks();
+    /// !Note: this function does not collect the edges that belongs to unwind paths unless unwind is enabled.
+    pub(crate) fn collect_edges(&mut self) {
+        let (body, unwind) = (self.body(), self.unwind);
+        self.graph_mut().collect_edges(body, unwind);
+    }
+
+    pub(crate) fn topo_order(&mut self) {
+        self.graph_mut().build_topo_order();
+    }
+
+}
+
+// The topo order of all the nodes (numbered below `num_nodes`) over the condensed dag of the sccs, the nodes of a
+// cycle are ordered together by bfs from the ones entered from outside. Each weakly connected component is ordered
+// as well (e.g., the dead blocks or the functions never called), and the edges to the nodes out of range are ignored.
+pub fn topological_order(edges: &Edges, num_nodes: usize) -> ToPo {
+    let mut graph = Graph::new(num_nodes);
+    for (node, successors) in edges.iter().enumerate().take(num_nodes) {
+        for next in successors.iter().filter(|next| **next < num_nodes) {
+            graph.e[node].push(*next);
+            graph.pre[*next].push(node);
+        }
+    }
+
+    let mut topo = Vec::with_capacity(num_nodes);
+    for scc in graph.find_sccs().iter().rev() {
+        topo.extend(graph.scc_order(scc));
+    }
+    topo
+}
+
+// the back edges of the order, i.e., the edges going to a node not after their source (including the self edges),
+// they are exactly the edges closing the cycles if the order is given by topological_order
+pub fn back_edges(edges: &Edges, topo: &ToPo) -> Vec<(usize, usize)> {
+    let pos: HashMap<usize, usize> = topo.iter().enumerate().map(|(pos, node)| (*node, pos)).collect();
+    let mut back = Vec::new();
+    for node in topo.iter() {
+        for next in edges.get(*node).into_iter().flatten() {
+            if matches!(pos.get(next), Some(p) if *p <= pos[node]) {
+                back.push((*node, *next));
+            }
+        }
+    }
+    back
+}
+
+impl Graph {
+    // build the cfg of the mir body with its edges, pre nodes and topo order, the cleanup (unwind) edges are ignored
+    // it is independent of the leak checker, e.g., for a separate pass over the same cfg
+    pub fn from_body(body: &Body<'_>) -> Self {
+        Self::from_body_with_cleanup(body, false)
+    }
+
+    // build the cfg of the mir body, including the cleanup edges if `cleanup` is set
+    pub fn from_body_with_cleanup(body: &Body<'_>, cleanup: bool) -> Self {
+        let mut graph = Graph::new(body.basic_blocks().len());
+        graph.collect_edges(body, cleanup);
+        graph.build_topo_order();
+        graph
+    }
+
+    // Build the graph from the terse text of its edges, e.g., `0 -> 1,2; 1 -> 3; 2 -> 3`, to check the graph
+    // algorithms without a mir body. The blocks are numbered up to the largest index in the text (a block without
+    // successors is given alone, e.g., `4`), the edges are Goto, and the pre nodes and topo order are built as usual.
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        let parse = |s: &str| {
+            s.trim().parse::<usize>().map_err(|e| format!("invalid block `{}` in `{}`: {}", s.trim(), text, e))
+        };
+
+        let mut edges: Vec<(usize, Vec<usize>)> = Vec::new();
+        for clause in text.split(';').map(str::trim).filter(|clause| !clause.is_empty()) {
+            let (from, to) = match clause.split_once("->") {
+                Some((from, to)) => (from, to),
+                None => (clause, ""),
+            };
+            let to = to
+                .split(',')
+                .filter(|next| !next.trim().is_empty())
+                .map(parse)
+                .collect::<Result<Vec<usize>, String>>()?;
+            edges.push((parse(from)?, to));
+        }
+
+        let len = edges
+            .iter()
+            .flat_map(|(from, to)| std::iter::once(from).chain(to.iter()))
+            .max()
+            .map_or(0, |max| max + 1);
+        let mut graph = Graph::new(len);
+        for (from, to) in edges {
+            graph.kinds[from].extend(to.iter().map(|_| EdgeKind::Goto));
+            graph.e[from].extend(to);
+        }
+        graph.merge_parallel_edges();
+        graph.build_topo_order();
+        Ok(graph)
+    }
+
+    // the terse text of the edges read by `from_text`, the blocks without any edge are given alone
+    pub fn to_text(&self) -> String {
+        let mut clauses = Vec::new();
+        for (block, edges) in self.e.iter().enumerate() {
+            if !edges.is_empty() {
+                let next: Vec<String> = edges.iter().map(|next| next.to_string()).collect();
+                clauses.push(format!("{} -> {}", block, next.join(",")));
+            } else if self.pre[block].is_empty() {
+                clauses.push(block.to_string());
+            }
+        }
+        clauses.join("; ")
+    }
+
+    /// !Note: this function does not collect the edges that belongs to unwind paths unless unwind is enabled.
+    fn collect_edges(&mut self, body: &Body<'_>, unwind: bool) {
+        let bbs = body.basic_blocks();
+        self.meta.clear();
+        let mut offset = 0;
         for (block, data) in bbs.iter().enumerate() {
-            let mut result:Vec<usize> = vec![];
+            let terminator = data.terminator().source_info;
+            let span = match data.statements.first() {
+                Some(first) => first.source_info.span.to(terminator.span),
+                None => terminator.span,
+            };
+            self.meta.push(BlockMeta::new(BasicBlock::from_usize(block), offset, data.statements.len(), span, terminator));
+            offset += data.statements.len();
+
+            let mut result:Vec<(usize, EdgeKind)> = vec![];
             match &data.terminator().kind {
                 TerminatorKind::Goto { target } =>
-                    result.push(target.as_usize()),
+                    result.push((target.as_usize(), EdgeKind::Goto)),
                 TerminatorKind::SwitchInt { targets, .. } =>
                     {
+                        // all arms are collected, including the Break arm in the desugaring of `?`
+                        // that returns early via `FromResidual::from_residual`
                         for bb in targets.all_targets() {
-                            result.push(bb.as_usize());
+                            result.push((bb.as_usize(), EdgeKind::SwitchArm));
                         }
                     },
                 TerminatorKind::Resume =>
@@ -59,57 +236,265 @@ impl<'tcx> NodeOrder<'tcx> {
                     (),
                 TerminatorKind::Unreachable =>
                     (),
-                TerminatorKind::Drop { target, .. } =>
-                    result.push(target.as_usize()),
-                TerminatorKind::DropAndReplace { .. } =>
-                    (),
-                TerminatorKind::Assert { target, .. } =>
-                    result.push(target.as_usize()),
+                TerminatorKind::Drop { target, unwind: cleanup, .. } => {
+                    result.push((target.as_usize(), EdgeKind::DropSuccess));
+                    if let (true, Some(c)) = (unwind, cleanup) {
+                        result.push((c.as_usize(), EdgeKind::Cleanup));
+                    }
+                },
+                TerminatorKind::DropAndReplace { target, unwind: cleanup, .. } => {
+                    result.push((target.as_usize(), EdgeKind::DropSuccess));
+                    if let (true, Some(c)) = (unwind, cleanup) {
+                        result.push((c.as_usize(), EdgeKind::Cleanup));
+                    }
+                },
+                TerminatorKind::Assert { target, cleanup, .. } => {
+                    result.push((target.as_usize(), EdgeKind::AssertSuccess));
+                    if let (true, Some(c)) = (unwind, cleanup) {
+                        result.push((c.as_usize(), EdgeKind::Cleanup));
+                    }
+                },
                 TerminatorKind::Yield { .. } =>
                     (),
                 TerminatorKind::GeneratorDrop =>
                     (),
-                TerminatorKind::FalseEdge { .. } =>
-                    (),
-                TerminatorKind::FalseUnwind { .. } =>
-                    (),
+                // the false edges only exist before the drop elaboration (in the built and analysis MIR),
+                // the imaginary target and the unwind of a loop are never taken
+                TerminatorKind::FalseEdge { real_target, .. } =>
+                    result.push((real_target.as_usize(), EdgeKind::Goto)),
+                TerminatorKind::FalseUnwind { real_target, .. } =>
+                    result.push((real_target.as_usize(), EdgeKind::Goto)),
                 TerminatorKind::InlineAsm { .. } =>
                     (),
-                TerminatorKind::Call { target, .. } => {
+                TerminatorKind::Call { target, cleanup, .. } => {
                     // We check the destination due to following case.
                     // Terminator { source_info: SourceInfo { span: src/main.rs:100:9: 100:35 (#7), scope: scope[0] },
                     // kind: core::panicking::panic(const "assertion failed: index <= self.len") -> bb24 },
                     // destination -> None, cleanup -> Some(bb24)
                     match target {
-                        Some(t) => { result.push(t.as_usize()) },
+                        Some(t) => { result.push((t.as_usize(), EdgeKind::CallReturn)) },
                         None => (),
                     }
+                    if let (true, Some(c)) = (unwind, cleanup) {
+                        result.push((c.as_usize(), EdgeKind::Cleanup));
+                    }
                 }
             }
-            // Update the lev for generating topo order.
-            for index in result.iter() {
-                lev[*index] = lev[*index] + 1;
-                self.graph_mut().get_pre_mut()[*index].push(block);
+            for (index, _) in result.iter() {
+                self.pre[*index].push(block);
             }
-            self.graph_mut().get_edges_mut()[block] = result;
+            self.e[block] = result.iter().map(|(index, _)| *index).collect();
+            self.kinds[block] = result.iter().map(|(_, kind)| *kind).collect();
 
         }
+        self.merge_parallel_edges();
+    }
+
+    // the topo order is generated on the condensed dag of sccs, so the blocks of a loop are
+    // ordered together and flagged as cyclic, the blocks unreachable from bb0 are not in the order
+    fn build_topo_order(&mut self) {
+        let len = self.e.len();
+        if len == 0 {
+            return;
+        }
+
+        for scc in self.find_sccs() {
+            if scc.len() > 1 || self.has_self_loop(scc[0]) {
+                for bidx in scc {
+                    self.set_cyclic(bidx);
+                }
+            }
+        }
+
+        // the dead blocks keep their slots in the contexts (indexed by block), they are just not visited
+        let reachable = self.reachable_from(0);
+        self.topo = topological_order(&self.e, len)
+            .into_iter()
+            .filter(|bidx| reachable.contains(bidx))
+            .collect();
     }
 
-    pub(crate) fn topo_order(&mut self, lev: &mut Vec<usize>) {
-        let mut q:BinaryHeap<usize> = BinaryHeap::new();
-        q.push(0);
-        while !q.is_empty() {
-            let top = q.pop().unwrap();
-            self.graph_mut().get_topo_mut().push(top);
-            for cnt in 0..self.graph().e[top].len() {
-                let next = self.graph().e[top][cnt];
-                lev[next] = lev[next] - 1;
-                if lev[next] == 0 {
-                    q.push(next);
+    // order the blocks inside a scc by bfs from its entries (the blocks having pre nodes outside)
+    fn scc_order(&self, scc: &[usize]) -> Vec<usize> {
+        if scc.len() == 1 {
+            return scc.to_vec();
+        }
+
+        let mut in_scc = vec![false ; self.e.len()];
+        for bidx in scc.iter() {
+            in_scc[*bidx] = true;
+        }
+
+        let mut visited = vec![false ; self.e.len()];
+        let mut queue: VecDeque<usize> = scc
+            .iter()
+            .filter(|bidx| **bidx == 0 || self.pre[**bidx].iter().any(|pre| !in_scc[*pre]))
+            .copied()
+            .collect();
+        // the scc entered from nowhere is the root of another component (e.g., a dead loop), start from its first block
+        if queue.is_empty() {
+            queue.push_back(scc[0]);
+        }
+        for bidx in queue.iter() {
+            visited[*bidx] = true;
+        }
+
+        let mut order = Vec::with_capacity(scc.len());
+        while let Some(top) = queue.pop_front() {
+            order.push(top);
+            for next in self.e[top].iter() {
+                if in_scc[*next] && !visited[*next] {
+                    visited[*next] = true;
+                    queue.push_back(*next);
                 }
             }
         }
+
+        // the rest blocks can not be reached from any entry, keep them in index order
+        for bidx in scc.iter() {
+            if !visited[*bidx] {
+                order.push(*bidx);
+            }
+        }
+        order
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustc_index::vec::IndexVec;
+    use rustc_middle::mir::{AssertKind, BasicBlockData, Local, Operand, Place, SourceInfo, Terminator};
+    use rustc_span::DUMMY_SP;
+
+    // the small body of `_1` dropped (unwinding to bb4), then checked by an assert (unwinding to bb4 as well)
+    // and joined to the return through a false edge, i.e., bb0 -> bb1 -> bb2 -> bb3 with the cleanup bb4
+    fn small_body() -> Body<'static> {
+        let place = Place::from(Local::from_usize(1));
+        let kinds = vec![
+            TerminatorKind::Drop { place, target: BasicBlock::from_usize(1), unwind: Some(BasicBlock::from_usize(4)) },
+            TerminatorKind::Assert {
+                cond: Operand::Copy(place),
+                expected: true,
+                msg: AssertKind::OverflowNeg(Operand::Copy(place)),
+                target: BasicBlock::from_usize(2),
+                cleanup: Some(BasicBlock::from_usize(4)),
+            },
+            TerminatorKind::FalseEdge { real_target: BasicBlock::from_usize(3), imaginary_target: BasicBlock::from_usize(4) },
+            TerminatorKind::Return,
+            TerminatorKind::Resume,
+        ];
+        let blocks: IndexVec<BasicBlock, BasicBlockData<'static>> = kinds
+            .into_iter()
+            .map(|kind| BasicBlockData::new(Some(Terminator { source_info: SourceInfo::outermost(DUMMY_SP), kind })))
+            .collect();
+        Body::new_cfg_only(blocks)
+    }
+
+    #[test]
+    fn graph_from_body() {
+        rustc_span::create_default_session_globals_then(|| {
+            let graph = Graph::from_body(&small_body());
+            assert_eq!(graph.get_edges(), &vec![vec![1], vec![2], vec![3], vec![], vec![]]);
+            assert_eq!(graph.get_pre(), &vec![vec![], vec![0], vec![1], vec![2], vec![]]);
+            assert_eq!(graph.edge_kind(0, 1), Some(EdgeKind::DropSuccess));
+            assert_eq!(graph.edge_kind(1, 2), Some(EdgeKind::AssertSuccess));
+            // the cleanup block is not reachable without the unwind edges
+            assert_eq!(graph.get_topo(), &vec![0, 1, 2, 3]);
+        });
+    }
+
+    #[test]
+    fn graph_from_body_with_cleanup() {
+        rustc_span::create_default_session_globals_then(|| {
+            let graph = Graph::from_body_with_cleanup(&small_body(), true);
+            assert_eq!(graph.get_edges(), &vec![vec![1, 4], vec![2, 4], vec![3], vec![], vec![]]);
+            assert_eq!(graph.get_pre(), &vec![vec![], vec![0], vec![1], vec![2], vec![0, 1]]);
+            assert_eq!(graph.edge_kind(0, 4), Some(EdgeKind::Cleanup));
+            assert_eq!(graph.edge_kind(1, 4), Some(EdgeKind::Cleanup));
+            assert_eq!(graph.get_topo().len(), 5);
+            assert_eq!(graph.get_topo()[0], 0);
+        });
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let text = "0 -> 1,2; 1 -> 3; 2 -> 3";
+        let graph = Graph::from_text(text).unwrap();
+        assert_eq!(graph.to_text(), text);
+        assert_eq!(Graph::from_text(&graph.to_text()).unwrap().get_edges(), graph.get_edges());
+
+        assert_eq!(graph.get_edges(), &vec![vec![1, 2], vec![3], vec![3], vec![]]);
+        assert_eq!(graph.get_pre(), &vec![vec![], vec![0], vec![0], vec![1, 2]]);
+        assert_eq!(graph.get_topo().len(), 4);
+        assert_eq!(graph.get_topo()[0], 0);
+        assert_eq!(graph.get_topo()[3], 3);
+    }
+
+    #[test]
+    fn text_round_trip_with_dead_block() {
+        // the block 2 has no edge at all, it is kept in the text but is not in the topo order
+        let text = "0 -> 1; 2";
+        let graph = Graph::from_text(text).unwrap();
+        assert_eq!(graph.to_text(), text);
+        assert_eq!(graph.get_edges().len(), 3);
+        assert_eq!(graph.get_pre(), &vec![vec![], vec![0], vec![]]);
+        assert_eq!(graph.get_topo(), &vec![0, 1]);
+    }
+
+    // every node is in the order once, and every edge goes forward unless it is a back edge
+    fn assert_total_order(edges: &Edges, topo: &ToPo, back: &[(usize, usize)]) {
+        let mut sorted = topo.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..edges.len()).collect::<Vec<usize>>());
+        let pos: HashMap<usize, usize> = topo.iter().enumerate().map(|(pos, node)| (*node, pos)).collect();
+        for (node, successors) in edges.iter().enumerate() {
+            for next in successors {
+                assert!(pos[&node] < pos[next] || back.contains(&(node, *next)), "{} -> {} in {:?}", node, next, topo);
+            }
+        }
+    }
+
+    #[test]
+    fn topo_order_of_dag() {
+        let edges: Edges = vec![vec![1, 2], vec![3], vec![3], vec![4], vec![]];
+        let topo = topological_order(&edges, edges.len());
+        assert_total_order(&edges, &topo, &[]);
+        assert_eq!(topo[0], 0);
+        assert_eq!(topo[4], 4);
+        assert!(back_edges(&edges, &topo).is_empty());
+    }
+
+    #[test]
+    fn topo_order_of_disconnected_graph() {
+        // two components, 0 -> 1 and 2 -> 3 -> 4, and the alone node 5
+        let edges: Edges = vec![vec![1], vec![], vec![3], vec![4], vec![], vec![]];
+        let topo = topological_order(&edges, edges.len());
+        assert_total_order(&edges, &topo, &[]);
+        assert!(back_edges(&edges, &topo).is_empty());
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn topo_order_of_cyclic_graph() {
+        // the loop 1 -> 2 -> 3 -> 1 entered from 0 and left to 4, with the self loop on 4
+        let edges: Edges = vec![vec![1], vec![2], vec![3], vec![1, 4], vec![4]];
+        let topo = topological_order(&edges, edges.len());
+        let back = back_edges(&edges, &topo);
+        assert_total_order(&edges, &topo, &back);
+        assert_eq!(topo, vec![0, 1, 2, 3, 4]);
+        assert_eq!(back, vec![(3, 1), (4, 4)]);
+    }
+
+    #[test]
+    fn topo_order_ignores_nodes_out_of_range() {
+        let edges: Edges = vec![vec![1, 7], vec![]];
+        assert_eq!(topological_order(&edges, 2), vec![0, 1]);
+    }
+
+    #[test]
+    fn text_with_bad_block() {
+        let err = Graph::from_text("0 -> x").unwrap_err();
+        assert!(err.contains("invalid block `x`"));
+    }
+}
@@ -1,20 +1,126 @@
 use rustc_middle::ty::Ty;
+use rustc_span::Span;
 
 use crate::analysis::type_analysis::type_visitor::TyWithIndex;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::collections::hash_map::DefaultHasher;
 use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
 
 use z3::ast;
 
+// the program point (topo index of the block, statement index) and the span where a taint is introduced
+pub type TaintOrigin = ((usize, usize), Span);
+
+// The identity of an allocation whose ownership is introduced in the function, it is assigned in order of the
+// first visit of the introducing location (block, statement) and stays the same over the fixpoint iteration.
+// The copies of a pointer refer to the same allocation, so they share the identity with the taint.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct AllocId(usize);
+
+impl AllocId {
+    pub fn new(index: usize) -> Self {
+        Self(index)
+    }
+
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+// Where the ownership carried by a taint comes from: the heap item handed in by a parameter, allocated in the
+// function, or returned by a callee. The leak of the parameter-owned item is usually the fault of the caller.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum TaintSource {
+    Local,
+    CalleeReturn,
+    Param,
+}
+
+impl std::fmt::Display for TaintSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TaintSource::Local => write!(f, "local"),
+            TaintSource::CalleeReturn => write!(f, "callee-return"),
+            TaintSource::Param => write!(f, "param"),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Taint<'tcx> {
     set: HashSet<TyWithIndex<'tcx>>,
+    // the earliest origin of each entry, it is the metadata for reports and not compared
+    origins: HashMap<TyWithIndex<'tcx>, TaintOrigin>,
+    // the sources of the entries, it is the metadata for reports and not compared
+    sources: HashSet<TaintSource>,
+    // the span of the drop if the value is consumed (moved out by drop) on some path,
+    // which is distinct from the value that is never owned
+    consumed: Option<Span>,
+    // the allocations the local refers to (or owns again after the recovery), each of them is identified
+    // by the location where its ownership is taken over by a raw pointer
+    allocs: HashSet<AllocId>,
+    // the span of the drop if the allocations the local refers to are freed on some path
+    freed: Option<Span>,
+    // the entries carried by each field (the index in the layout) of the local, e.g., `s.f = move b`
+    fields: HashMap<usize, HashSet<TyWithIndex<'tcx>>>,
+    // the span of with_capacity (or reserve) if the container owns a buffer that is not handed out on some path,
+    // the buffer is allocated regardless of the element count
+    capacity: Option<Span>,
+    // the span where the guard (the adt with a hand-written drop) is disarmed on some path, its drop does not
+    // free the heap item then, e.g., `guard.disarmed = true` or the branch of `if guard.disarmed`
+    disarmed: Option<Span>,
+}
+
+impl<'tcx> PartialEq for Taint<'tcx> {
+    fn eq(&self, other: &Self) -> bool {
+        self.set == other.set
+            && self.consumed == other.consumed
+            && self.allocs == other.allocs
+            && self.freed == other.freed
+            && self.fields == other.fields
+            && self.capacity == other.capacity
+            && self.disarmed == other.disarmed
+    }
+}
+
+impl<'tcx> Eq for Taint<'tcx> {}
+
+// the taint is an unordered set, thus the hashes of the elements are combined commutatively
+// to keep the hash consistent with the equality of sets
+impl<'tcx> Hash for Taint<'tcx> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut acc: u64 = 0;
+        for elem in self.set.iter() {
+            let mut hasher = DefaultHasher::new();
+            elem.hash(&mut hasher);
+            acc = acc.wrapping_add(hasher.finish());
+        }
+        self.set.len().hash(state);
+        acc.hash(state);
+        self.consumed.hash(state);
+        self.allocs.len().hash(state);
+        self.freed.hash(state);
+        self.fields.len().hash(state);
+        self.capacity.hash(state);
+        self.disarmed.hash(state);
+    }
 }
 
 impl<'tcx> Default for Taint<'tcx> {
     fn default() -> Self {
-        Self { set: HashSet::default() }
+        Self {
+            set: HashSet::default(),
+            origins: HashMap::default(),
+            sources: HashSet::default(),
+            consumed: None,
+            allocs: HashSet::default(),
+            freed: None,
+            fields: HashMap::default(),
+            capacity: None,
+            disarmed: None,
+        }
     }
 }
 
@@ -42,6 +148,164 @@ impl<'tcx> Taint<'tcx> {
     pub fn set_mut(&mut self) -> &mut HashSet<TyWithIndex<'tcx>> {
         &mut self.set
     }
+
+    pub fn insert_with_origin(&mut self, k: TyWithIndex<'tcx>, origin: TaintOrigin, alloc: AllocId, source: TaintSource) {
+        self.set.insert(k.clone());
+        self.allocs.insert(alloc);
+        self.sources.insert(source);
+        self.merge_origin(k, origin);
+    }
+
+    // merge the taint from another path, the earliest origin in topo order is kept for each entry
+    pub fn merge(&mut self, other: &Taint<'tcx>) {
+        for elem in other.set.iter() {
+            self.set.insert(elem.clone());
+        }
+        for (elem, origin) in other.origins.iter() {
+            self.merge_origin(elem.clone(), *origin);
+        }
+        self.sources.extend(other.sources.iter().copied());
+        if self.consumed.is_none() {
+            self.consumed = other.consumed;
+        }
+        self.allocs.extend(other.allocs.iter().copied());
+        if self.freed.is_none() {
+            self.freed = other.freed;
+        }
+        if self.capacity.is_none() {
+            self.capacity = other.capacity;
+        }
+        if self.disarmed.is_none() {
+            self.disarmed = other.disarmed;
+        }
+        for (f, entries) in other.fields.iter() {
+            self.fields.entry(*f).or_default().extend(entries.iter().cloned());
+        }
+    }
+
+    fn merge_origin(&mut self, k: TyWithIndex<'tcx>, origin: TaintOrigin) {
+        match self.origins.get(&k) {
+            Some(old) if old.0 <= origin.0 => (),
+            _ => { self.origins.insert(k, origin); },
+        }
+    }
+
+    pub fn is_consumed(&self) -> bool {
+        self.consumed.is_some()
+    }
+
+    pub fn consumed(&self) -> Option<Span> {
+        self.consumed
+    }
+
+    pub fn set_consumed(&mut self, consumed: Option<Span>) {
+        self.consumed = consumed;
+    }
+
+    pub fn allocs(&self) -> &HashSet<AllocId> {
+        &self.allocs
+    }
+
+    pub fn allocs_mut(&mut self) -> &mut HashSet<AllocId> {
+        &mut self.allocs
+    }
+
+    pub fn freed(&self) -> Option<Span> {
+        self.freed
+    }
+
+    pub fn set_freed(&mut self, freed: Option<Span>) {
+        self.freed = freed;
+    }
+
+    pub fn capacity(&self) -> Option<Span> {
+        self.capacity
+    }
+
+    pub fn set_capacity(&mut self, capacity: Option<Span>) {
+        self.capacity = capacity;
+    }
+
+    pub fn is_disarmed(&self) -> bool {
+        self.disarmed.is_some()
+    }
+
+    pub fn disarmed(&self) -> Option<Span> {
+        self.disarmed
+    }
+
+    pub fn set_disarmed(&mut self, disarmed: Option<Span>) {
+        self.disarmed = disarmed;
+    }
+
+    pub fn insert_field(&mut self, f: usize, entries: &HashSet<TyWithIndex<'tcx>>) {
+        self.fields.entry(f).or_default().extend(entries.iter().cloned());
+    }
+
+    pub fn clear_fields(&mut self) {
+        self.fields.clear();
+    }
+
+    // the taint is field precise if every entry of it is carried by a known field of the local,
+    // otherwise the taint is only known for the whole local (e.g., it is given by a call)
+    pub fn is_field_precise(&self) -> bool {
+        !self.fields.is_empty() && self.set.iter().all(|elem| self.fields.values().any(|entries| entries.contains(elem)))
+    }
+
+    // the part of the taint carried by the field, none if the taint is not field precise
+    pub fn field_taint(&self, f: usize) -> Option<Taint<'tcx>> {
+        if !self.is_field_precise() {
+            return None;
+        }
+        let mut taint = Taint::default();
+        if let Some(entries) = self.fields.get(&f) {
+            for elem in entries.iter() {
+                taint.set.insert(elem.clone());
+                if let Some(origin) = self.origins.get(elem) {
+                    taint.origins.insert(elem.clone(), *origin);
+                }
+            }
+            // the allocations can not be told apart by fields, the field refers to all of them
+            if taint.is_tainted() {
+                taint.sources = self.sources.clone();
+                taint.allocs = self.allocs.clone();
+                taint.freed = self.freed;
+            }
+        }
+        Some(taint)
+    }
+
+    // the field is moved out or dropped, the entries carried only by it leave the local
+    pub fn remove_field(&mut self, f: usize) {
+        let precise = self.is_field_precise();
+        let entries = match self.fields.remove(&f) {
+            Some(entries) => entries,
+            None => return,
+        };
+        if !precise {
+            return;
+        }
+        for elem in entries {
+            if !self.fields.values().any(|other| other.contains(&elem)) {
+                self.set.remove(&elem);
+                self.origins.remove(&elem);
+            }
+        }
+    }
+
+    // the span where the earliest entry of the taint is introduced
+    pub fn origin(&self) -> Option<Span> {
+        self.origins.values().min_by_key(|origin| origin.0).map(|origin| origin.1)
+    }
+
+    pub fn sources(&self) -> &HashSet<TaintSource> {
+        &self.sources
+    }
+
+    // the source for reports, it is the param only if all the entries are handed in by the parameters
+    pub fn source(&self) -> Option<TaintSource> {
+        self.sources.iter().min().copied()
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
@@ -81,6 +345,12 @@ impl<'ctx> IntroVar<'ctx> {
         }
     }
 
+    // two vars are in the same state if both are declared, initialized or unsupported
+    // the concrete bit vector is ignored because every visit of a block generates new ones
+    pub fn is_same_state(&self, other: &IntroVar<'ctx>) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
     pub fn extract(&self) -> ast::BV<'ctx> {
         match self {
             IntroVar::Init(ref ast) => ast.clone(),
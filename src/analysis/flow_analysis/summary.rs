@@ -0,0 +1,134 @@
+use rustc_span::def_id::DefId;
+
+use crate::rlc_info;
+use crate::analysis::flow_analysis::ownership::Taint;
+use crate::components::context::RlcGlobalCtxt;
+use crate::components::fs::rlc_read_to_string;
+use crate::components::log::rlc_error_and_exit;
+
+use std::collections::HashMap;
+
+pub type FnSummaries<'tcx> = HashMap<DefId, FnSummary<'tcx>>;
+
+// the summaries of the functions without mir, keyed by the def path, e.g., `libc::free`
+pub type KnownSummaries<'tcx> = HashMap<String, FnSummary<'tcx>>;
+
+// the summaries of the common std and libc functions shipped with RLC
+const DEFAULT_SUMMARIES: &str = include_str!("summaries.toml");
+
+// The summary of one function in the summaries file, the params are given by their indexes, e.g.,
+//
+// ["libc::free"]
+// consumed = [0]
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KnownSummary {
+    #[serde(default)]
+    consumed: Vec<usize>,
+    #[serde(default)]
+    escaping: Vec<usize>,
+    #[serde(default)]
+    owned_return: bool,
+}
+
+impl KnownSummary {
+    fn to_summary<'tcx>(&self) -> FnSummary<'tcx> {
+        let flags = |params: &Vec<usize>| {
+            let mut flags = vec![false ; params.iter().max().map_or(0, |max| max + 1)];
+            for param in params {
+                flags[*param] = true;
+            }
+            flags
+        };
+        FnSummary::new(flags(&self.consumed), flags(&self.escaping), self.owned_return)
+    }
+}
+
+fn parse_summaries(toml: &str, file: &str) -> HashMap<String, KnownSummary> {
+    match toml::from_str(toml) {
+        Ok(summaries) => summaries,
+        Err(e) => rlc_error_and_exit(format!("Invalid summaries in {}: {}", file, e)),
+    }
+}
+
+// Load the summaries of the functions without mir (the non-generic std functions, the foreign functions, etc.)
+// before the analysis. The call sites consult them if the callee is not analyzed, before falling back to the
+// signature. The summaries in the file given by RLC_SUMMARIES override the default ones of the same def path.
+pub fn load_known_summaries(rcx: &mut RlcGlobalCtxt<'_>, path: Option<&str>) {
+    let mut summaries = parse_summaries(DEFAULT_SUMMARIES, "the default summaries");
+    if let Some(path) = path {
+        let toml = rlc_read_to_string(path, "Failed to read the summaries file");
+        let given = parse_summaries(&toml, path);
+        rlc_info!("{} summaries are loaded from {}", given.len(), path);
+        summaries.extend(given);
+    }
+    for (def_path, summary) in summaries {
+        rcx.known_summaries_mut().insert(def_path, summary.to_summary());
+    }
+}
+
+// The ownership summary of a function computed after its intra-procedural analysis.
+// The call sites consult the summary of the callee rather than the signature only, and the callee
+// without a summary (not analyzed yet, e.g., in a recursive cycle) falls back to the signature.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FnSummary<'tcx> {
+    // the params (raw pointers) whose pointee ownership is taken back by the callee, e.g., `Box::from_raw(p)`
+    consumed: Vec<bool>,
+    // the params whose value escapes into the return value or behind an out-pointer
+    escaping: Vec<bool>,
+    // the return value owns heap items, e.g., Box<T>
+    owned_return: bool,
+    // the ownership carried by the returned raw pointer, e.g., `Box::into_raw(b)` returned to the caller
+    ret_taint: Taint<'tcx>,
+}
+
+impl<'tcx> FnSummary<'tcx> {
+    pub fn new(consumed: Vec<bool>, escaping: Vec<bool>, owned_return: bool) -> Self {
+        Self {
+            consumed,
+            escaping,
+            owned_return,
+            ret_taint: Taint::default(),
+        }
+    }
+
+    // the summary of the function whose body is not analyzed (e.g., an `unsafe fn` skipped by RLC_SKIP_UNSAFE),
+    // it takes over all the args and returns nothing owned
+    pub fn opaque(params: usize) -> Self {
+        Self::new(vec![true ; params], vec![false ; params], false)
+    }
+
+    pub fn with_ret_taint(mut self, ret_taint: Taint<'tcx>) -> Self {
+        self.ret_taint = ret_taint;
+        self
+    }
+
+    pub fn consumed(&self) -> &Vec<bool> {
+        &self.consumed
+    }
+
+    pub fn escaping(&self) -> &Vec<bool> {
+        &self.escaping
+    }
+
+    pub fn is_consumed(&self, param: usize) -> bool {
+        self.consumed.get(param).copied().unwrap_or(false)
+    }
+
+    pub fn is_escaping(&self, param: usize) -> bool {
+        self.escaping.get(param).copied().unwrap_or(false)
+    }
+
+    pub fn is_owned_return(&self) -> bool {
+        self.owned_return
+    }
+
+    // the ownership of the param is transferred to the callee: freed or moved into the owned return value
+    pub fn takes_ownership(&self, param: usize) -> bool {
+        self.is_consumed(param) || (self.is_escaping(param) && self.is_owned_return())
+    }
+
+    pub fn ret_taint(&self) -> &Taint<'tcx> {
+        &self.ret_taint
+    }
+}
@@ -2,14 +2,21 @@ pub mod connect;
 pub mod type_visitor;
 pub mod ownership;
 
-use rustc_middle::ty::{Ty, TyCtxt};
+use rustc_middle::ty::{self, Ty, TyCtxt, TyKind, TypeVisitable};
+use rustc_ast::Mutability;
+use rustc_middle::mir::{BasicBlock, Body, Operand, Place, ProjectionElem, Rvalue, StatementKind, TerminatorKind, UnOp};
 use rustc_span::def_id::DefId;
+use rustc_span::sym;
 
 use crate::rlc_info;
 use crate::analysis::RcxMut;
 use crate::analysis::flow_analysis::FlowAnalysis;
-use crate::analysis::type_analysis::ownership::RawTypeOwner;
+use crate::analysis::type_analysis::ownership::{ContainerOwner, RawTypeOwner, SharedOwner};
+use crate::analysis::type_analysis::type_visitor::mir_body;
 use crate::components::context::RlcGlobalCtxt;
+use crate::RLC_MAX_TYPE_DEPTH;
+
+use rustc_data_structures::fx::FxHashSet;
 
 use std::collections::{HashMap, HashSet};
 use std::env;
@@ -17,12 +24,14 @@ use std::env;
 use stopwatch::Stopwatch;
 
 type TyMap<'tcx> = HashMap<Ty<'tcx>, String>;
-type OwnerUnit = (RawTypeOwner, Vec<bool>);
+pub type OwnerUnit = (RawTypeOwner, Vec<bool>);
 pub type AdtOwner = HashMap<DefId, Vec<OwnerUnit>>;
 type Parameters = HashSet<usize>;
-pub type Unique = HashSet<DefId>;
+pub type Unique = FxHashSet<DefId>;
 pub type OwnershipLayout = Vec<RawTypeOwner>;
 pub type RustBV = Vec<bool>;
+// the container instances (e.g., Vec<Box<i32>>) and the ownership of their elements
+pub type ContainerOwners<'tcx> = HashMap<Ty<'tcx>, ContainerOwner>;
 
 // Type Analysis is the first step and it will perform a simple-inter-procedural analysis
 // for current crate and collect types after monomorphism as well as extracting 'adt-def'.
@@ -33,18 +42,32 @@ pub struct TypeAnalysis<'tcx, 'a> {
     fn_set: Unique,
     ty_map: TyMap<'tcx>,
     adt_recorder: Unique,
+    // the depth of nested adts in current visit and the maximum depth to expand
+    depth: usize,
+    max_depth: usize,
 }
 
 impl<'tcx, 'a> TypeAnalysis<'tcx, 'a> {
     pub fn new(rcx: &'a mut RlcGlobalCtxt<'tcx>) -> Self {
         Self {
             rcx,
-            fn_set: HashSet::new(),
+            fn_set: Unique::default(),
             ty_map: HashMap::new(),
-            adt_recorder: HashSet::new(),
+            adt_recorder: Unique::default(),
+            depth: 0,
+            max_depth: RLC_MAX_TYPE_DEPTH,
         }
     }
 
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
     pub fn ty_map(&self) -> &TyMap<'tcx> {
         &self.ty_map
     }
@@ -82,6 +105,7 @@ impl<'tcx, 'a> TypeAnalysis<'tcx, 'a> {
     pub fn start(&mut self) {
 
         let mut sw = Stopwatch::start_new();
+        let _span = tracing::info_span!("type_analysis").entered();
 
         // Get the analysis result from rlc phase llvm
         // self.connect();
@@ -91,6 +115,7 @@ impl<'tcx, 'a> TypeAnalysis<'tcx, 'a> {
         //rlc_info!("AdtDef Sum:{:?}", self.adt_owner().len());
         //rlc_info!("Tymap Sum:{:?}", self.ty_map().len());
         // rlc_info!("@@@@@@@@@@@@@Type Analysis:{:?}", sw.elapsed_ms());
+        self.rcx_mut().metrics_mut().add_time_type(sw.elapsed_ms());
         sw.stop();
     }
 }
@@ -196,7 +221,7 @@ impl<'tcx, 'a> RawGenericPropagation<'tcx, 'a> {
         Self {
             tcx,
             record,
-            unique: HashSet::new(),
+            unique: Unique::default(),
             source_enum,
             ref_adt_owner,
         }
@@ -250,7 +275,7 @@ impl<'tcx, 'a> OwnerPropagation<'tcx, 'a> {
         Self {
             tcx,
             ownership,
-            unique: HashSet::new(),
+            unique: Unique::default(),
             ref_adt_owner,
         }
     }
@@ -285,6 +310,12 @@ pub struct DefaultOwnership<'tcx, 'a> {
     res: RawTypeOwner,
     param: bool,
     ptr: bool,
+    // the type re-enters an adt on the stack, the expansion stops there
+    recursive: bool,
+    // the type exceeds the max depth, the deeper generic args are not expanded and the layout is approximated
+    truncated: bool,
+    depth: usize,
+    max_depth: usize,
 }
 
 impl<'tcx, 'a> DefaultOwnership<'tcx, 'a> {
@@ -295,14 +326,23 @@ impl<'tcx, 'a> DefaultOwnership<'tcx, 'a> {
     {
         Self {
             tcx,
-            unique: HashSet::new(),
+            unique: Unique::default(),
             ref_adt_owner,
             res: RawTypeOwner::Unowned,
             param: false,
             ptr: false,
+            recursive: false,
+            truncated: false,
+            depth: 0,
+            max_depth: usize::MAX,
         }
     }
 
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
     pub fn tcx(&self) -> TyCtxt<'tcx> {
         self.tcx
     }
@@ -351,10 +391,37 @@ impl<'tcx, 'a> DefaultOwnership<'tcx, 'a> {
         self.ptr == true
     }
 
+    pub fn is_recursive(&self) -> bool {
+        self.recursive
+    }
+
+    pub fn set_recursive(&mut self, r: bool) {
+        self.recursive = r;
+    }
+
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    pub fn set_truncated(&mut self, t: bool) {
+        self.truncated = t;
+    }
+
     pub fn owner(&self) -> &'a AdtOwner {
         self.ref_adt_owner
     }
 
+    // visit the generic arg of an adt one level deeper, mark the result as truncated if it is too deep
+    pub fn visit_generic_arg(&mut self, ty: Ty<'tcx>) {
+        if self.depth >= self.max_depth {
+            self.set_truncated(true);
+            return;
+        }
+        self.depth += 1;
+        ty.visit_with(self);
+        self.depth -= 1;
+    }
+
 }
 
 #[derive(Clone)]
@@ -397,6 +464,430 @@ impl<'tcx> FindPtr<'tcx> {
     }
 }
 
+// Find whether the given adt can reach itself through a field of Rc<..>/Arc<..>,
+// e.g., struct Node { next: RefCell<Option<Rc<Node>>> }, which may form a reference cycle.
+#[derive(Clone)]
+pub struct FindRcCycle<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    target: DefId,
+    unique: Unique,
+    shared: Vec<SharedOwner>,
+    cycle: Option<SharedOwner>,
+}
+
+impl<'tcx> FindRcCycle<'tcx> {
+    pub fn new(
+        tcx: TyCtxt<'tcx>,
+        target: DefId,
+    ) -> Self
+    {
+        Self {
+            tcx,
+            target,
+            unique: Unique::default(),
+            shared: Vec::new(),
+            cycle: None,
+        }
+    }
+
+    pub fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    pub fn target(&self) -> DefId {
+        self.target
+    }
+
+    pub fn unique(&self) -> &Unique {
+        &self.unique
+    }
+
+    pub fn unique_mut(&mut self) -> &mut Unique {
+        &mut self.unique
+    }
+
+    pub fn shared(&self) -> &Vec<SharedOwner> {
+        &self.shared
+    }
+
+    pub fn shared_mut(&mut self) -> &mut Vec<SharedOwner> {
+        &mut self.shared
+    }
+
+    pub fn cycle(&self) -> Option<SharedOwner> {
+        self.cycle
+    }
+
+    pub fn set_cycle(&mut self, cycle: SharedOwner) {
+        self.cycle = Some(cycle);
+    }
+}
+
+// The value inside ManuallyDrop<T> is managed by the caller and its heap item will never be dropped
+// automatically, thus the ownership of T is not held by the wrapper.
+pub fn is_manually_drop(tcx: TyCtxt, did: DefId) -> bool {
+    tcx.lang_items().manually_drop() == Some(did)
+}
+
+// The Pin<P> wrapper only restricts the moves of the pointee, it has the same ownership as its pointer P,
+// e.g., Pin<Box<T>> is a Box. All the layers of Pin are peeled off.
+pub fn unwrap_pin<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Ty<'tcx> {
+    let mut ty = ty;
+    while let TyKind::Adt(adtdef, substs) = ty.kind() {
+        if tcx.lang_items().pin_type() != Some(adtdef.did()) {
+            break;
+        }
+        ty = substs.type_at(0);
+    }
+    ty
+}
+
+// Cow<'_, B> is a variant-sensitive owner: Cow::Owned owns `<B as ToOwned>::Owned` (e.g., String for Cow<str>)
+// and Cow::Borrowed owns nothing. The owned type is a projection that the adt layout of Cow can not see, it is
+// normalized for the instance here and kept as the projection if B is still generic.
+pub fn cow_owned_ty<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Option<Ty<'tcx>> {
+    let (adtdef, substs) = match ty.kind() {
+        TyKind::Adt(adtdef, substs) if tcx.is_diagnostic_item(sym::Cow, adtdef.did()) => (adtdef, substs),
+        _ => return None,
+    };
+    let owned = adtdef.variants().iter().find(|variant| variant.name.as_str() == "Owned")?;
+    let owned_ty = owned.fields.first()?.ty(tcx, substs);
+    Some(tcx.try_normalize_erasing_regions(ty::ParamEnv::reveal_all(), owned_ty).unwrap_or(owned_ty))
+}
+
+// the depth of the structs and pointees expanded in the display of a layout, the deeper ones are summarized
+const LAYOUT_DISPLAY_DEPTH: usize = 3;
+
+// The ownership layout of the type for the reports, e.g., `Box<struct Foo { a: owned, b: not-owned }>`. The Box and
+// the structs (and tuples) defined in the current crate are expanded with their fields to explain why the type is
+// owning, the other types are summarized by whether they own a heap item, e.g., `Vec<u8>: owned`.
+pub fn display_layout<'tcx>(tcx: TyCtxt<'tcx>, owner: &AdtOwner, max_depth: usize, ty: Ty<'tcx>) -> String {
+    match expand_layout(tcx, owner, max_depth, ty, 0) {
+        Some(expanded) => expanded,
+        None => format!("{}: {}", ty, owned_word(is_owning(tcx, owner, max_depth, ty))),
+    }
+}
+
+// the expanded layout of the type, none if the type is not expanded
+fn expand_layout<'tcx>(tcx: TyCtxt<'tcx>, owner: &AdtOwner, max_depth: usize, ty: Ty<'tcx>, depth: usize) -> Option<String> {
+    if depth >= LAYOUT_DISPLAY_DEPTH {
+        return None;
+    }
+    let field = |field_ty: Ty<'tcx>| {
+        expand_layout(tcx, owner, max_depth, field_ty, depth + 1)
+            .unwrap_or_else(|| owned_word(is_owning(tcx, owner, max_depth, field_ty)).to_string())
+    };
+    match ty.kind() {
+        TyKind::Adt(..) if ty.is_box() => {
+            let pointee = ty.boxed_ty();
+            let inner = expand_layout(tcx, owner, max_depth, pointee, depth + 1).unwrap_or_else(|| pointee.to_string());
+            Some(format!("Box<{}>", inner))
+        },
+        TyKind::Adt(adtdef, substs) if adtdef.is_struct() && adtdef.did().is_local() => {
+            let fields: Vec<String> = adtdef
+                .all_fields()
+                .map(|f| format!("{}: {}", f.name, field(f.ty(tcx, substs))))
+                .collect();
+            if fields.is_empty() {
+                return Some(format!("struct {}", tcx.item_name(adtdef.did())));
+            }
+            Some(format!("struct {} {{ {} }}", tcx.item_name(adtdef.did()), fields.join(", ")))
+        },
+        TyKind::Tuple(tys) if !tys.is_empty() => {
+            let fields: Vec<String> = tys.iter().map(field).collect();
+            Some(format!("({})", fields.join(", ")))
+        },
+        TyKind::RawPtr(typeandmut) => {
+            let pointee = typeandmut.ty;
+            let inner = expand_layout(tcx, owner, max_depth, pointee, depth + 1).unwrap_or_else(|| pointee.to_string());
+            let mutbl = match typeandmut.mutbl {
+                Mutability::Mut => "mut",
+                Mutability::Not => "const",
+            };
+            Some(format!("*{} {}", mutbl, inner))
+        },
+        _ => None,
+    }
+}
+
+fn is_owning<'tcx>(tcx: TyCtxt<'tcx>, owner: &AdtOwner, max_depth: usize, ty: Ty<'tcx>) -> bool {
+    let mut default_ownership = DefaultOwnership::new(tcx, owner).with_max_depth(max_depth);
+    ty.visit_with(&mut default_ownership);
+    default_ownership.is_owning_true()
+}
+
+fn owned_word(owned: bool) -> &'static str {
+    if owned { "owned" } else { "not-owned" }
+}
+
+// The def path of the item with the facade crates spelled as std, e.g., `core::mem::take` and `alloc::rc::Weak`
+// seen from a no_std crate are `std::mem::take` and `std::rc::Weak`, so the items are recognized by one path
+// whichever of std, alloc and core is linked. The item missing in the crate graph (e.g., `Box` in a no_std crate
+// without alloc) never has a DefId to look up, thus the recognition of it simply fails rather than panics.
+pub fn std_def_path(tcx: TyCtxt, did: DefId) -> String {
+    let path = tcx.def_path_str(did);
+    match path.strip_prefix("core::").or_else(|| path.strip_prefix("alloc::")) {
+        Some(rest) => format!("std::{}", rest),
+        None => path,
+    }
+}
+
+// The functions mem::forget, ManuallyDrop::new and the leak methods (Box::leak, Vec::leak and String::leak)
+// are used to leak the value deliberately.
+pub fn is_intentional_leak_fn(tcx: TyCtxt, did: DefId) -> bool {
+    if tcx.is_diagnostic_item(sym::mem_forget, did) {
+        return true;
+    }
+
+    match tcx.impl_of_method(did) {
+        Some(impl_did) => {
+            let adt_did = match tcx.type_of(impl_did).kind() {
+                TyKind::Adt(adtdef, ..) => adtdef.did(),
+                _ => return false,
+            };
+            let name = tcx.item_name(did);
+            if is_manually_drop(tcx, adt_did) {
+                return name == sym::new;
+            }
+            let is_leak_impl = tcx.lang_items().owned_box() == Some(adt_did)
+                || tcx.is_diagnostic_item(sym::Vec, adt_did)
+                || tcx.is_diagnostic_item(sym::String, adt_did);
+            is_leak_impl && name.as_str() == "leak"
+        },
+        None => false,
+    }
+}
+
+// The functions terminating the process without unwinding the stack, e.g., process::exit and abort.
+// No destructor runs after them, so the ownership held at the call is given up deliberately.
+pub fn is_process_exit_fn(tcx: TyCtxt, did: DefId) -> bool {
+    matches!(
+        std_def_path(tcx, did).as_str(),
+        "std::process::exit" | "std::process::abort" | "std::intrinsics::abort"
+            | "libc::exit" | "libc::_exit" | "libc::abort"
+    )
+}
+
+// The functions mem::replace, mem::take and Option::take move the old value behind the reference out
+// to the caller and leave a new one (the given value, the default or None) there.
+pub fn is_replace_fn(tcx: TyCtxt, did: DefId) -> bool {
+    if tcx.is_diagnostic_item(sym::mem_replace, did) {
+        return true;
+    }
+
+    if std_def_path(tcx, did) == "std::mem::take" {
+        return true;
+    }
+
+    match tcx.impl_of_method(did) {
+        Some(impl_did) => {
+            let is_option_impl = match tcx.type_of(impl_did).kind() {
+                TyKind::Adt(adtdef, ..) => tcx.is_diagnostic_item(sym::Option, adtdef.did()),
+                _ => false,
+            };
+            is_option_impl && tcx.item_name(did).as_str() == "take"
+        },
+        None => false,
+    }
+}
+
+// The functions ptr::write and ptr::write_unaligned (and the methods of raw pointers with the same names)
+// overwrite the pointee without dropping it, unlike the assignment that drops the old value by the drop glue.
+pub fn is_ptr_write_fn(tcx: TyCtxt, did: DefId) -> bool {
+    if matches!(std_def_path(tcx, did).as_str(), "std::ptr::write" | "std::ptr::write_unaligned") {
+        return true;
+    }
+
+    match tcx.impl_of_method(did) {
+        Some(impl_did) => {
+            tcx.type_of(impl_did).is_unsafe_ptr()
+                && matches!(tcx.item_name(did).as_str(), "write" | "write_unaligned")
+        },
+        None => false,
+    }
+}
+
+// The weak pointers of Rc/Arc (std::rc::Weak and std::sync::Weak) do not keep the allocation alive,
+// thus a reference cycle through them is broken, e.g., the back-pointer from a child to its parent.
+pub fn is_weak_ty<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> bool {
+    match ty.kind() {
+        TyKind::Adt(adtdef, ..) => matches!(
+            std_def_path(tcx, adtdef.did()).as_str(),
+            "std::rc::Weak" | "std::sync::Weak"
+        ),
+        _ => false,
+    }
+}
+
+// the body of `<T as Drop>::drop`, i.e., the hand-written destructor run by the drop glue
+pub fn is_drop_method(tcx: TyCtxt<'_>, did: DefId) -> bool {
+    let impl_did = match tcx.impl_of_method(did) {
+        Some(impl_did) => impl_did,
+        None => return false,
+    };
+    match (tcx.trait_id_of_impl(impl_did), tcx.lang_items().drop_trait()) {
+        (Some(trait_did), Some(drop_did)) => trait_did == drop_did,
+        _ => false,
+    }
+}
+
+// The methods of the std containers allocating the buffer ahead of the elements, the buffer is owned
+// even if no element is pushed, e.g., Vec::with_capacity(16) or v.reserve(16). The ones called on an existing
+// container reallocate its buffer: the old allocation is freed by the method and a new one is owned.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum CapacityFnKind {
+    // the returned container owns the buffer
+    WithCapacity,
+    // the container behind the first arg (&mut self) owns the buffer
+    Reserve,
+    // the buffer of the container behind the first arg (&mut self) is reallocated to fit the elements,
+    // it is freed if the container is empty
+    Shrink,
+}
+
+pub fn capacity_fn_kind(tcx: TyCtxt, did: DefId) -> Option<CapacityFnKind> {
+    let impl_did = tcx.impl_of_method(did)?;
+    let is_container = match tcx.type_of(impl_did).kind() {
+        TyKind::Adt(adtdef, ..) => {
+            tcx.is_diagnostic_item(sym::Vec, adtdef.did())
+                || tcx.is_diagnostic_item(sym::VecDeque, adtdef.did())
+                || tcx.is_diagnostic_item(sym::String, adtdef.did())
+                || tcx.is_diagnostic_item(sym::HashMap, adtdef.did())
+        },
+        _ => false,
+    };
+    if !is_container {
+        return None;
+    }
+    match tcx.item_name(did).as_str() {
+        "with_capacity" | "with_capacity_in" | "with_capacity_and_hasher" => Some(CapacityFnKind::WithCapacity),
+        "reserve" | "reserve_exact" => Some(CapacityFnKind::Reserve),
+        "shrink_to_fit" | "shrink_to" => Some(CapacityFnKind::Shrink),
+        _ => None,
+    }
+}
+
+// The flag disarming the hand-written drop of a guard, e.g., `if !self.disarmed { free(self.ptr) }`.
+// It is the bool field of self switched on by Drop::drop where one branch reaches no call (nothing is freed),
+// the index of the field and the value of it disarming the drop are returned.
+pub fn drop_guard_flag(tcx: TyCtxt, adt_did: DefId) -> Option<(usize, bool)> {
+    let dtor = tcx.adt_destructor(adt_did)?;
+    if !dtor.did.is_local() {
+        return None;
+    }
+    let body = mir_body(tcx, dtor.did);
+
+    for data in body.basic_blocks().iter() {
+        let (discr, targets) = match &data.terminator().kind {
+            TerminatorKind::SwitchInt { discr: Operand::Move(place) | Operand::Copy(place), targets, .. } => (place, targets),
+            _ => continue,
+        };
+
+        // trace the switched local back to the field of self, it may be negated, e.g., `_2 = Not(move _3)`
+        let mut place = *discr;
+        let mut negated = false;
+        for stmt in data.statements.iter().rev() {
+            if self_bool_field(&place).is_some() {
+                break;
+            }
+            let rvalue = match &stmt.kind {
+                StatementKind::Assign(box(lplace, rvalue)) if *lplace == place => rvalue,
+                _ => continue,
+            };
+            match rvalue {
+                Rvalue::UnaryOp(UnOp::Not, Operand::Move(rplace) | Operand::Copy(rplace)) => {
+                    negated = !negated;
+                    place = *rplace;
+                },
+                Rvalue::Use(Operand::Move(rplace) | Operand::Copy(rplace)) => place = *rplace,
+                _ => break,
+            }
+        }
+        let field = match self_bool_field(&place) {
+            Some(field) => field,
+            None => continue,
+        };
+
+        let false_frees = reaches_call(body, targets.target_for_value(0));
+        let true_frees = reaches_call(body, targets.otherwise());
+        let disarm = match (false_frees, true_frees) {
+            (false, true) => false,
+            (true, false) => true,
+            _ => continue,
+        };
+        return Some((field, disarm ^ negated));
+    }
+    None
+}
+
+// the bool field of self in Drop::drop, i.e., `(*_1).f`
+fn self_bool_field(place: &Place) -> Option<usize> {
+    match place.projection.as_ref() {
+        [ProjectionElem::Deref, ProjectionElem::Field(field, ty)] if place.local.as_usize() == 1 && ty.is_bool() => {
+            Some(field.index())
+        },
+        _ => None,
+    }
+}
+
+// whether a call (or a drop) is reachable from the block on the normal path
+fn reaches_call(body: &Body, start: BasicBlock) -> bool {
+    let mut visited = HashSet::new();
+    let mut worklist = vec![start];
+    while let Some(bb) = worklist.pop() {
+        if !visited.insert(bb) {
+            continue;
+        }
+        let data = &body.basic_blocks()[bb];
+        if data.is_cleanup {
+            continue;
+        }
+        match &data.terminator().kind {
+            TerminatorKind::Call { .. } | TerminatorKind::Drop { .. } | TerminatorKind::DropAndReplace { .. } => return true,
+            _ => worklist.extend(data.terminator().successors()),
+        }
+    }
+    false
+}
+
+// The manual heap management through the allocator API, the returned pointer owns the allocation
+// until it is passed back to dealloc (or realloc which frees the old one and returns a new one).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum AllocFnKind {
+    Alloc,
+    Dealloc,
+    Realloc,
+}
+
+// get the kind of the allocator function and the index of its pointer arg (if any)
+// it covers the free functions in std::alloc and the methods of GlobalAlloc (e.g., System.alloc(layout)),
+// which are both independent of the #[global_allocator] in use
+pub fn alloc_fn_kind(tcx: TyCtxt, did: DefId) -> Option<(AllocFnKind, usize)> {
+    let (name, ptr_idx) = match tcx.trait_of_item(did) {
+        Some(trait_did) => {
+            if std_def_path(tcx, trait_did) != "std::alloc::GlobalAlloc" {
+                return None;
+            }
+            // the self of the method comes first
+            (tcx.item_name(did).to_string(), 1)
+        },
+        None => {
+            let path = std_def_path(tcx, did);
+            match path.strip_prefix("std::alloc::") {
+                Some(name) => (name.to_string(), 0),
+                None => return None,
+            }
+        },
+    };
+
+    match name.as_str() {
+        "alloc" | "alloc_zeroed" => Some((AllocFnKind::Alloc, ptr_idx)),
+        "dealloc" => Some((AllocFnKind::Dealloc, ptr_idx)),
+        "realloc" => Some((AllocFnKind::Realloc, ptr_idx)),
+        _ => None,
+    }
+}
+
 #[derive(Debug, Copy, Clone, Hash)]
 pub enum AdtOwnerDisplay {
     Verbose,
@@ -1,10 +1,11 @@
-use rustc_middle::ty::Ty;
+use rustc_middle::ty::{Ty, TyCtxt, TyKind};
+use rustc_span::sym;
 
-use crate::analysis::type_analysis::{DefaultOwnership, OwnershipLayout};
+use crate::analysis::type_analysis::{self, DefaultOwnership, OwnershipLayout};
 
 use std::fmt::Debug;
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum RawTypeOwner {
     Owned,
     Unowned,
@@ -27,17 +28,135 @@ impl RawTypeOwner {
     }
 }
 
+// The shared ownership held by reference counting pointers, which is distinct from the unique
+// ownership (like Box) that the flow analysis reasons about. They can leak by forming a cycle.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum SharedOwner {
+    Rc,
+    Arc,
+}
+
+impl SharedOwner {
+    pub fn from_ty<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Option<Self> {
+        match ty.kind() {
+            TyKind::Adt(adtdef, ..) => {
+                if tcx.is_diagnostic_item(sym::Rc, adtdef.did()) {
+                    Some(SharedOwner::Rc)
+                } else if tcx.is_diagnostic_item(sym::Arc, adtdef.did()) {
+                    Some(SharedOwner::Arc)
+                } else {
+                    None
+                }
+            },
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for SharedOwner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SharedOwner::Rc => write!(f, "Rc"),
+            SharedOwner::Arc => write!(f, "Arc"),
+        }
+    }
+}
+
+// The standard containers own their heap buffer as well as the elements inside it,
+// so leaking the container also leaks all the element allocations.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ContainerKind {
+    Vec,
+    VecDeque,
+    HashMap,
+    BTreeMap,
+    BoxedSlice,
+}
+
+impl ContainerKind {
+    // get the kind of container and the element types (e.g., K and V for HashMap<K, V>)
+    pub fn from_ty<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Option<(Self, Vec<Ty<'tcx>>)> {
+        let ty = type_analysis::unwrap_pin(tcx, ty);
+        let (adtdef, substs) = match ty.kind() {
+            TyKind::Adt(adtdef, substs) => (adtdef, substs),
+            _ => return None,
+        };
+
+        if ty.is_box() {
+            return match ty.boxed_ty().kind() {
+                TyKind::Slice(elem_ty) => Some((ContainerKind::BoxedSlice, vec![*elem_ty])),
+                _ => None,
+            };
+        }
+
+        let kind = if tcx.is_diagnostic_item(sym::Vec, adtdef.did()) {
+            ContainerKind::Vec
+        } else if tcx.is_diagnostic_item(sym::VecDeque, adtdef.did()) {
+            ContainerKind::VecDeque
+        } else if tcx.is_diagnostic_item(sym::HashMap, adtdef.did()) {
+            ContainerKind::HashMap
+        } else if tcx.is_diagnostic_item(sym::BTreeMap, adtdef.did()) {
+            ContainerKind::BTreeMap
+        } else {
+            return None;
+        };
+
+        let elems = match kind {
+            ContainerKind::HashMap | ContainerKind::BTreeMap => substs.types().take(2).collect(),
+            _ => substs.types().take(1).collect(),
+        };
+        Some((kind, elems))
+    }
+}
+
+impl std::fmt::Display for ContainerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContainerKind::Vec => write!(f, "Vec"),
+            ContainerKind::VecDeque => write!(f, "VecDeque"),
+            ContainerKind::HashMap => write!(f, "HashMap"),
+            ContainerKind::BTreeMap => write!(f, "BTreeMap"),
+            ContainerKind::BoxedSlice => write!(f, "Box<[T]>"),
+        }
+    }
+}
+
+// The ownership of a container instance: the container allocation and the ownership of its elements.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ContainerOwner {
+    kind: ContainerKind,
+    element: RawTypeOwner,
+}
+
+impl ContainerOwner {
+    pub fn new(kind: ContainerKind, element: RawTypeOwner) -> Self {
+        Self { kind, element }
+    }
+
+    pub fn kind(&self) -> ContainerKind {
+        self.kind
+    }
+
+    pub fn element(&self) -> RawTypeOwner {
+        self.element
+    }
+}
+
 pub enum TypeOwner<'tcx> {
     Owned(Ty<'tcx>),
     Unowned,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct OwnershipLayoutResult {
     layout: OwnershipLayout,
     param: bool,
     requirement: bool,
     owned: bool,
+    // the marker of recursive owned type, the layout of the recursive part is not expanded
+    recursive: bool,
+    // the marker of the layout cut at the max depth, the ownership deeper than it is approximated
+    truncated: bool,
 }
 
 impl OwnershipLayoutResult {
@@ -47,9 +166,27 @@ impl OwnershipLayoutResult {
             param: false,
             requirement: false,
             owned: false,
+            recursive: false,
+            truncated: false,
         }
     }
 
+    pub fn is_recursive(&self) -> bool {
+        self.recursive
+    }
+
+    pub fn set_recursive(&mut self, r: bool) {
+        self.recursive = r;
+    }
+
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    pub fn set_truncated(&mut self, t: bool) {
+        self.truncated = t;
+    }
+
     pub fn layout(&self) -> &OwnershipLayout {
         &self.layout
     }
@@ -104,6 +241,14 @@ impl OwnershipLayoutResult {
             self.set_owned(true);
         }
 
+        if default_ownership.is_recursive() {
+            self.set_recursive(true);
+        }
+
+        if default_ownership.is_truncated() {
+            self.set_truncated(true);
+        }
+
         self.layout_mut().push(default_ownership.get_res());
 
         self.set_param(default_ownership.get_param());
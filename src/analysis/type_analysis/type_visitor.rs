@@ -4,26 +4,140 @@ use rustc_middle::ty::subst::GenericArgKind;
 use rustc_middle::mir::visit::{Visitor, TyContext};
 use rustc_middle::mir::{Body, BasicBlock, BasicBlockData, Local, LocalDecl, Operand, TerminatorKind};
 use rustc_span::def_id::DefId;
+use rustc_hir::def::DefKind;
 use rustc_target::abi::VariantIdx;
 
 use crate::components::display::{self, Display};
 use crate::analysis::RcxMut;
 use crate::analysis::type_analysis::{self, TypeAnalysis, OwnerPropagation, RawGeneric,
                                      RawGenericFieldSubst, RawGenericPropagation, RawTypeOwner,
-                                     DefaultOwnership, FindPtr};
+                                     DefaultOwnership, FindPtr, FindRcCycle};
+use crate::analysis::type_analysis::ownership::{ContainerKind, ContainerOwner, SharedOwner};
+use crate::components::report::LeakReport;
+use crate::rlc_info;
 
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::ops::ControlFlow;
 
 use colorful::{Color, Colorful};
 use stopwatch::Stopwatch;
 
+// The phase of the MIR to analyze. The built MIR and the analysis MIR (the one seen by the borrow checker, after
+// the promotion of constants) keep a drop terminator at each scope exit of an owned local, and such a drop is
+// conditional, i.e., it frees nothing if the local is moved. The optimized MIR is smaller, but the drop elaboration
+// and the optimizations remove the drops of the moved locals and may merge the blocks, thus it is less faithful to
+// the drop semantics of the source. The bodies of the other crates are only available as the optimized MIR.
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum MirPhase {
+    Built,
+    Analysis,
+    Optimized,
+}
+
+impl MirPhase {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "built" => Some(MirPhase::Built),
+            "analysis" => Some(MirPhase::Analysis),
+            "optimized" => Some(MirPhase::Optimized),
+            _ => None,
+        }
+    }
+
+    pub fn is_optimized(&self) -> bool {
+        *self == MirPhase::Optimized
+    }
+}
+
+impl std::fmt::Display for MirPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MirPhase::Built => write!(f, "built"),
+            MirPhase::Analysis => write!(f, "analysis"),
+            MirPhase::Optimized => write!(f, "optimized"),
+        }
+    }
+}
+
+thread_local! {
+    // the bodies of the local crate in the phase before the optimized one, they are copied out by the query
+    // providers of rlc before being stolen by the later phases, together with the session of the tcx owning them
+    static PHASE_BODIES: RefCell<(usize, HashMap<DefId, &'static Body<'static>>)> = RefCell::new((0, HashMap::new()));
+}
+
+// the key of the session of tcx, the bodies recorded in another session are never read back
+fn session_key(tcx: TyCtxt) -> usize {
+    tcx.sess as *const _ as usize
+}
+
+/// Keep a copy of the body in the arena of tcx, it is called by the query providers overridden in the driver.
+///
+/// # Safety
+///
+/// The copy lives in the arena of tcx but it is kept in a thread-local map as `'static`. The caller must call
+/// `clear_mir_bodies` on the same thread before tcx is dropped, i.e., at the end of the session.
+pub unsafe fn record_mir_body<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId, body: &Body<'tcx>) {
+    let body: &'tcx Body<'tcx> = tcx.arena.alloc(body.clone());
+    let body = std::mem::transmute::<&'tcx Body<'tcx>, &'static Body<'static>>(body);
+    let key = session_key(tcx);
+    PHASE_BODIES.with(|bodies| {
+        let mut bodies = bodies.borrow_mut();
+        // the bodies left by a former session on this thread are dropped
+        if bodies.0 != key {
+            *bodies = (key, HashMap::new());
+        }
+        bodies.1.insert(def_id, body);
+    });
+}
+
+// drop the bodies recorded in the session, it is called by the driver once the analysis is done
+pub fn clear_mir_bodies() {
+    PHASE_BODIES.with(|bodies| *bodies.borrow_mut() = (0, HashMap::new()));
+}
+
+fn recorded_mir_body(tcx: TyCtxt<'_>, def_id: DefId) -> Option<&Body<'_>> {
+    let key = session_key(tcx);
+    PHASE_BODIES.with(|bodies| {
+        let bodies = bodies.borrow();
+        if bodies.0 == key { bodies.1.get(&def_id).copied() } else { None }
+    })
+    // the body is read back within the session of tcx, its arena is still alive
+    .map(|body| unsafe { std::mem::transmute::<&'static Body<'static>, &Body<'_>>(body) })
+}
+
+// the body recorded in the selected phase is preferred, otherwise the const-like bodies (const, static and
+// anon const) get the mir_for_ctfe from instance_mir, while the fn-like bodies get the optimized_mir
 pub(crate) fn mir_body(tcx: TyCtxt, def_id: DefId) -> &Body {
+    if let Some(body) = recorded_mir_body(tcx, def_id) {
+        return body;
+    }
     let id = ty::WithOptConstParam::unknown(def_id);
     let def = ty::InstanceDef::Item(id);
     tcx.instance_mir(def)
 }
 
+// The bodies analyzed by rlc are the fn-like bodies and the initializers of const, static and anon const.
+// The promoteds are not analyzed as separate bodies, since a promoted constant never allocates on the heap.
+pub(crate) fn is_analyzable_body(tcx: TyCtxt, def_id: DefId) -> bool {
+    if !tcx.is_mir_available(def_id) {
+        return false;
+    }
+    matches!(
+        tcx.def_kind(def_id),
+        DefKind::Fn
+            | DefKind::AssocFn
+            | DefKind::Closure
+            | DefKind::Generator
+            | DefKind::Ctor(..)
+            | DefKind::Const
+            | DefKind::AssocConst
+            | DefKind::Static(..)
+            | DefKind::AnonConst
+            | DefKind::InlineConst
+    )
+}
+
 // This function is aiming at resolving problems due to 'TyContext' not implementing 'Clone' trait,
 // thus we call function 'copy_ty_context' to simulate 'self.clone()'.
 #[inline(always)]
@@ -91,6 +205,10 @@ impl<'tcx, 'a> TypeAnalysis<'tcx, 'a> {
         for each_mir in mir_keys {
             // Get the defid of current crate and get mir Body through this id
             let def_id = each_mir.to_def_id();
+            // the body of extern fns, trait method declarations and intrinsics is not available
+            if !is_analyzable_body(tcx, def_id) {
+                continue;
+            }
             let body = mir_body(tcx, def_id);
             show_mir_if_needed(def_id, body);
 
@@ -102,16 +220,61 @@ impl<'tcx, 'a> TypeAnalysis<'tcx, 'a> {
             }
         }
 
-        let dids: Vec<DefId> = self.adt_recorder.iter().map(|did| *did).collect();
+        let dids = self.propagate();
 
-        start_channel(|did| self.extract_raw_generic(did), &dids);
-        start_channel(|did| self.extract_raw_generic_prop(did), &dids);
-        start_channel(|did| self.extract_phantom_unit(did), &dids);
-        start_channel(|did| self.extract_owner_prop(did), &dids);
+        // the rc cycles are not the leaks of unique ownership, only check them if required
+        if self.rcx().config().rc_cycle() {
+            start_channel(|did| self.extract_rc_cycle(did), &dids);
+        }
 
         show_owner_if_needed(self);
     }
 
+    // Load the ownership of the adts in the body that are not collected yet on the first encounter.
+    // The adts of the dependencies are rebuilt from the cross-crate metadata in tcx, e.g., the DepType
+    // of a local Box<DepType> that is only met through the instantiation of a foreign generic fn.
+    pub fn load_adts(&mut self, body: &Body<'tcx>) {
+        for (local, local_decl) in body.local_decls.iter_enumerated() {
+            self.visit_local_decl(local, local_decl);
+        }
+        if self.adt_recorder().iter().any(|did| !self.adt_owner().contains_key(did)) {
+            self.propagate();
+        }
+    }
+
+    // propagate the ownership of the recorded adts, the adts already in the adt owner (e.g., loaded from
+    // the cache of upstream crates) are not extracted again, the sorted adts are returned
+    fn propagate(&mut self) -> Vec<DefId> {
+        // sort the adts to keep the order of analysis (and the findings) stable across runs
+        let tcx = self.tcx();
+        let mut dids: Vec<DefId> = self.adt_recorder.iter().copied().collect();
+        dids.sort_by_cached_key(|did| tcx.def_path_str(*did));
+        let uncached: Vec<DefId> = dids.iter().copied().filter(|did| !self.adt_owner().contains_key(did)).collect();
+
+        for did in uncached.iter() {
+            self.extract_raw_generic(*did);
+        }
+        for did in uncached.iter() {
+            self.extract_raw_generic_prop(*did);
+        }
+        for did in uncached.iter() {
+            self.extract_phantom_unit(*did);
+        }
+        for did in uncached.iter() {
+            self.extract_owner_prop(*did);
+        }
+        for did in dids.iter() {
+            self.extract_custom_drop(*did);
+        }
+
+        let tys: Vec<Ty<'tcx>> = self.ty_map().keys().copied().collect();
+        for ty in tys {
+            self.extract_container_owner(ty);
+        }
+
+        dids
+    }
+
     // Extract params in adt types, the 'param' means one generic parameter acting like 'T', 'A', etc...
     // In the sub-visitor RawGeneric, it will visit the given type recursively, and extract all params.
     //
@@ -312,6 +475,78 @@ impl<'tcx, 'a> TypeAnalysis<'tcx, 'a> {
 
         self.adt_owner_mut().insert(did, v_res);
     }
+
+    // Record the adt defined in current crate that implements Drop by hand, e.g., a RAII wrapper
+    // freeing the FFI allocation through its raw pointer. The drop of such an instance is regarded as
+    // consuming all its fields rather than only the owned ones (the drop glue).
+    #[inline(always)]
+    fn extract_custom_drop(&mut self, did: DefId) {
+        if !did.is_local() {
+            return;
+        }
+        if self.tcx().adt_def(did).destructor(self.tcx()).is_some() {
+            self.rcx_mut().custom_drop_mut().insert(did);
+        }
+    }
+
+    // Record the element ownership of the container instance, e.g., Vec<Box<T>> owns the boxes
+    // as well as its buffer while Vec<i32> only owns the buffer.
+    #[inline(always)]
+    fn extract_container_owner(&mut self, ty: Ty<'tcx>) {
+        let (kind, elems) = match ContainerKind::from_ty(self.tcx(), ty) {
+            Some(container) => container,
+            None => return,
+        };
+
+        let mut element = RawTypeOwner::Unowned;
+        for elem_ty in elems {
+            let mut default_ownership = DefaultOwnership::new(self.tcx(), self.adt_owner())
+                .with_max_depth(self.rcx().config().max_layout_depth());
+            elem_ty.visit_with(&mut default_ownership);
+            if default_ownership.is_owning_true() {
+                element = RawTypeOwner::Owned;
+                break;
+            }
+        }
+
+        self.rcx_mut().container_owner_mut().insert(ty, ContainerOwner::new(kind, element));
+    }
+
+    // Report the adt defined in current crate which can reach itself through Rc/Arc transitively.
+    // Such an adt may form a reference cycle at runtime and the counter will never drop to zero.
+    #[inline(always)]
+    fn extract_rc_cycle(&mut self, did: DefId) {
+        if !did.is_local() {
+            return;
+        }
+
+        let ty = self.tcx().type_of(did);
+        let (adt_def, substs) = match ty.kind() {
+            TyKind::Adt(adt_def, substs) => (adt_def, substs),
+            _ => unreachable!(),
+        };
+
+        let mut find_rc_cycle = FindRcCycle::new(self.tcx(), did);
+        find_rc_cycle.unique_mut().insert(did);
+        for field in adt_def.all_fields() {
+            let field_ty = field.ty(self.tcx(), substs);
+            field_ty.visit_with(&mut find_rc_cycle);
+            if find_rc_cycle.cycle().is_some() {
+                break;
+            }
+        }
+
+        if let Some(shared) = find_rc_cycle.cycle() {
+            let message = format!(
+                "The adt `{}` may leak through a reference cycle of {}",
+                self.tcx().def_path_str(did),
+                shared,
+            );
+            rlc_info!("{}", message);
+            let span = self.tcx().def_span(did);
+            self.rcx_mut().reports_mut().push(LeakReport::new(did, span, None, Vec::new(), message));
+        }
+    }
 }
 
 
@@ -344,6 +579,12 @@ impl<'tcx, 'a> Visitor<'tcx> for TypeAnalysis<'tcx, 'a> {
                 self.ty_map_mut().insert(ty, format!("{:?}", ty));
                 self.adt_recorder_mut().insert(adtdef.did());
 
+                // the polymorphic recursive types generate new types endlessly, stop at the max depth
+                if self.depth >= self.max_depth() {
+                    return;
+                }
+                self.depth += 1;
+
                 for field in adtdef.all_fields() {
                     self.visit_ty(field.ty(self.tcx(), substs) ,copy_ty_context(&ty_context))
                 }
@@ -351,6 +592,8 @@ impl<'tcx, 'a> Visitor<'tcx> for TypeAnalysis<'tcx, 'a> {
                 for ty in substs.types() {
                     self.visit_ty(ty, copy_ty_context(&ty_context));
                 }
+
+                self.depth -= 1;
             },
             TyKind::Array(ty, ..) => {
                 self.visit_ty(*ty, ty_context);
@@ -621,6 +864,60 @@ impl<'tcx> TypeVisitor<'tcx> for FindPtr<'tcx> {
 
 }
 
+impl<'tcx> TypeVisitor<'tcx> for FindRcCycle<'tcx> {
+    type BreakTy = ();
+
+    #[inline(always)]
+    fn visit_ty(&mut self, ty: Ty<'tcx>) -> ControlFlow<Self::BreakTy> {
+        match ty.kind() {
+            TyKind::Adt( adtdef, substs ) => {
+                // the edge through Weak does not keep the pointee alive and can not form a cycle
+                if type_analysis::is_weak_ty(self.tcx(), ty) {
+                    return ControlFlow::CONTINUE;
+                }
+
+                // the target is reached under at least one Rc/Arc, the outermost pointer is reported
+                if adtdef.did() == self.target() && !self.shared().is_empty() {
+                    self.set_cycle(self.shared()[0]);
+                    return ControlFlow::Break(());
+                }
+
+                if let Some(shared) = SharedOwner::from_ty(self.tcx(), ty) {
+                    self.shared_mut().push(shared);
+                    let res = ty.super_visit_with(self);
+                    self.shared_mut().pop();
+                    return res;
+                }
+
+                // the generic args such as Option<T>, RefCell<T>, Vec<T> are always visited,
+                // and the fields are visited once for each adt to step into the nested adts
+                if ty.super_visit_with(self).is_break() {
+                    return ControlFlow::Break(());
+                }
+
+                if !self.unique_mut().insert(adtdef.did()) { return ControlFlow::CONTINUE; }
+
+                for field in adtdef.all_fields() {
+                    let field_ty = field.ty(self.tcx(), substs);
+                    if field_ty.visit_with(self).is_break() {
+                        return ControlFlow::Break(());
+                    }
+                }
+                ControlFlow::CONTINUE
+            },
+            TyKind::Array( .. )
+            | TyKind::Slice( .. )
+            | TyKind::Tuple( .. ) => {
+                ty.super_visit_with(self)
+            },
+            _ => {
+                ControlFlow::CONTINUE
+            },
+        }
+    }
+
+}
+
 impl<'tcx, 'a> TypeVisitor<'tcx> for DefaultOwnership<'tcx, 'a>   {
     type BreakTy = ();
 
@@ -632,14 +929,65 @@ impl<'tcx, 'a> TypeVisitor<'tcx> for DefaultOwnership<'tcx, 'a>   {
     #[inline(always)]
     fn visit_ty(&mut self, ty: Ty<'tcx>) -> ControlFlow<Self::BreakTy> {
 
+        // Pin<P> owns the same as P regardless of whether Pin itself is collected
+        let pointer = type_analysis::unwrap_pin(self.tcx(), ty);
+        if pointer != ty {
+            return self.visit_ty(pointer);
+        }
+
         match ty.kind() {
             TyKind::Adt(adtdef, substs) => {
 
+                // Cow owns the heap item if its owned type does, the instance in Cow::Borrowed is cut by the match arm
+                if let Some(owned_ty) = type_analysis::cow_owned_ty(self.tcx(), ty) {
+                    if let TyKind::Projection( .. ) = owned_ty.kind() {
+                        self.set_param(true);
+                        self.set_res(RawTypeOwner::Owned);
+                        return ControlFlow::Break(());
+                    }
+                    return self.visit_ty(owned_ty);
+                }
+
+                // an enum owns the heap item if any of its variants owns, e.g., Option<Box<T>>
                 if adtdef.is_enum() {
-                    return ControlFlow::Break(());
+                    if !self.unique_mut().insert(adtdef.did()) {
+                        self.set_recursive(true);
+                        return ControlFlow::CONTINUE;
+                    }
+
+                    let variants = match self.owner().get(&adtdef.did()) {
+                        Some(variants) => variants.clone(),
+                        None => return ControlFlow::Break(()),
+                    };
+                    // the generic args are new types (e.g., Option<Option<Box<T>>>), the depth guards them
+                    self.unique_mut().remove(&adtdef.did());
+
+                    for (unit_res, generic_list) in variants {
+                        if unit_res == RawTypeOwner::Owned {
+                            self.set_res(RawTypeOwner::Owned);
+                            return ControlFlow::Break(());
+                        }
+                        for (index, each_generic) in generic_list.iter().enumerate() {
+                            if !*each_generic { continue; }
+                            self.visit_generic_arg(substs[index].expect_ty());
+                            if self.is_owning_true() {
+                                return ControlFlow::Break(());
+                            }
+                        }
+                    }
+
+                    return ControlFlow::CONTINUE;
                 }
 
-                if !self.unique_mut().insert(adtdef.did()) { return ControlFlow::CONTINUE; }
+                // the inner ownership of ManuallyDrop<T> is caller-managed
+                if type_analysis::is_manually_drop(self.tcx(), adtdef.did()) {
+                    return ControlFlow::CONTINUE;
+                }
+
+                if !self.unique_mut().insert(adtdef.did()) {
+                    self.set_recursive(true);
+                    return ControlFlow::CONTINUE;
+                }
 
                 let get_ans = self.owner().get(&adtdef.did()).unwrap();
 
@@ -659,7 +1007,7 @@ impl<'tcx, 'a> TypeVisitor<'tcx> for DefaultOwnership<'tcx, 'a>   {
                             } else {
                                 let subset_ty = substs[index].expect_ty();
                                 self.unique_mut().remove(&adtdef.did());
-                                subset_ty.visit_with(self);
+                                self.visit_generic_arg(subset_ty);
                             }
                         }
                     }
@@ -707,6 +1055,13 @@ impl<'tcx> TyWithIndex<'tcx> {
             TyKind::Tuple( list ) => {
                 TyWithIndex(Some((list.len(), &ty.kind(), None, true)))
             },
+            // the captured upvars of closure and generator are viewed as the fields of a tuple
+            TyKind::Closure(_, substs) => {
+                TyWithIndex(Some((substs.as_closure().upvar_tys().count(), ty.kind(), None, true)))
+            },
+            TyKind::Generator(_, substs, ..) => {
+                TyWithIndex(Some((substs.as_generator().upvar_tys().count(), ty.kind(), None, true)))
+            },
             TyKind::Adt(adtdef, ..) => {
                 if adtdef.is_enum() {
                     if vidx.is_none() { return TyWithIndex(None); }
@@ -733,10 +1088,35 @@ impl<'tcx> TyWithIndex<'tcx> {
             | TyKind::Slice( .. ) => {
                 TyWithIndex(Some((1, &ty.kind(), None, false)))
             },
+            // the trait object behind a fat pointer (e.g., `&*b` for Box<dyn Trait>) is opaque, the ownership
+            // of the heap item is held by the box or the raw pointer itself rather than by the pointee
+            TyKind::Dynamic( .. ) => {
+                TyWithIndex(Some((1, ty.kind(), None, false)))
+            },
             _ => TyWithIndex(None),
         }
     }
 
+    // The variant index (and the field count that depends on it) matters when navigating the fields of an
+    // enum, since each variant has its own layout. It does not matter when asking whether two entries refer to
+    // the same type, e.g., merging the taints at a join point whose paths reach the type in different variants.
+    pub fn same_ty(&self, other: &Self) -> bool {
+        match (self.0, other.0) {
+            (Some((_, kind, ..)), Some((_, other_kind, ..))) => kind == other_kind,
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    pub fn kind(&self) -> Option<&'tcx TyKind<'tcx>> {
+        self.0.map(|(_, kind, ..)| kind)
+    }
+
+    // the bare type without the variant index, the type is re-interned from its kind
+    pub fn ty(&self, tcx: TyCtxt<'tcx>) -> Option<Ty<'tcx>> {
+        self.kind().map(|kind| tcx.mk_ty(kind.clone()))
+    }
+
     // 0->unsupported, 1->trivial, 2-> needed
     pub fn get_priority(&self) -> usize {
         if self.0.is_none() { return 0; }
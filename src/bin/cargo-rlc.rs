@@ -274,12 +274,16 @@ fn rlc_add_env(cmd: &mut Command) {
     if has_rlc_arg_flag("-ADT=V") {
         cmd.env("ADT_DISPLAY", "");
     }
-    if has_rlc_arg_flag("-Z3-GOAL=V") {
+    // the path given by Z3_GOAL is kept, the flag only enables the display
+    if has_rlc_arg_flag("-Z3-GOAL=V") && env::var_os("Z3_GOAL").is_none() {
         cmd.env("Z3_GOAL", "");
     }
     if has_rlc_arg_flag("-ICX-SLICE=V") {
         cmd.env("ICX_SLICE", "");
     }
+    if let Some(timeout) = env::args().skip_while(|val| val == "--").find_map(|val| val.strip_prefix("-Z3-TIMEOUT=").map(ToString::to_string)) {
+        cmd.env("Z3_TIMEOUT", timeout);
+    }
 
 }
 
@@ -676,8 +680,9 @@ impl TargetKind {
 }
 
 fn main() {
-    // Init the log_system for RLC
-    Verbosity::init_rlc_log_system_with_verbosity(Verbosity::Info).expect("Failed to set up RLC log system");
+    // Init the log_system for RLC, the quiet mode (also forwarded to rlc) hides the progress of the phases
+    let verbosity = if has_rlc_arg_flag("-QUIET") || has_rlc_arg_flag("--quiet") { Verbosity::Quiet } else { Verbosity::Info };
+    Verbosity::init_rlc_log_system_with_verbosity(verbosity).expect("Failed to set up RLC log system");
     if let Some("rlc") = env::args().nth(1).as_ref().map(AsRef::as_ref) {
         // `cargo rlc`: call `cargo rustc` for each applicable target,
         // but with the `RUSTC` env var set to the `cargo-rlc` binary so that we come back in the other branch,
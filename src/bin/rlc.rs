@@ -13,9 +13,10 @@ extern crate rustc_session;
 extern crate log as rust_log;
 
 use rustc_driver::{Compilation, Callbacks};
-use rustc_interface::{interface::Compiler, Queries, Config};
-use rustc_middle::ty::query::ExternProviders;
+use rustc_interface::{interface::Compiler, Queries, Config, DEFAULT_QUERY_PROVIDERS};
+use rustc_middle::ty::query::{ExternProviders, Providers};
 use rustc_data_structures::sync::Lrc;
+use rustc_session::Session;
 use rustc_session::search_paths::PathKind;
 
 use std::env;
@@ -23,20 +24,25 @@ use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
 
 use rlc::{RlcConfig, compile_time_sysroot, RLC_DEFAULT_ARGS, start_analyzer};
-use rlc::analysis::flow_analysis::{IcxSliceDisplay, Z3GoalDisplay};
+use rlc::analysis::flow_analysis::{FfiMode, IcxSliceDisplay, VirtualCallMode, Z3GoalDisplay};
 use rlc::analysis::type_analysis::AdtOwnerDisplay;
+use rlc::analysis::type_analysis::type_visitor::{MirPhase, clear_mir_bodies, record_mir_body};
 use rlc::components::display::MirDisplay;
 use rlc::components::grain::RlcGrain;
 use rlc::components::log::Verbosity;
 use rlc::rlc_info;
+use rlc::components::log::rlc_error_and_exit;
+use rlc::components::report::severity::RLC_EXIT_DENIED;
 
-#[derive(Copy, Clone)]
+#[derive(Clone)]
 struct RlcCompilerCalls {
     rlc_config: RlcConfig,
+    // the number of the findings denied by RLC_DENY, it decides the exit code
+    denied: usize,
 }
 
 impl Default for RlcCompilerCalls {
-    fn default() -> Self { Self { rlc_config: RlcConfig::default() } }
+    fn default() -> Self { Self { rlc_config: RlcConfig::default(), denied: 0 } }
 }
 
 impl Display for RlcCompilerCalls {
@@ -51,15 +57,18 @@ impl Display for RlcCompilerCalls {
 
 impl Callbacks for RlcCompilerCalls {
     fn config(&mut self, config: &mut Config) {
-        config.override_queries = Some(|_, _, external_providers| {
-           external_providers.used_crate_source = |tcx, cnum| {
-               let mut providers = ExternProviders::default();
-               rustc_metadata::provide_extern(&mut providers);
-               let mut crate_source = (providers.used_crate_source)(tcx, cnum);
-               Lrc::make_mut(&mut crate_source).rlib = Some((PathBuf::new(), PathKind::All));
-               crate_source
-           };
-        });
+        let override_queries: fn(&Session, &mut Providers, &mut ExternProviders) = match self.rlc_config.mir_phase() {
+            MirPhase::Built => |_, providers, external_providers| {
+                provide_extern(external_providers);
+                provide_built(providers);
+            },
+            MirPhase::Analysis => |_, providers, external_providers| {
+                provide_extern(external_providers);
+                provide_analysis(providers);
+            },
+            MirPhase::Optimized => |_, _, external_providers| provide_extern(external_providers),
+        };
+        config.override_queries = Some(override_queries);
     }
 
     fn after_analysis<'tcx>(
@@ -68,12 +77,12 @@ impl Callbacks for RlcCompilerCalls {
         queries: &'tcx Queries<'tcx>,
     ) -> Compilation {
         compiler.session().abort_if_errors();
-        Verbosity::init_rlc_log_system_with_verbosity(self.rlc_config.verbose()).expect("Failed to set up RLC log system");
 
         rlc_info!("RLC Start");
-        queries.global_ctxt().unwrap().peek_mut().enter(
-            |tcx| start_analyzer(tcx, self.rlc_config)
+        self.denied = queries.global_ctxt().unwrap().peek_mut().enter(
+            |tcx| start_analyzer(tcx, self.rlc_config.clone())
         );
+        clear_mir_bodies();
         rlc_info!("RLC Stop");
 
         compiler.session().abort_if_errors();
@@ -81,9 +90,43 @@ impl Callbacks for RlcCompilerCalls {
     }
 }
 
+fn provide_extern(external_providers: &mut ExternProviders) {
+    external_providers.used_crate_source = |tcx, cnum| {
+        let mut providers = ExternProviders::default();
+        rustc_metadata::provide_extern(&mut providers);
+        let mut crate_source = (providers.used_crate_source)(tcx, cnum);
+        Lrc::make_mut(&mut crate_source).rlib = Some((PathBuf::new(), PathKind::All));
+        crate_source
+    };
+}
+
+// the built MIR is stolen by the const qualification, it is copied out right after being built
+fn provide_built(providers: &mut Providers) {
+    providers.mir_built = |tcx, def| {
+        let body = (DEFAULT_QUERY_PROVIDERS.mir_built)(tcx, def);
+        if def.const_param_did.is_none() {
+            // the bodies are cleared in after_analysis, before tcx is dropped
+            unsafe { record_mir_body(tcx, def.did.to_def_id(), &body.borrow()); }
+        }
+        body
+    };
+}
+
+// the promoted MIR is the one seen by the borrow checker, it is stolen by the drop elaboration afterwards
+fn provide_analysis(providers: &mut Providers) {
+    providers.mir_promoted = |tcx, def| {
+        let (body, promoted) = (DEFAULT_QUERY_PROVIDERS.mir_promoted)(tcx, def);
+        if def.const_param_did.is_none() {
+            // the bodies are cleared in after_analysis, before tcx is dropped
+            unsafe { record_mir_body(tcx, def.did.to_def_id(), &body.borrow()); }
+        }
+        (body, promoted)
+    };
+}
+
 impl RlcCompilerCalls {
     #[allow(dead_code)]
-    fn new(rlc_config: RlcConfig) -> Self { Self {rlc_config} }
+    fn new(rlc_config: RlcConfig) -> Self { Self { rlc_config, denied: 0 } }
 }
 
 struct RlcArgs {
@@ -125,6 +168,40 @@ impl RlcArgs {
 
     pub fn set_icx_slice_display(&mut self) { self.rlc_cc.rlc_config.set_icx_slice_display(IcxSliceDisplay::Verbose); }
 
+    pub fn set_unwind(&mut self) { self.rlc_cc.rlc_config.set_unwind(true); }
+
+    pub fn set_rc_cycle(&mut self) { self.rlc_cc.rlc_config.set_rc_cycle(true); }
+
+    pub fn set_double_free(&mut self) { self.rlc_cc.rlc_config.set_double_free(true); }
+
+    pub fn set_use_after_free(&mut self) { self.rlc_cc.rlc_config.set_use_after_free(true); }
+
+    pub fn set_divergent_ownership(&mut self) { self.rlc_cc.rlc_config.set_divergent_ownership(true); }
+
+    pub fn set_static_store(&mut self) { self.rlc_cc.rlc_config.set_static_store(true); }
+
+    pub fn set_param_owned(&mut self) { self.rlc_cc.rlc_config.set_param_owned(true); }
+
+    pub fn set_virtual_call_optimistic(&mut self) { self.rlc_cc.rlc_config.set_virtual_call(VirtualCallMode::Optimistic); }
+
+    pub fn set_virtual_call_pessimistic(&mut self) { self.rlc_cc.rlc_config.set_virtual_call(VirtualCallMode::Pessimistic); }
+
+    pub fn set_ffi_strict(&mut self) { self.rlc_cc.rlc_config.set_ffi(FfiMode::Strict); }
+
+    pub fn set_ffi_friendly(&mut self) { self.rlc_cc.rlc_config.set_ffi(FfiMode::Friendly); }
+
+    pub fn set_z3_timeout(&mut self, timeout: u64) { self.rlc_cc.rlc_config.set_z3_timeout(timeout); }
+
+    pub fn set_max_layout_depth(&mut self, depth: usize) { self.rlc_cc.rlc_config.set_max_layout_depth(depth); }
+
+    pub fn set_inline_threshold(&mut self, threshold: usize) { self.rlc_cc.rlc_config.set_inline_threshold(threshold); }
+
+    pub fn set_mir_phase(&mut self, phase: MirPhase) { self.rlc_cc.rlc_config.set_mir_phase(phase); }
+
+    pub fn set_quiet(&mut self) { self.rlc_cc.rlc_config.set_verbose(Verbosity::Quiet); }
+
+    pub fn set_config_from_env(&mut self) { self.rlc_cc.rlc_config = RlcConfig::from_env(); }
+
     pub fn push_args(&mut self, arg: String) { self.args.push(arg); }
 
     pub fn splice_args(&mut self) {
@@ -134,6 +211,7 @@ impl RlcArgs {
 
 fn config_parse() -> RlcArgs {
     let mut rlc_args = RlcArgs::default();
+    rlc_args.set_config_from_env();
     for arg in env::args() {
         match arg.as_str() {
             "-GRAIN=LOW" => rlc_args.set_config_low(),
@@ -145,6 +223,42 @@ fn config_parse() -> RlcArgs {
             "-ADT=V" => rlc_args.set_adt_display_verbose(),
             "-Z3-GOAL=V" => rlc_args.set_z3_goal_display_verbose(),
             "-ICX-SLICE=V" => rlc_args.set_icx_slice_display(),
+            "-UNWIND" => rlc_args.set_unwind(),
+            "-RC-CYCLE" => rlc_args.set_rc_cycle(),
+            "-DOUBLE-FREE" => rlc_args.set_double_free(),
+            "-UAF" => rlc_args.set_use_after_free(),
+            "-DIVERGENT" => rlc_args.set_divergent_ownership(),
+            "-STATIC" => rlc_args.set_static_store(),
+            "-PARAM-OWNED" => rlc_args.set_param_owned(),
+            "-DYN=OPTIMISTIC" => rlc_args.set_virtual_call_optimistic(),
+            "-DYN=PESSIMISTIC" => rlc_args.set_virtual_call_pessimistic(),
+            "-FFI=STRICT" => rlc_args.set_ffi_strict(),
+            "-FFI=FRIENDLY" => rlc_args.set_ffi_friendly(),
+            "-QUIET" | "--quiet" => rlc_args.set_quiet(),
+            s if s.starts_with("-Z3-TIMEOUT=") => {
+                match s["-Z3-TIMEOUT=".len()..].parse() {
+                    Ok(timeout) => rlc_args.set_z3_timeout(timeout),
+                    Err(_) => rlc_error_and_exit(format!("Invalid z3 timeout: {}", s)),
+                }
+            },
+            s if s.starts_with("-LAYOUT-DEPTH=") => {
+                match s["-LAYOUT-DEPTH=".len()..].parse() {
+                    Ok(depth) => rlc_args.set_max_layout_depth(depth),
+                    Err(_) => rlc_error_and_exit(format!("Invalid layout depth: {}", s)),
+                }
+            },
+            s if s.starts_with("-MIR-PHASE=") => {
+                match MirPhase::from_name(&s["-MIR-PHASE=".len()..]) {
+                    Some(phase) => rlc_args.set_mir_phase(phase),
+                    None => rlc_error_and_exit(format!("Invalid MIR phase: {}", s)),
+                }
+            },
+            s if s.starts_with("-INLINE=") => {
+                match s["-INLINE=".len()..].parse() {
+                    Ok(threshold) => rlc_args.set_inline_threshold(threshold),
+                    Err(_) => rlc_error_and_exit(format!("Invalid inline threshold: {}", s)),
+                }
+            },
             _ => rlc_args.push_args(arg),
         }
     }
@@ -152,6 +266,10 @@ fn config_parse() -> RlcArgs {
 }
 
 /// Execute a compiler with the given CLI arguments and callbacks.
+///
+/// The exit code follows rustc: 0 on success, 1 if the compilation fails and 101 on the ICE. If the
+/// env var RLC_DENY is given and any finding is denied by it, the exit code is 2 (`RLC_EXIT_DENIED`).
+/// The findings never change the exit code if RLC_WARN is given or none of them is given.
 fn run_complier(rlc_args: &mut RlcArgs) -> i32 {
     // Make sure we use the right default sysroot. The default sysroot is wrong,
     // because `get_or_default_sysroot` in `librustc_session` bases that on `current_exe`.
@@ -173,7 +291,11 @@ fn run_complier(rlc_args: &mut RlcArgs) -> i32 {
     let rlc_final_args = rlc_args.args.clone();
 
     let run_compiler = rustc_driver::RunCompiler::new(&rlc_args.args, &mut rlc_args.rlc_cc);
-    let exit_code = rustc_driver::catch_with_exit_code(move || run_compiler.run());
+    let mut exit_code = rustc_driver::catch_with_exit_code(move || run_compiler.run());
+    if exit_code == 0 && rlc_args.rlc_cc.denied > 0 {
+        rlc_info!("{} findings are denied by RLC_DENY", rlc_args.rlc_cc.denied);
+        exit_code = RLC_EXIT_DENIED;
+    }
 
     if option_env!("RLC_VERBOSE").is_some() {
         rlc_info!("The arg for compilation is {:?}", rlc_final_args);
@@ -186,6 +308,10 @@ fn main() {
     // Installs a panic hook that will print the ICE message on unexpected panics.
     rustc_driver::install_ice_hook();
 
+    // The log is set up before parsing, so that the invalid values of the env vars and the flags are reported.
+    let verbosity = if env::args().any(|arg| arg == "-QUIET" || arg == "--quiet") { Verbosity::Quiet } else { Verbosity::Info };
+    Verbosity::init_rlc_log_system_with_verbosity(verbosity).expect("Failed to set up RLC log system");
+
     // Parse the config and arguments from env.
     let mut rlc_args = config_parse();
 
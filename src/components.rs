@@ -1,5 +1,9 @@
+pub mod cache;
 pub mod context;
 pub mod display;
+pub mod filter;
 pub mod fs;
 pub mod grain;
-pub mod log;
\ No newline at end of file
+pub mod log;
+pub mod metrics;
+pub mod report;
\ No newline at end of file
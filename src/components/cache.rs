@@ -0,0 +1,117 @@
+use rustc_middle::ty::TyCtxt;
+use rustc_span::def_id::{CrateNum, DefId, DefPathHash, LOCAL_CRATE};
+use rustc_data_structures::fingerprint::Fingerprint;
+
+use crate::rlc_info;
+use crate::analysis::type_analysis::OwnerUnit;
+use crate::analysis::flow_analysis::summary::FnSummary;
+use crate::components::context::RlcGlobalCtxt;
+use crate::components::fs::{rlc_create_dir, rlc_create_file, rlc_write};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// the def path hash is stable across sessions, unlike the DefId
+type CachedDefPath = (u64, u64);
+
+// The cached results of one crate: the ownership of its adts and the summaries of its functions.
+// The cache is keyed by the stable crate id, and it is valid only if the crate hash (covering the source of
+// the crate) is unchanged. The ownership carried by the returned value (the taint) of a summary refers to the
+// types of the session, thus it is not cached.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CrateCache {
+    stable_crate_id: u64,
+    crate_hash: u64,
+    adt_owner: Vec<(CachedDefPath, Vec<OwnerUnit>)>,
+    summaries: Vec<(CachedDefPath, CachedSummary)>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CachedSummary {
+    consumed: Vec<bool>,
+    escaping: Vec<bool>,
+    owned_return: bool,
+}
+
+fn cache_file(tcx: TyCtxt<'_>, dir: &str, cnum: CrateNum) -> PathBuf {
+    let name = tcx.crate_name(cnum);
+    let stable_crate_id = tcx.stable_crate_id(cnum).to_u64();
+    Path::new(dir).join(format!("{}-{:016x}.json", name, stable_crate_id))
+}
+
+fn def_path(tcx: TyCtxt<'_>, did: DefId) -> CachedDefPath {
+    tcx.def_path_hash(did).0.as_value()
+}
+
+// Load the caches of the upstream crates before the analysis, the adts in them are not analyzed again
+// and their functions are called with the cached summaries.
+// The current crate is always analyzed, and its cache is refreshed by `save_cache` afterwards.
+pub fn load_caches(rcx: &mut RlcGlobalCtxt<'_>, dir: &str) {
+    let tcx = rcx.tcx();
+    let mut loaded = 0;
+    for cnum in tcx.crates(()).iter() {
+        let cache: CrateCache = match fs::read_to_string(cache_file(tcx, dir, *cnum))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+        {
+            Some(cache) => cache,
+            None => continue,
+        };
+        // the crate is changed since the cache is written, the cache is stale
+        if cache.stable_crate_id != tcx.stable_crate_id(*cnum).to_u64()
+            || cache.crate_hash != tcx.crate_hash(*cnum).as_u64()
+        {
+            continue;
+        }
+
+        let to_def_id = |(hi, lo): CachedDefPath| {
+            tcx.def_path_hash_to_def_id(DefPathHash(Fingerprint::new(hi, lo)), &mut || {
+                panic!("the def path hash in the cache of {} is not found", tcx.crate_name(*cnum))
+            })
+        };
+        for (path, owner) in cache.adt_owner {
+            rcx.adt_owner_mut().insert(to_def_id(path), owner);
+        }
+        for (path, summary) in cache.summaries {
+            rcx.summaries_mut().insert(
+                to_def_id(path),
+                FnSummary::new(summary.consumed, summary.escaping, summary.owned_return),
+            );
+        }
+        loaded += 1;
+    }
+
+    if loaded > 0 {
+        rlc_info!("The analysis caches of {} crates are loaded from {}", loaded, dir);
+    }
+}
+
+// Write the results of the current crate into its cache after the analysis.
+pub fn save_cache(rcx: &RlcGlobalCtxt<'_>, dir: &str) {
+    let tcx = rcx.tcx();
+    let mut cache = CrateCache {
+        stable_crate_id: tcx.stable_crate_id(LOCAL_CRATE).to_u64(),
+        crate_hash: tcx.crate_hash(LOCAL_CRATE).as_u64(),
+        ..CrateCache::default()
+    };
+
+    for (did, owner) in rcx.adt_owner().iter().filter(|(did, _)| did.is_local()) {
+        cache.adt_owner.push((def_path(tcx, *did), owner.clone()));
+    }
+    for (did, summary) in rcx.summaries().iter().filter(|(did, _)| did.is_local()) {
+        let cached = CachedSummary {
+            consumed: summary.consumed().clone(),
+            escaping: summary.escaping().clone(),
+            owned_return: summary.is_owned_return(),
+        };
+        cache.summaries.push((def_path(tcx, *did), cached));
+    }
+    // keep the file stable across runs for the same crate
+    cache.adt_owner.sort_by_key(|(path, _)| *path);
+    cache.summaries.sort_by_key(|(path, _)| *path);
+
+    rlc_create_dir(dir, "Failed to create dir for analysis cache");
+    let json = serde_json::to_string(&cache).expect("failed to serialize analysis cache");
+    let file = rlc_create_file(cache_file(tcx, dir, LOCAL_CRATE), "Failed to create analysis cache");
+    rlc_write(file, json.as_bytes(), "Failed to write analysis cache");
+}
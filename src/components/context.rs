@@ -1,8 +1,14 @@
 use rustc_middle::ty::TyCtxt;
+use rustc_span::def_id::DefId;
 
 use crate::{RlcConfig, Elapsed};
 use crate::analysis::flow_analysis::MirGraph;
-use crate::analysis::type_analysis::AdtOwner;
+use crate::analysis::flow_analysis::call_graph::CallGraph;
+use crate::analysis::flow_analysis::summary::{FnSummaries, FnSummary, KnownSummaries};
+use crate::analysis::type_analysis::{AdtOwner, ContainerOwners, Unique, std_def_path};
+use crate::components::filter::RlcFilter;
+use crate::components::metrics::{Approximations, Metrics};
+use crate::components::report::LeakReport;
 
 use std::collections::HashMap;
 
@@ -11,18 +17,44 @@ pub struct RlcGlobalCtxt<'tcx> {
     tcx: TyCtxt<'tcx>,
     config: RlcConfig,
     adt_owner: AdtOwner,
+    container_owner: ContainerOwners<'tcx>,
+    // the adts in current crate with a hand-written Drop impl
+    custom_drop: Unique,
     mir_graph: MirGraph,
+    call_graph: CallGraph,
+    summaries: FnSummaries<'tcx>,
+    known_summaries: KnownSummaries<'tcx>,
+    // the leaks of generic functions depending on the type params, they are resolved at the concrete call sites
+    generic_leaks: HashMap<DefId, Vec<LeakReport>>,
+    // the generic functions instantiated with concrete type args by the call sites in current crate
+    instantiated: Unique,
     elasped: Elapsed,
+    reports: Vec<LeakReport>,
+    metrics: Metrics,
+    // the sites where the analysis bails out or approximates, printed with the statistics
+    approximations: Approximations,
+    filter: RlcFilter,
 }
 
 impl<'tcx> RlcGlobalCtxt<'tcx> {
     pub fn new(tcx:TyCtxt<'tcx>, config: RlcConfig) -> Self {
         Self {
             tcx,
-            config,
             adt_owner: HashMap::default(),
+            container_owner: HashMap::default(),
+            custom_drop: Unique::default(),
             mir_graph: HashMap::default(),
+            call_graph: CallGraph::default(),
+            summaries: HashMap::default(),
+            known_summaries: HashMap::default(),
+            generic_leaks: HashMap::default(),
+            instantiated: Unique::default(),
             elasped: (0, 0),
+            reports: Vec::new(),
+            metrics: Metrics::default(),
+            approximations: Approximations::default(),
+            filter: config.filter().clone(),
+            config,
         }
     }
 
@@ -30,8 +62,8 @@ impl<'tcx> RlcGlobalCtxt<'tcx> {
         self.tcx
     }
 
-    pub fn config(&self) -> RlcConfig {
-        self.config
+    pub fn config(&self) -> &RlcConfig {
+        &self.config
     }
 
     pub fn adt_owner(&self) -> &AdtOwner {
@@ -42,6 +74,22 @@ impl<'tcx> RlcGlobalCtxt<'tcx> {
         &mut self.adt_owner
     }
 
+    pub fn container_owner(&self) -> &ContainerOwners<'tcx> {
+        &self.container_owner
+    }
+
+    pub fn container_owner_mut(&mut self) -> &mut ContainerOwners<'tcx> {
+        &mut self.container_owner
+    }
+
+    pub fn custom_drop(&self) -> &Unique {
+        &self.custom_drop
+    }
+
+    pub fn custom_drop_mut(&mut self) -> &mut Unique {
+        &mut self.custom_drop
+    }
+
     pub fn mir_graph(&self) -> &MirGraph {
         &self.mir_graph
     }
@@ -50,6 +98,86 @@ impl<'tcx> RlcGlobalCtxt<'tcx> {
         &mut self.mir_graph
     }
 
+    pub fn call_graph(&self) -> &CallGraph {
+        &self.call_graph
+    }
+
+    pub fn call_graph_mut(&mut self) -> &mut CallGraph {
+        &mut self.call_graph
+    }
+
+    pub fn summaries(&self) -> &FnSummaries<'tcx> {
+        &self.summaries
+    }
+
+    pub fn summaries_mut(&mut self) -> &mut FnSummaries<'tcx> {
+        &mut self.summaries
+    }
+
+    pub fn known_summaries_mut(&mut self) -> &mut KnownSummaries<'tcx> {
+        &mut self.known_summaries
+    }
+
+    // the known summary of the function by its def path, or by the path with the facade crates spelled as std
+    // (e.g., `core::ptr::drop_in_place` called in a no_std crate)
+    pub fn known_summary(&self, did: DefId) -> Option<FnSummary<'tcx>> {
+        if self.known_summaries.is_empty() {
+            return None;
+        }
+        self.known_summaries
+            .get(&self.tcx.def_path_str(did))
+            .or_else(|| self.known_summaries.get(&std_def_path(self.tcx, did)))
+            .cloned()
+    }
+
+    pub fn generic_leaks(&self) -> &HashMap<DefId, Vec<LeakReport>> {
+        &self.generic_leaks
+    }
+
+    pub fn generic_leaks_mut(&mut self) -> &mut HashMap<DefId, Vec<LeakReport>> {
+        &mut self.generic_leaks
+    }
+
+    pub fn instantiated(&self) -> &Unique {
+        &self.instantiated
+    }
+
+    pub fn instantiated_mut(&mut self) -> &mut Unique {
+        &mut self.instantiated
+    }
+
+    pub fn reports(&self) -> &Vec<LeakReport> {
+        &self.reports
+    }
+
+    pub fn reports_mut(&mut self) -> &mut Vec<LeakReport> {
+        &mut self.reports
+    }
+
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    pub fn metrics_mut(&mut self) -> &mut Metrics {
+        &mut self.metrics
+    }
+
+    pub fn approximations(&self) -> &Approximations {
+        &self.approximations
+    }
+
+    pub fn approximations_mut(&mut self) -> &mut Approximations {
+        &mut self.approximations
+    }
+
+    pub fn filter(&self) -> &RlcFilter {
+        &self.filter
+    }
+
+    pub fn set_filter(&mut self, filter: RlcFilter) {
+        self.filter = filter;
+    }
+
     pub fn get_time_build(&self) -> i64 {
         self.elasped.0
     }
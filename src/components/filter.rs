@@ -0,0 +1,126 @@
+use rustc_ast::ast::AttrKind;
+use rustc_hir::Unsafety;
+use rustc_hir::def::DefKind;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::def_id::DefId;
+
+use crate::analysis::flow_analysis::call_graph::CallGraph;
+use crate::components::log::rlc_error_and_exit;
+
+use regex::Regex;
+
+use std::collections::HashSet;
+
+// the path of the tool attribute to opt a function out of the analysis, i.e., `#[rlc::ignore]`
+static RLC_IGNORE_ATTR: [&str; 2] = ["rlc", "ignore"];
+
+// The functions to analyze selected by the regexes over def_path_str given by the env vars RLC_ALLOW
+// and RLC_DENY_FN. A function is analyzed if it matches the allowlist (or the allowlist is not given)
+// and it does not match the denylist.
+// The single function given by the env var RLC_ONLY (the exact def_path_str) further restricts the analysis
+// to it, together with its callees if the env var RLC_ONLY_CALLEES is given, in addition to the lists.
+// The bodies of `unsafe fn` are trusted and skipped if the env var RLC_SKIP_UNSAFE is given.
+#[derive(Debug, Clone, Default)]
+pub struct RlcFilter {
+    allow: Option<Regex>,
+    deny: Option<Regex>,
+    only: Option<String>,
+    only_callees: bool,
+    skip_unsafe: bool,
+}
+
+impl RlcFilter {
+    pub fn with_allow(mut self, allow: &str) -> Self {
+        self.allow = Some(compile_regex(allow));
+        self
+    }
+
+    pub fn with_deny(mut self, deny: &str) -> Self {
+        self.deny = Some(compile_regex(deny));
+        self
+    }
+
+    pub fn with_only(mut self, only: String) -> Self {
+        self.only = Some(only);
+        self
+    }
+
+    pub fn with_only_callees(mut self, only_callees: bool) -> Self {
+        self.only_callees = only_callees;
+        self
+    }
+
+    pub fn with_skip_unsafe(mut self, skip_unsafe: bool) -> Self {
+        self.skip_unsafe = skip_unsafe;
+        self
+    }
+
+    pub fn only(&self) -> Option<&String> {
+        self.only.as_ref()
+    }
+
+    // the functions selected by RLC_ONLY (and their callees in the call graph if enabled),
+    // none if RLC_ONLY is not given and all functions are in scope
+    pub fn only_scope(&self, tcx: TyCtxt<'_>, call_graph: &CallGraph) -> Option<HashSet<DefId>> {
+        let only = self.only.as_ref()?;
+        let mut scope = HashSet::new();
+        for did in call_graph.sccs().into_iter().flatten() {
+            if tcx.def_path_str(did) != *only {
+                continue;
+            }
+            match self.only_callees {
+                true => scope.extend(call_graph.reachable(&did)),
+                false => { scope.insert(did); },
+            }
+        }
+        Some(scope)
+    }
+
+    pub fn is_allowed(&self, def_path: &str) -> bool {
+        let allowed = match &self.allow {
+            Some(allow) => allow.is_match(def_path),
+            None => true,
+        };
+        let denied = match &self.deny {
+            Some(deny) => deny.is_match(def_path),
+            None => false,
+        };
+        allowed && !denied
+    }
+
+    // check both the config and the attribute in source code
+    pub fn is_analyzed(&self, tcx: TyCtxt<'_>, did: DefId) -> bool {
+        self.is_allowed(&tcx.def_path_str(did)) && !is_ignored_by_attr(tcx, did) && !self.is_skipped_unsafe(tcx, did)
+    }
+
+    // the `unsafe fn` (or unsafe method) skipped by RLC_SKIP_UNSAFE, the closures inside it are still analyzed
+    pub fn is_skipped_unsafe(&self, tcx: TyCtxt<'_>, did: DefId) -> bool {
+        self.skip_unsafe
+            && matches!(tcx.def_kind(did), DefKind::Fn | DefKind::AssocFn)
+            && tcx.fn_sig(did).unsafety() == Unsafety::Unsafe
+    }
+}
+
+fn compile_regex(re: &str) -> Regex {
+    match Regex::new(re) {
+        Ok(regex) => regex,
+        Err(e) => rlc_error_and_exit(format!("Invalid regex {}: {}", re, e)),
+    }
+}
+
+// the closures inside an ignored function are ignored as well
+pub fn is_ignored_by_attr(tcx: TyCtxt<'_>, did: DefId) -> bool {
+    let root = tcx.typeck_root_def_id(did);
+    tcx.get_attrs_unchecked(root).iter().any(|attr| {
+        match &attr.kind {
+            AttrKind::Normal(item, _) => {
+                item.path.segments.len() == RLC_IGNORE_ATTR.len()
+                    && item.path.segments
+                        .iter()
+                        .zip(RLC_IGNORE_ATTR.iter())
+                        .all(|(seg, name)| seg.ident.name.as_str() == *name)
+            },
+            AttrKind::DocComment(..) => false,
+        }
+    })
+}
@@ -45,6 +45,13 @@ pub fn rlc_create_file<P: AsRef<Path>>(path: P, msg: impl AsRef<str>) -> fs::Fil
     }
 }
 
+pub fn rlc_append_file<P: AsRef<Path>>(path: P, msg: impl AsRef<str>) -> fs::File {
+    match fs::OpenOptions::new().create(true).append(true).open(path) {
+        Ok(file) => file,
+        Err(e) => rlc_error_and_exit(format!("{}: {}", msg.as_ref(), e)),
+    }
+}
+
 pub fn rlc_read<P: AsRef<Path>>(path: P, msg: impl AsRef<str>) -> fs::File {
     match fs::File::open(path) {
         Ok(file) => file,
@@ -52,6 +59,13 @@ pub fn rlc_read<P: AsRef<Path>>(path: P, msg: impl AsRef<str>) -> fs::File {
     }
 }
 
+pub fn rlc_read_to_string<P: AsRef<Path>>(path: P, msg: impl AsRef<str>) -> String {
+    match fs::read_to_string(path) {
+        Ok(s) => s,
+        Err(e) => rlc_error_and_exit(format!("{}: {}", msg.as_ref(), e)),
+    }
+}
+
 pub fn rlc_write(mut file: File, buf: &[u8], msg: impl AsRef<str>) -> usize {
     file.write(buf)
         .unwrap_or_else(|e|
@@ -6,6 +6,8 @@ use log::LevelFilter;
 
 #[derive(Debug, Copy, Clone, Hash)]
 pub enum Verbosity {
+    // only the warnings and the errors are logged, the findings are summarized by the text report
+    Quiet,
     Info,
     Debug,
     Trace,
@@ -16,6 +18,7 @@ impl Verbosity {
         let mut dispatch = Dispatch::new();
 
         dispatch = match verbose {
+            Verbosity::Quiet => dispatch.level(LevelFilter::Warn),
             Verbosity::Info => dispatch.level(LevelFilter::Info),
             Verbosity::Debug => dispatch.level(LevelFilter::Debug),
             Verbosity::Trace => dispatch.level(LevelFilter::Trace),
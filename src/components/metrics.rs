@@ -0,0 +1,199 @@
+use rustc_span::Span;
+use rustc_span::def_id::DefId;
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+// the counters of one run of rlc for tuning and regression tracking
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Metrics {
+    // the functions collected in the mir graph
+    functions: usize,
+    analyzed: usize,
+    skipped: usize,
+    // the functions excluded by the allowlist, denylist or `#[rlc::ignore]`
+    denied: usize,
+    // the basic blocks visited by the flow analysis, including the re-visits of the fixpoint iteration
+    blocks: usize,
+    z3_queries: usize,
+    // the time (ms) spent in the z3 checks, it is part of the intro phase
+    time_z3: i64,
+    // the wall-clock time (ms) of the type analysis, the order phase and the intro phase
+    time_type: i64,
+    time_order: i64,
+    time_intro: i64,
+}
+
+impl Metrics {
+    pub fn functions(&self) -> usize {
+        self.functions
+    }
+
+    pub fn set_functions(&mut self, functions: usize) {
+        self.functions = functions;
+    }
+
+    pub fn analyzed(&self) -> usize {
+        self.analyzed
+    }
+
+    pub fn add_analyzed(&mut self) {
+        self.analyzed += 1;
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    pub fn add_skipped(&mut self) {
+        self.skipped += 1;
+    }
+
+    pub fn denied(&self) -> usize {
+        self.denied
+    }
+
+    pub fn add_denied(&mut self) {
+        self.denied += 1;
+    }
+
+    pub fn blocks(&self) -> usize {
+        self.blocks
+    }
+
+    pub fn add_blocks(&mut self, blocks: usize) {
+        self.blocks += blocks;
+    }
+
+    pub fn z3_queries(&self) -> usize {
+        self.z3_queries
+    }
+
+    pub fn add_z3_queries(&mut self, queries: usize) {
+        self.z3_queries += queries;
+    }
+
+    pub fn time_z3(&self) -> i64 {
+        self.time_z3
+    }
+
+    pub fn add_time_z3(&mut self, time: i64) {
+        self.time_z3 += time;
+    }
+
+    pub fn time_type(&self) -> i64 {
+        self.time_type
+    }
+
+    pub fn add_time_type(&mut self, time: i64) {
+        self.time_type += time;
+    }
+
+    pub fn time_order(&self) -> i64 {
+        self.time_order
+    }
+
+    pub fn add_time_order(&mut self, time: i64) {
+        self.time_order += time;
+    }
+
+    pub fn time_intro(&self) -> i64 {
+        self.time_intro
+    }
+
+    pub fn add_time_intro(&mut self, time: i64) {
+        self.time_intro += time;
+    }
+}
+
+impl Display for Metrics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Functions: {}, Analyzed: {}, Skipped: {}, Denied: {}, Blocks Visited: {}, Z3 Queries: {}, Z3: {}ms, Type: {}ms, Order: {}ms, Intro: {}ms",
+            self.functions,
+            self.analyzed,
+            self.skipped,
+            self.denied,
+            self.blocks,
+            self.z3_queries,
+            self.time_z3,
+            self.time_type,
+            self.time_order,
+            self.time_intro,
+        )
+    }
+}
+
+// the constructs that make the analysis bail out or approximate, the histogram of them tells the coverage
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Approximation {
+    InlineAsm,
+    // the call through a trait object whose callee is unknown
+    VirtualCall,
+    // z3 can neither prove nor disprove the leak
+    Z3Unknown,
+    // the intrinsic is viewed as an opaque call
+    Intrinsic,
+    // the foreign function (e.g., in C) is viewed as an opaque call
+    ForeignCall,
+    // the in state of a loop block is widened, the widened vars are no longer tracked
+    WidenedLoop,
+    // the fixpoint iteration of a block is cut by the cap before it converges
+    FixpointCap,
+}
+
+impl Display for Approximation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Approximation::InlineAsm => write!(f, "InlineAsm"),
+            Approximation::VirtualCall => write!(f, "VirtualCall"),
+            Approximation::Z3Unknown => write!(f, "Z3Unknown"),
+            Approximation::Intrinsic => write!(f, "Intrinsic"),
+            Approximation::ForeignCall => write!(f, "ForeignCall"),
+            Approximation::WidenedLoop => write!(f, "WidenedLoop"),
+            Approximation::FixpointCap => write!(f, "FixpointCap"),
+        }
+    }
+}
+
+// the sites of each kind of approximation, i.e., the function and the span of the construct
+#[derive(Debug, Clone, Default)]
+pub struct Approximations {
+    sites: HashMap<Approximation, Vec<(DefId, Span)>>,
+}
+
+impl Approximations {
+    pub fn add(&mut self, kind: Approximation, did: DefId, span: Span) {
+        self.sites.entry(kind).or_default().push((did, span));
+    }
+
+    pub fn count(&self, kind: Approximation) -> usize {
+        self.sites.get(&kind).map_or(0, |sites| sites.len())
+    }
+
+    pub fn sites(&self, kind: Approximation) -> &[(DefId, Span)] {
+        self.sites.get(&kind).map_or(&[], |sites| sites.as_slice())
+    }
+
+    // the kinds encountered in the run, the most frequent one first
+    pub fn kinds(&self) -> Vec<Approximation> {
+        let mut kinds: Vec<Approximation> = self.sites.keys().copied().collect();
+        kinds.sort_by_key(|kind| (std::cmp::Reverse(self.count(*kind)), *kind));
+        kinds
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sites.is_empty()
+    }
+}
+
+impl Display for Approximations {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let histogram: Vec<String> = self.kinds()
+            .into_iter()
+            .map(|kind| format!("{}: {}", kind, self.count(kind)))
+            .collect();
+        write!(f, "{}", histogram.join(", "))
+    }
+}
@@ -0,0 +1,435 @@
+pub mod sarif;
+pub mod json;
+pub mod diagnostic;
+pub mod severity;
+pub mod text;
+
+use rustc_middle::mir::{BasicBlock, Local};
+use rustc_span::Span;
+use rustc_span::def_id::DefId;
+
+use crate::analysis::flow_analysis::ownership::TaintSource;
+use crate::analysis::type_analysis::OwnershipLayout;
+use crate::analysis::type_analysis::ownership::{ContainerOwner, RawTypeOwner};
+use crate::components::metrics::Approximation;
+
+use std::collections::HashMap;
+
+// the kind of the finding, the leak is the default one
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ReportKind {
+    Leak,
+    // z3 can neither prove nor disprove the leak
+    Unknown,
+    DoubleFree,
+    UseAfterFree,
+    // the pre nodes of a join point disagree on whether the local owns the heap item
+    DivergentOwnership,
+    // the heap item is stored into a static (or a thread-local), it is not leaked by the function
+    StoredInStatic,
+}
+
+impl std::fmt::Display for ReportKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportKind::Leak => write!(f, "leak"),
+            ReportKind::Unknown => write!(f, "unknown"),
+            ReportKind::DoubleFree => write!(f, "double-free"),
+            ReportKind::UseAfterFree => write!(f, "use-after-free"),
+            ReportKind::DivergentOwnership => write!(f, "divergent-ownership"),
+            ReportKind::StoredInStatic => write!(f, "stored-in-static"),
+        }
+    }
+}
+
+// The confidence of the finding for the triage, it is lowered by the approximations on the paths reaching it.
+// The opaque callees (intrinsics and foreign functions) are approximated by their signatures, which is usually
+// right, while the unknown callees, the widened loops and the inline asm lose track of the ownership.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Confidence {
+    High,
+    Medium,
+    Low,
+}
+
+impl Confidence {
+    pub fn of_approximation(kind: Approximation) -> Self {
+        match kind {
+            Approximation::Intrinsic | Approximation::ForeignCall => Confidence::Medium,
+            Approximation::InlineAsm
+            | Approximation::VirtualCall
+            | Approximation::Z3Unknown
+            | Approximation::WidenedLoop
+            | Approximation::FixpointCap => Confidence::Low,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "high" => Some(Confidence::High),
+            "medium" => Some(Confidence::Medium),
+            "low" => Some(Confidence::Low),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Confidence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Confidence::High => write!(f, "high"),
+            Confidence::Medium => write!(f, "medium"),
+            Confidence::Low => write!(f, "low"),
+        }
+    }
+}
+
+fn approximation_reason(kind: Approximation) -> &'static str {
+    match kind {
+        Approximation::InlineAsm => "the inline asm is not analyzed",
+        Approximation::VirtualCall => "the callee of the call through a trait object is unknown",
+        Approximation::Z3Unknown => "z3 can neither prove nor disprove the leak",
+        Approximation::Intrinsic => "the intrinsic is approximated by its signature",
+        Approximation::ForeignCall => "the foreign function is approximated by its signature",
+        Approximation::WidenedLoop => "the state of the loop is widened",
+        Approximation::FixpointCap => "the state of the loop does not converge, its last iteration is kept",
+    }
+}
+
+// A step of the witness path of a leak: the block through which the owned taint survives to the exit, and the
+// branch taken out of it if the block has several successors, e.g., `move _5 = 1` for an arm of the switch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WitnessStep {
+    bb: BasicBlock,
+    // the source range of the block, none if the graph carries no block meta
+    span: Option<Span>,
+    branch: Option<String>,
+}
+
+impl WitnessStep {
+    pub fn new(bb: BasicBlock, span: Option<Span>, branch: Option<String>) -> Self {
+        Self { bb, span, branch }
+    }
+
+    pub fn bb(&self) -> BasicBlock {
+        self.bb
+    }
+
+    pub fn span(&self) -> Option<Span> {
+        self.span
+    }
+
+    pub fn branch(&self) -> Option<&String> {
+        self.branch.as_ref()
+    }
+}
+
+impl std::fmt::Display for WitnessStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.branch {
+            Some(branch) => write!(f, "{:?} [{}]", self.bb, branch),
+            None => write!(f, "{:?}", self.bb),
+        }
+    }
+}
+
+// the condensed witness path, e.g., `bb0 [move _2 = 1] -> bb2 -> bb5`
+pub fn witness_text(witness: &[WitnessStep]) -> String {
+    witness.iter().map(ToString::to_string).collect::<Vec<_>>().join(" -> ")
+}
+
+// the leak finding generated by the flow analysis
+#[derive(Debug, Clone)]
+pub struct LeakReport {
+    kind: ReportKind,
+    did: DefId,
+    // the precise source location of the leak (statement or terminator)
+    span: Span,
+    // the local holding the leaked item, none if the leak can not be blamed on a single local
+    local: Option<Local>,
+    // the ownership layout of the leaking local that triggered the report
+    layout: OwnershipLayout,
+    // the readable layout of the type of the leaking local, e.g., `Box<struct Foo { a: owned, b: not-owned }>`
+    layout_display: Option<String>,
+    // the leaking local is a container, the elements owned by it are leaked as well
+    container: Option<ContainerOwner>,
+    // the span where the taint (the conversion into a raw pointer) of the leaking local is introduced
+    origin: Option<Span>,
+    // where the ownership of the leaking taint comes from, none if the local is not tainted
+    source: Option<TaintSource>,
+    // the span where the storage of the leaking local is dead while it still owns the heap item
+    dead: Option<Span>,
+    // the leak is found on the unwinding path rather than the normal return
+    unwind: bool,
+    // the ownership layout of the leaking local is cut at the max layout depth, the result is approximated
+    truncated: bool,
+    // the estimated size of the leaked heap item, none if it is unsized or unknown (e.g., the buffer of Vec)
+    leaked_bytes: Option<u64>,
+    // the calls through trait objects approximated in the function, the finding has a reduced confidence
+    virtual_calls: Vec<Span>,
+    // the approximated constructs on the paths reaching the finding, they lower its confidence
+    approximations: Vec<(Approximation, Span)>,
+    // the blocks along which the owned taint of the leaking local survives to the exit, empty if it is unknown
+    witness: Vec<WitnessStep>,
+    // the leaked local has a type depending on the type params of the generic function
+    generic: bool,
+    // the concrete instance of the generic function and the call site instantiating it
+    instance: Option<(String, Span)>,
+    // the number of identical findings collapsed into this report, e.g., the allocation in a loop
+    count: usize,
+    message: String,
+}
+
+impl LeakReport {
+    pub fn new(did: DefId, span: Span, local: Option<Local>, layout: OwnershipLayout, message: String) -> Self {
+        Self {
+            kind: ReportKind::Leak,
+            did,
+            span,
+            local,
+            layout,
+            layout_display: None,
+            container: None,
+            origin: None,
+            source: None,
+            dead: None,
+            unwind: false,
+            truncated: false,
+            leaked_bytes: None,
+            virtual_calls: Vec::new(),
+            approximations: Vec::new(),
+            witness: Vec::new(),
+            generic: false,
+            instance: None,
+            count: 1,
+            message,
+        }
+    }
+
+    pub fn with_kind(mut self, kind: ReportKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn kind(&self) -> ReportKind {
+        self.kind
+    }
+
+    pub fn with_container(mut self, container: Option<ContainerOwner>) -> Self {
+        self.container = container;
+        self
+    }
+
+    pub fn did(&self) -> DefId {
+        self.did
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn local(&self) -> Option<Local> {
+        self.local
+    }
+
+    pub fn layout(&self) -> &OwnershipLayout {
+        &self.layout
+    }
+
+    pub fn with_layout_display(mut self, layout_display: Option<String>) -> Self {
+        self.layout_display = layout_display;
+        self
+    }
+
+    pub fn layout_display(&self) -> Option<&String> {
+        self.layout_display.as_ref()
+    }
+
+    pub fn with_origin(mut self, origin: Option<Span>) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    pub fn origin(&self) -> Option<Span> {
+        self.origin
+    }
+
+    pub fn with_source(mut self, source: Option<TaintSource>) -> Self {
+        self.source = source;
+        self
+    }
+
+    pub fn source(&self) -> Option<TaintSource> {
+        self.source
+    }
+
+    // the leaked heap item is handed in by the parameters, the caller is responsible for it
+    pub fn is_param_owned(&self) -> bool {
+        self.source == Some(TaintSource::Param)
+    }
+
+    pub fn with_dead(mut self, dead: Option<Span>) -> Self {
+        self.dead = dead;
+        self
+    }
+
+    pub fn dead(&self) -> Option<Span> {
+        self.dead
+    }
+
+    pub fn with_unwind(mut self, unwind: bool) -> Self {
+        self.unwind = unwind;
+        self
+    }
+
+    pub fn is_unwind(&self) -> bool {
+        self.unwind
+    }
+
+    pub fn with_truncated(mut self, truncated: bool) -> Self {
+        self.truncated = truncated;
+        self
+    }
+
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    pub fn with_leaked_bytes(mut self, leaked_bytes: Option<u64>) -> Self {
+        self.leaked_bytes = leaked_bytes;
+        self
+    }
+
+    pub fn leaked_bytes(&self) -> Option<u64> {
+        self.leaked_bytes
+    }
+
+    pub fn with_virtual_calls(mut self, virtual_calls: Vec<Span>) -> Self {
+        self.virtual_calls = virtual_calls;
+        self
+    }
+
+    pub fn virtual_calls(&self) -> &Vec<Span> {
+        &self.virtual_calls
+    }
+
+    pub fn with_approximations(mut self, approximations: Vec<(Approximation, Span)>) -> Self {
+        self.approximations = approximations;
+        self
+    }
+
+    pub fn approximations(&self) -> &Vec<(Approximation, Span)> {
+        &self.approximations
+    }
+
+    pub fn with_witness(mut self, witness: Vec<WitnessStep>) -> Self {
+        self.witness = witness;
+        self
+    }
+
+    pub fn witness(&self) -> &Vec<WitnessStep> {
+        &self.witness
+    }
+
+    // the finding is of high confidence if the paths reaching it are fully precise
+    pub fn confidence(&self) -> Confidence {
+        self.downgrades().iter().map(|(confidence, ..)| *confidence).max().unwrap_or(Confidence::High)
+    }
+
+    // the reasons lowering the confidence, with the span of the approximated construct if any
+    pub fn downgrades(&self) -> Vec<(Confidence, &'static str, Option<Span>)> {
+        let mut downgrades = Vec::new();
+        if self.kind == ReportKind::Unknown {
+            downgrades.push((Confidence::Low, approximation_reason(Approximation::Z3Unknown), None));
+        }
+        if self.truncated {
+            downgrades.push((Confidence::Medium, "the ownership layout is truncated at the max layout depth", None));
+        }
+        for (kind, span) in self.approximations.iter() {
+            downgrades.push((Confidence::of_approximation(*kind), approximation_reason(*kind), Some(*span)));
+        }
+        downgrades
+    }
+
+    pub fn with_generic(mut self, generic: bool) -> Self {
+        self.generic = generic;
+        self
+    }
+
+    pub fn is_generic(&self) -> bool {
+        self.generic
+    }
+
+    pub fn with_instance(mut self, instance: Option<(String, Span)>) -> Self {
+        self.instance = instance;
+        self
+    }
+
+    pub fn instance(&self) -> Option<&(String, Span)> {
+        self.instance.as_ref()
+    }
+
+    pub fn with_count(mut self, count: usize) -> Self {
+        self.count = count;
+        self
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    // the span where the leaked heap item is allocated, i.e., the origin of the taint if it is known
+    pub fn alloc_span(&self) -> Span {
+        self.origin.unwrap_or(self.span)
+    }
+
+    // the kind of the leaked ownership: the container holding it, or whether the layout of the local is owned
+    pub fn ownership_kind(&self) -> String {
+        if let Some(container) = self.container {
+            return container.kind().to_string();
+        }
+        if self.local.is_none() {
+            return "unknown".to_string();
+        }
+        match self.layout.iter().any(|owner| *owner == RawTypeOwner::Owned) {
+            true => "owned".to_string(),
+            false => "unowned".to_string(),
+        }
+    }
+
+    pub fn with_message(mut self, message: String) -> Self {
+        self.message = message;
+        self
+    }
+
+    // the finding relies on an approximated construct or z3 gives up on it, i.e., its confidence is not high
+    pub fn is_approximated(&self) -> bool {
+        self.confidence() != Confidence::High
+    }
+
+    pub fn container(&self) -> Option<ContainerOwner> {
+        self.container
+    }
+
+    pub fn message(&self) -> &String {
+        &self.message
+    }
+}
+
+// Collapse the findings of the same function, allocation site and ownership kind into the first one of them,
+// the repeats are counted in it. The order of the first findings is kept.
+pub fn dedup_reports(reports: Vec<LeakReport>) -> Vec<LeakReport> {
+    let mut deduped: Vec<LeakReport> = Vec::with_capacity(reports.len());
+    let mut index: HashMap<(ReportKind, DefId, Span, String), usize> = HashMap::default();
+    for report in reports {
+        let key = (report.kind(), report.did(), report.alloc_span(), report.ownership_kind());
+        match index.get(&key) {
+            Some(idx) => deduped[*idx].count += report.count(),
+            None => {
+                index.insert(key, deduped.len());
+                deduped.push(report);
+            },
+        }
+    }
+    deduped
+}
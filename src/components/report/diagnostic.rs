@@ -0,0 +1,77 @@
+use rustc_middle::ty::TyCtxt;
+
+use crate::components::report::{Confidence, LeakReport, ReportKind};
+
+// Emit the findings as compiler warnings through the diagnostic handler of rustc, thus they look like the
+// native lints in cargo runs and follow `--error-format` (e.g., `--error-format=json` for rustc tooling).
+pub fn emit_diagnostics(tcx: TyCtxt<'_>, reports: &[LeakReport]) {
+    for report in reports {
+        let mut diag = tcx.sess.struct_span_warn(report.span(), report.message().as_str());
+        diag.span_label(report.span(), primary_label(report));
+        if let Some(origin) = report.origin() {
+            diag.span_note(origin, "the ownership of the heap item is taken over by a raw pointer here");
+        }
+        if let Some(dead) = report.dead() {
+            diag.span_note(dead, "the storage of the local is dead here while it still owns the heap item");
+        }
+        if let Some(layout) = report.layout_display() {
+            diag.note(&format!("the leaked local is owning by its layout `{}`", layout));
+        }
+        if let Some(container) = report.container().filter(|c| c.element().is_owned()) {
+            diag.note(&format!("the owned elements of the {} are leaked together with it", container.kind()));
+        }
+        if let Some(bytes) = report.leaked_bytes() {
+            diag.note(&format!("the leaked heap item is estimated to be {} bytes", bytes));
+        }
+        if report.is_truncated() {
+            diag.note("the ownership layout is truncated at the max layout depth, the result is approximated");
+        }
+        match report.instance() {
+            Some((instance, call)) => { diag.span_note(*call, &format!("the generic function is instantiated as `{}` here", instance)); },
+            None if report.is_generic() => { diag.note("the leaked heap item depends on the type params, the function is not instantiated in this crate"); },
+            None => (),
+        }
+        if let Some(call) = report.virtual_calls().first() {
+            diag.span_note(*call, "the callee of this call through a trait object is unknown, the result is approximated");
+        }
+        if report.confidence() != Confidence::High {
+            let reasons: Vec<&str> = report.downgrades().iter().map(|(_, reason, _)| *reason).collect();
+            diag.note(&format!("the confidence of the finding is {}: {}", report.confidence(), reasons.join(", ")));
+        }
+        if report.count() > 1 {
+            diag.note(&format!("the same finding is collapsed {} times for this allocation", report.count()));
+        }
+        if report.is_unwind() {
+            diag.note("the leak is found on the unwinding path");
+        }
+        diag.help(help(report.kind()));
+        diag.emit();
+    }
+}
+
+fn primary_label(report: &LeakReport) -> &'static str {
+    match (report.kind(), report.local().is_some()) {
+        (ReportKind::Leak, true) => "the heap item is allocated here and never freed",
+        (ReportKind::Leak, false) => "the heap item is leaked in this function",
+        (ReportKind::Unknown, _) => "the heap item may be leaked here",
+        (ReportKind::DoubleFree, _) => "the heap item is dropped again here",
+        (ReportKind::UseAfterFree, _) => "the freed heap item is used here",
+        (ReportKind::DivergentOwnership, _) => "the paths joined here disagree on the ownership",
+        (ReportKind::StoredInStatic, _) => "the heap item is stored into a static here",
+    }
+}
+
+fn help(kind: ReportKind) -> &'static str {
+    match kind {
+        ReportKind::Leak | ReportKind::Unknown =>
+            "recover the ownership and drop it on every path before returning, e.g., `drop(Box::from_raw(ptr))`",
+        ReportKind::DoubleFree =>
+            "drop the heap item only once, e.g., `mem::forget` the copy that does not own it",
+        ReportKind::UseAfterFree =>
+            "use the pointer before the heap item is dropped, or keep the owner alive",
+        ReportKind::DivergentOwnership =>
+            "recover (or give away) the ownership on every branch so that the paths agree before they join",
+        ReportKind::StoredInStatic =>
+            "take the heap item out of the static and drop it if it is not meant to live until the program exits",
+    }
+}
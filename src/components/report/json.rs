@@ -0,0 +1,131 @@
+use rustc_middle::ty::TyCtxt;
+use rustc_span::Span;
+
+use crate::rlc_info;
+use crate::analysis::type_analysis::type_visitor::mir_body;
+use crate::analysis::flow_analysis::intro_visitor::local_debug_name;
+use crate::components::fs::{rlc_create_file, rlc_write};
+use crate::components::report::{LeakReport, witness_text};
+
+// the plain JSON array of findings for ad-hoc scripts, it is generated from the same reports as SARIF
+pub fn emit_json(tcx: TyCtxt<'_>, reports: &[LeakReport], path: &str) {
+    let findings: Vec<JsonFinding> = reports
+        .iter()
+        .map(|report| JsonFinding::new(tcx, report))
+        .collect();
+    let json = serde_json::to_string_pretty(&findings).expect("failed to serialize JSON report");
+
+    let file = rlc_create_file(path, "Failed to create JSON file");
+    rlc_write(file, json.as_bytes(), "Failed to write JSON file");
+
+    rlc_info!("JSON report with {} findings is written to {}", reports.len(), path);
+}
+
+#[derive(Debug, Serialize)]
+struct JsonFinding {
+    kind: String,
+    def_path: String,
+    crate_name: String,
+    span: JsonSpan,
+    // the debug name of the leaking local (e.g., `x` or `_3`), null if it is not blamed on a single local
+    leaked_local: Option<String>,
+    ownership_kind: String,
+    // the readable ownership layout of the leaking local, null if it is not blamed on a single local
+    layout: Option<String>,
+    on_unwind: bool,
+    // the estimated size of the leaked heap item, null if it is unsized or unknown
+    leaked_bytes: Option<u64>,
+    // the confidence is not high, see the downgrades
+    approximated: bool,
+    // high, medium or low by the approximations on the paths reaching the finding
+    confidence: String,
+    // the reasons lowering the confidence, with the span of the approximated construct if any
+    downgrades: Vec<JsonDowngrade>,
+    // the condensed path through which the ownership survives to the exit, e.g., `bb0 [move _2 = 1] -> bb2`
+    witness: Option<String>,
+    // the steps of the path, with the branch taken out of each block having several successors
+    witness_steps: Vec<JsonWitnessStep>,
+    // the number of identical findings collapsed into this one
+    count: usize,
+    message: String,
+}
+
+impl JsonFinding {
+    fn new(tcx: TyCtxt<'_>, report: &LeakReport) -> Self {
+        let did = report.did();
+        let leaked_local = report.local().map(|local| local_debug_name(mir_body(tcx, did), local));
+
+        Self {
+            kind: report.kind().to_string(),
+            def_path: tcx.def_path_str(did),
+            crate_name: tcx.crate_name(did.krate).to_string(),
+            span: JsonSpan::new(tcx, report.span()),
+            leaked_local,
+            ownership_kind: report.ownership_kind(),
+            layout: report.layout_display().cloned(),
+            on_unwind: report.is_unwind(),
+            leaked_bytes: report.leaked_bytes(),
+            approximated: report.is_approximated(),
+            confidence: report.confidence().to_string(),
+            downgrades: report
+                .downgrades()
+                .into_iter()
+                .map(|(_, reason, span)| JsonDowngrade {
+                    reason: reason.to_string(),
+                    span: span.map(|span| JsonSpan::new(tcx, span)),
+                })
+                .collect(),
+            witness: Some(witness_text(report.witness())).filter(|text| !text.is_empty()),
+            witness_steps: report
+                .witness()
+                .iter()
+                .map(|step| JsonWitnessStep {
+                    block: format!("{:?}", step.bb()),
+                    branch: step.branch().cloned(),
+                    span: step.span().map(|span| JsonSpan::new(tcx, span)),
+                })
+                .collect(),
+            count: report.count(),
+            message: report.message().clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct JsonWitnessStep {
+    block: String,
+    branch: Option<String>,
+    span: Option<JsonSpan>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonDowngrade {
+    reason: String,
+    span: Option<JsonSpan>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonSpan {
+    file: String,
+    line: usize,
+    col: usize,
+    end_line: usize,
+    end_col: usize,
+}
+
+impl JsonSpan {
+    fn new(tcx: TyCtxt<'_>, span: Span) -> Self {
+        let source_map = tcx.sess.source_map();
+        let lo = source_map.lookup_char_pos(span.lo());
+        let hi = source_map.lookup_char_pos(span.hi());
+
+        // the line in rustc starts from 1 while the column starts from 0
+        Self {
+            file: lo.file.name.prefer_local().to_string(),
+            line: lo.line,
+            col: lo.col.0 + 1,
+            end_line: hi.line,
+            end_col: hi.col.0 + 1,
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use rustc_middle::ty::TyCtxt;
+use rustc_span::Span;
+
+use crate::rlc_info;
+use crate::components::fs::{rlc_create_file, rlc_write};
+use crate::components::report::{LeakReport, ReportKind, witness_text};
+
+static SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+static SARIF_VERSION: &str = "2.1.0";
+static RLC_RULE_ID: &str = "rlc-memory-leak";
+static RLC_DOUBLE_FREE_RULE_ID: &str = "rlc-double-free";
+static RLC_USE_AFTER_FREE_RULE_ID: &str = "rlc-use-after-free";
+static RLC_DIVERGENT_OWNERSHIP_RULE_ID: &str = "rlc-divergent-ownership";
+static RLC_STORED_IN_STATIC_RULE_ID: &str = "rlc-stored-in-static";
+static RLC_INFO_URI: &str = "https://github.com/Artisan-Lab/rCanary";
+
+pub fn emit_sarif(tcx: TyCtxt<'_>, reports: &[LeakReport], path: &str) {
+    let log = SarifLog::new(tcx, reports);
+    let json = serde_json::to_string_pretty(&log).expect("failed to serialize SARIF log");
+
+    let file = rlc_create_file(path, "Failed to create SARIF file");
+    rlc_write(file, json.as_bytes(), "Failed to write SARIF file");
+
+    rlc_info!("SARIF report with {} results is written to {}", reports.len(), path);
+}
+
+#[derive(Debug, Serialize)]
+struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<SarifRun>,
+}
+
+impl SarifLog {
+    fn new(tcx: TyCtxt<'_>, reports: &[LeakReport]) -> Self {
+        let results = reports
+            .iter()
+            .map(|report| SarifResult::new(tcx, report))
+            .collect();
+
+        Self {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: vec![ SarifRun { tool: SarifTool::default(), results } ],
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SarifRun {
+    tool: SarifTool,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifTool {
+    driver: SarifDriver,
+}
+
+impl Default for SarifTool {
+    fn default() -> Self {
+        Self {
+            driver: SarifDriver {
+                name: "rlc",
+                version: env!("CARGO_PKG_VERSION"),
+                information_uri: RLC_INFO_URI,
+                rules: vec![
+                    SarifRule {
+                        id: RLC_RULE_ID,
+                        short_description: SarifMessage { text: "Memory leak of the heap item whose ownership is never recovered".to_string() },
+                    },
+                    SarifRule {
+                        id: RLC_DOUBLE_FREE_RULE_ID,
+                        short_description: SarifMessage { text: "Double free of the heap item that is already dropped on the path".to_string() },
+                    },
+                    SarifRule {
+                        id: RLC_USE_AFTER_FREE_RULE_ID,
+                        short_description: SarifMessage { text: "Use of the raw pointer whose heap item is already freed on the path".to_string() },
+                    },
+                    SarifRule {
+                        id: RLC_DIVERGENT_OWNERSHIP_RULE_ID,
+                        short_description: SarifMessage { text: "Join of the paths that disagree on whether the raw pointer owns the heap item".to_string() },
+                    },
+                    SarifRule {
+                        id: RLC_STORED_IN_STATIC_RULE_ID,
+                        short_description: SarifMessage { text: "Heap item stored into a static that lives until the program exits".to_string() },
+                    },
+                ],
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifDriver {
+    name: &'static str,
+    version: &'static str,
+    information_uri: &'static str,
+    rules: Vec<SarifRule>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRule {
+    id: &'static str,
+    short_description: SarifMessage,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifMessage {
+    text: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: &'static str,
+    level: &'static str,
+    message: SarifMessage,
+    locations: Vec<SarifLocation>,
+    related_locations: Vec<SarifLocation>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    code_flows: Vec<SarifCodeFlow>,
+    properties: SarifProperties,
+}
+
+// the witness path of the leak as a single thread flow, each location is a block through which the ownership survives
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifCodeFlow {
+    message: SarifMessage,
+    thread_flows: Vec<SarifThreadFlow>,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifThreadFlow {
+    locations: Vec<SarifThreadFlowLocation>,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifThreadFlowLocation {
+    location: SarifLocation,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifProperties {
+    confidence: String,
+}
+
+impl SarifResult {
+    fn new(tcx: TyCtxt<'_>, report: &LeakReport) -> Self {
+        let fn_name = tcx.def_path_str(report.did());
+
+        let mut location = SarifLocation::new(tcx, report.span(), None);
+        location.logical_locations.push(
+            SarifLogicalLocation {
+                fully_qualified_name: fn_name.clone(),
+                kind: "function",
+            }
+        );
+
+        // the whole function is the related location for the result
+        let fn_location = SarifLocation::new(
+            tcx,
+            tcx.def_span(report.did()),
+            Some(SarifMessage { text: format!("in function `{}`", fn_name) }),
+        );
+
+        let mut related_locations = vec![ fn_location ];
+        // the point where the local is converted into a raw pointer
+        if let Some(origin) = report.origin() {
+            related_locations.push(
+                SarifLocation::new(
+                    tcx,
+                    origin,
+                    Some(SarifMessage { text: "the ownership is taken over by a raw pointer here".to_string() }),
+                )
+            );
+        }
+
+        // the approximated constructs lowering the confidence of the result
+        for (_, reason, span) in report.downgrades() {
+            if let Some(span) = span {
+                related_locations.push(
+                    SarifLocation::new(
+                        tcx,
+                        span,
+                        Some(SarifMessage { text: format!("the confidence is lowered here: {}", reason) }),
+                    )
+                );
+            }
+        }
+
+        // the blocks without a source range (e.g., the graph without block meta) are left out of the flow
+        let steps: Vec<SarifThreadFlowLocation> = report
+            .witness()
+            .iter()
+            .filter_map(|step| {
+                let span = step.span()?;
+                Some(SarifThreadFlowLocation { location: SarifLocation::new(tcx, span, Some(SarifMessage { text: step.to_string() })) })
+            })
+            .collect();
+        let code_flows = match steps.is_empty() {
+            true => Vec::new(),
+            false => vec![
+                SarifCodeFlow {
+                    message: SarifMessage { text: format!("the ownership survives to the exit along {}", witness_text(report.witness())) },
+                    thread_flows: vec![ SarifThreadFlow { locations: steps } ],
+                }
+            ],
+        };
+
+        Self {
+            rule_id: match report.kind() {
+                ReportKind::Leak | ReportKind::Unknown => RLC_RULE_ID,
+                ReportKind::DoubleFree => RLC_DOUBLE_FREE_RULE_ID,
+                ReportKind::UseAfterFree => RLC_USE_AFTER_FREE_RULE_ID,
+                ReportKind::DivergentOwnership => RLC_DIVERGENT_OWNERSHIP_RULE_ID,
+                ReportKind::StoredInStatic => RLC_STORED_IN_STATIC_RULE_ID,
+            },
+            level: "warning",
+            message: SarifMessage { text: report.message().clone() },
+            locations: vec![ location ],
+            related_locations,
+            code_flows,
+            properties: SarifProperties { confidence: report.confidence().to_string() },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifLocation {
+    physical_location: SarifPhysicalLocation,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    logical_locations: Vec<SarifLogicalLocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<SarifMessage>,
+}
+
+impl SarifLocation {
+    fn new(tcx: TyCtxt<'_>, span: Span, message: Option<SarifMessage>) -> Self {
+        let source_map = tcx.sess.source_map();
+        let lo = source_map.lookup_char_pos(span.lo());
+        let hi = source_map.lookup_char_pos(span.hi());
+
+        Self {
+            physical_location: SarifPhysicalLocation {
+                artifact_location: SarifArtifactLocation {
+                    uri: lo.file.name.prefer_local().to_string(),
+                },
+                // the line in rustc starts from 1 while the column starts from 0
+                region: SarifRegion {
+                    start_line: lo.line,
+                    start_column: lo.col.0 + 1,
+                    end_line: hi.line,
+                    end_column: hi.col.0 + 1,
+                },
+            },
+            logical_locations: Vec::new(),
+            message,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifPhysicalLocation {
+    artifact_location: SarifArtifactLocation,
+    region: SarifRegion,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifArtifactLocation {
+    uri: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRegion {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifLogicalLocation {
+    fully_qualified_name: String,
+    kind: &'static str,
+}
@@ -0,0 +1,78 @@
+use crate::{rlc_error, rlc_info};
+use crate::components::report::{LeakReport, ReportKind};
+
+// The exit code of the driver if any finding is denied, the other codes follow rustc (1 for the compile
+// errors and 101 for the ICE).
+pub const RLC_EXIT_DENIED: i32 = 2;
+
+// The severity of the findings for CI gating. The findings are only reported (and the exit code is 0) by default.
+// The env var RLC_DENY fails the run on the findings, and its value selects which of them count:
+// `unwind` only counts the leaks on the panic paths and `exact` ignores the approximated findings, e.g.,
+// `RLC_DENY=unwind,exact`. The env var RLC_WARN keeps reporting only, even if RLC_DENY is given.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Severity {
+    deny: bool,
+    unwind_only: bool,
+    exact_only: bool,
+}
+
+impl Severity {
+    // the severity denying the findings in the scopes given by the value of RLC_DENY
+    pub fn from_scopes(value: &str) -> Self {
+        let mut severity = Self::default().with_deny(true);
+        for scope in value.split(',').map(str::trim).filter(|scope| !scope.is_empty()) {
+            match scope {
+                "all" | "1" => (),
+                "unwind" => severity.unwind_only = true,
+                "exact" => severity.exact_only = true,
+                _ => rlc_error!("Unknown scope of RLC_DENY: {}, all findings are denied", scope),
+            }
+        }
+        severity
+    }
+
+    pub fn with_deny(mut self, deny: bool) -> Self {
+        self.deny = deny;
+        self
+    }
+
+    pub fn with_unwind_only(mut self, unwind_only: bool) -> Self {
+        self.unwind_only = unwind_only;
+        self
+    }
+
+    pub fn with_exact_only(mut self, exact_only: bool) -> Self {
+        self.exact_only = exact_only;
+        self
+    }
+
+    pub fn is_deny(&self) -> bool {
+        self.deny
+    }
+
+    // whether the finding fails the run
+    pub fn is_denied(&self, report: &LeakReport) -> bool {
+        if !self.deny {
+            return false;
+        }
+        if self.unwind_only && !(report.kind() == ReportKind::Leak && report.is_unwind()) {
+            return false;
+        }
+        !(self.exact_only && report.is_approximated())
+    }
+
+    // count the findings by confidence and return the number of the denied ones
+    pub fn check(&self, reports: &[LeakReport]) -> usize {
+        let approximated = reports.iter().filter(|report| report.is_approximated()).count();
+        let denied = reports.iter().filter(|report| self.is_denied(report)).count();
+        if !reports.is_empty() {
+            rlc_info!(
+                "Findings: {} exact, {} approximated, {} denied",
+                reports.len() - approximated,
+                approximated,
+                denied,
+            );
+        }
+        denied
+    }
+}
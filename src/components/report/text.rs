@@ -0,0 +1,150 @@
+use rustc_middle::ty::TyCtxt;
+
+use crate::components::report::{Confidence, LeakReport, ReportKind};
+
+use std::fmt::Write;
+
+static REPORT_KINDS: [ReportKind; 6] = [
+    ReportKind::Leak,
+    ReportKind::Unknown,
+    ReportKind::DoubleFree,
+    ReportKind::UseAfterFree,
+    ReportKind::DivergentOwnership,
+    ReportKind::StoredInStatic,
+];
+
+// The readable report printed to stdout at the end of the run. The findings are grouped by the source file
+// and then by the function, and each of them takes one line with its position. The quiet mode prints the summary only.
+pub struct TextReporter<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    quiet: bool,
+}
+
+impl<'tcx> TextReporter<'tcx> {
+    pub fn new(tcx: TyCtxt<'tcx>) -> Self {
+        Self { tcx, quiet: false }
+    }
+
+    pub fn with_quiet(mut self, quiet: bool) -> Self {
+        self.quiet = quiet;
+        self
+    }
+
+    pub fn is_quiet(&self) -> bool {
+        self.quiet
+    }
+
+    pub fn emit(&self, reports: &[LeakReport]) {
+        print!("{}", self.render(reports));
+    }
+
+    pub fn render(&self, reports: &[LeakReport]) -> String {
+        let mut lines: Vec<TextLine> = reports.iter().map(|report| TextLine::new(self.tcx, report)).collect();
+        // the functions in a file are ordered by their definitions, the findings in a function by their lines
+        lines.sort_by(|a, b| {
+            (&a.file, a.fn_line, &a.fn_path, a.line, a.col).cmp(&(&b.file, b.fn_line, &b.fn_path, b.line, b.col))
+        });
+
+        let mut out = String::new();
+        let mut files = 0;
+        let mut fns = 0;
+        let mut last: Option<(&String, &String)> = None;
+        for line in lines.iter() {
+            let new_file = last.map_or(true, |(file, _)| *file != line.file);
+            let new_fn = new_file || last.map_or(true, |(_, fn_path)| *fn_path != line.fn_path);
+            if new_file {
+                files += 1;
+            }
+            if new_fn {
+                fns += 1;
+            }
+            if !self.quiet {
+                if new_file {
+                    if last.is_some() {
+                        out.push('\n');
+                    }
+                    let _ = writeln!(out, "{}", line.file);
+                }
+                if new_fn {
+                    let _ = writeln!(out, "  {}", line.fn_path);
+                }
+                let _ = writeln!(out, "    {}:{} {}: {}{}", line.line, line.col, line.kind, line.message, line.notes);
+            }
+            last = Some((&line.file, &line.fn_path));
+        }
+
+        if !self.quiet && !lines.is_empty() {
+            out.push('\n');
+        }
+        let _ = writeln!(out, "{}", summary(reports, fns, files));
+        out
+    }
+}
+
+// the summary line with the number of findings of each kind, e.g., `RLC: 3 findings (2 leak, 1 double-free) in 2 functions of 1 file`
+fn summary(reports: &[LeakReport], fns: usize, files: usize) -> String {
+    if reports.is_empty() {
+        return "RLC: no finding".to_string();
+    }
+    let kinds: Vec<String> = REPORT_KINDS
+        .iter()
+        .map(|kind| (kind, reports.iter().filter(|report| report.kind() == *kind).count()))
+        .filter(|(_, count)| *count > 0)
+        .map(|(kind, count)| format!("{} {}", count, kind))
+        .collect();
+    format!(
+        "RLC: {} ({}) in {} of {}",
+        plural(reports.len(), "finding"),
+        kinds.join(", "),
+        plural(fns, "function"),
+        plural(files, "file"),
+    )
+}
+
+fn plural(n: usize, noun: &str) -> String {
+    if n == 1 { format!("{} {}", n, noun) } else { format!("{} {}s", n, noun) }
+}
+
+struct TextLine {
+    file: String,
+    fn_path: String,
+    // the line of the function definition, it orders the functions as in the source file
+    fn_line: usize,
+    line: usize,
+    col: usize,
+    kind: ReportKind,
+    message: String,
+    // the short marks following the message, e.g., the leak on unwinding and the lowered confidence
+    notes: String,
+}
+
+impl TextLine {
+    fn new(tcx: TyCtxt<'_>, report: &LeakReport) -> Self {
+        let source_map = tcx.sess.source_map();
+        let lo = source_map.lookup_char_pos(report.span().lo());
+        let fn_lo = source_map.lookup_char_pos(tcx.def_span(report.did()).lo());
+
+        let mut notes = String::new();
+        if report.is_unwind() {
+            notes.push_str(" [unwind]");
+        }
+        if report.confidence() != Confidence::High {
+            let _ = write!(notes, " [{} confidence]", report.confidence());
+        }
+        if report.count() > 1 {
+            let _ = write!(notes, " [x{}]", report.count());
+        }
+
+        // the line in rustc starts from 1 while the column starts from 0
+        Self {
+            file: lo.file.name.prefer_local().to_string(),
+            fn_path: tcx.def_path_str(report.did()),
+            fn_line: fn_lo.line,
+            line: lo.line,
+            col: lo.col.0 + 1,
+            kind: report.kind(),
+            message: report.message().clone(),
+            notes,
+        }
+    }
+}
@@ -13,27 +13,54 @@ extern crate rustc_hir;
 extern crate rustc_span;
 extern crate rustc_index;
 extern crate rustc_target;
+extern crate rustc_data_structures;
+extern crate rustc_ast;
+// the tracing shipped with rustc, the spans of the phases are zero-cost unless a subscriber is installed
+extern crate tracing;
 
 extern crate serde;
 extern crate serde_json;
+extern crate regex;
+extern crate toml;
 
 #[macro_use]
 extern crate serde_derive;
 extern crate core;
 
 use rustc_middle::ty::TyCtxt;
+use rustc_span::def_id::DefId;
+
+use std::env;
 
 use crate::components::grain::RlcGrain;
 use crate::components::log::Verbosity;
 use crate::components::context::RlcGlobalCtxt;
 use crate::components::display::MirDisplay;
-use crate::analysis::flow_analysis::{FlowAnalysis, IcxSliceDisplay, Z3GoalDisplay};
+use crate::components::filter::RlcFilter;
+use crate::components::report::sarif::emit_sarif;
+use crate::components::report::diagnostic::emit_diagnostics;
+use crate::components::report::text::TextReporter;
+use crate::components::report::Confidence;
+use crate::components::report::severity::Severity;
+use crate::components::cache::{load_caches, save_cache};
+use crate::analysis::flow_analysis::summary::load_known_summaries;
+use crate::analysis::flow_analysis::{FfiMode, FlowAnalysis, IcxSliceDisplay, VirtualCallMode, Z3GoalDisplay};
 use crate::analysis::type_analysis::{TypeAnalysis, AdtOwnerDisplay};
+use crate::analysis::type_analysis::type_visitor::MirPhase;
+
+pub use crate::components::report::LeakReport;
 
 // Insert rustc arguments at the beginning of the argument list that RLC wants to be
 // set per default, for maximal validation power.
 pub static RLC_DEFAULT_ARGS: &[&str] =
-    &["-Zalways-encode-mir", "-Zmir-opt-level=0", "--cfg=rlc"];
+    &[
+        "-Zalways-encode-mir",
+        "-Zmir-opt-level=0",
+        "--cfg=rlc",
+        // register the tool `rlc` for the attributes like `#[cfg_attr(rlc, rlc::ignore)]`
+        "-Zcrate-attr=feature(register_tool)",
+        "-Zcrate-attr=register_tool(rlc)",
+    ];
 pub static RLC_ROOT:&str = "/tmp/rlc";
 pub static RLC_LLVM_CACHE:&str = "/tmp/rlc/llvm-cache";
 pub static RLC_LLVM_IR:&str = "/tmp/rlc/llvm-ir";
@@ -41,7 +68,14 @@ pub static RLC_LLVM_RES:&str = "/tmp/rlc/llvm-res";
 
 pub type Elapsed = (i64, i64);
 
-#[derive(Debug, Copy, Clone, Hash)]
+// the maximum depth of nested adts visited when collecting the types of the bodies
+// it guards the expansion of polymorphic recursive types like S<T> { next: Option<Box<S<Vec<T>>>> }
+pub static RLC_MAX_TYPE_DEPTH: usize = 8;
+
+// the default timeout (ms) of each check of z3 solver
+pub static RLC_Z3_TIMEOUT: u64 = 10000;
+
+#[derive(Debug, Clone)]
 pub struct RlcConfig {
     grain: RlcGrain,
     verbose: Verbosity,
@@ -49,6 +83,48 @@ pub struct RlcConfig {
     adt_display: AdtOwnerDisplay,
     z3_goal_display: Z3GoalDisplay,
     icx_slice_display: IcxSliceDisplay,
+    z3_timeout: u64,
+    unwind: bool,
+    rc_cycle: bool,
+    double_free: bool,
+    use_after_free: bool,
+    divergent_ownership: bool,
+    // report the owned values stored into statics, they are treated as escapes rather than leaks anyway
+    static_store: bool,
+    // report the leaks of the ownership handed in by the parameters, the caller is responsible for them by default
+    param_owned: bool,
+    virtual_call: VirtualCallMode,
+    ffi: FfiMode,
+    // the maximum depth of nested generic owners to expand, the deeper part of the layout is truncated
+    // it is unbounded by default, the recursive adts are still guarded by the recursion marker
+    max_layout_depth: usize,
+    // the max number of basic blocks of a leaf callee to be analyzed again at each call site, 0 disables it
+    inline_threshold: usize,
+    // the phase of the MIR of the local crate to analyze
+    mir_phase: MirPhase,
+    // the dir of the dot files of the cfgs
+    dot_path: Option<String>,
+    // the dir of the in and out contexts of the blocks
+    icx_dump_path: Option<String>,
+    // the dir of the SMT-LIB2 queries of the exits
+    smt2_path: Option<String>,
+    // the file or the dir of the verbose z3 goals instead of stdout
+    z3_goal_path: Option<String>,
+    // print the statistics of the run
+    stats: bool,
+    // only build the graphs and the layouts without solving
+    dry_run: bool,
+    // the files of the findings as a plain JSON array and as SARIF
+    json_path: Option<String>,
+    sarif_path: Option<String>,
+    // the dir of the analysis cache of the upstream crates
+    cache_dir: Option<String>,
+    // the file of the known summaries of the functions without mir
+    summaries_path: Option<String>,
+    filter: RlcFilter,
+    // the findings below this confidence are dropped
+    min_confidence: Option<Confidence>,
+    severity: Severity,
 }
 
 impl Default for RlcConfig {
@@ -60,11 +136,38 @@ impl Default for RlcConfig {
             adt_display: AdtOwnerDisplay::Disabled,
             z3_goal_display: Z3GoalDisplay::Disabled,
             icx_slice_display: IcxSliceDisplay::Disabled,
+            z3_timeout: RLC_Z3_TIMEOUT,
+            unwind: false,
+            rc_cycle: false,
+            double_free: false,
+            use_after_free: false,
+            divergent_ownership: false,
+            static_store: false,
+            param_owned: false,
+            virtual_call: VirtualCallMode::Pessimistic,
+            ffi: FfiMode::Friendly,
+            max_layout_depth: usize::MAX,
+            inline_threshold: 0,
+            mir_phase: MirPhase::Analysis,
+            dot_path: None,
+            icx_dump_path: None,
+            smt2_path: None,
+            z3_goal_path: None,
+            stats: false,
+            dry_run: false,
+            json_path: None,
+            sarif_path: None,
+            cache_dir: None,
+            summaries_path: None,
+            filter: RlcFilter::default(),
+            min_confidence: None,
+            severity: Severity::default(),
         }
     }
 }
 
 impl RlcConfig {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         grain: RlcGrain,
         verbose: Verbosity,
@@ -72,6 +175,19 @@ impl RlcConfig {
         adt_display: AdtOwnerDisplay,
         z3_goal_display: Z3GoalDisplay,
         icx_slice_display: IcxSliceDisplay,
+        z3_timeout: u64,
+        unwind: bool,
+        rc_cycle: bool,
+        double_free: bool,
+        use_after_free: bool,
+        divergent_ownership: bool,
+        static_store: bool,
+        param_owned: bool,
+        virtual_call: VirtualCallMode,
+        ffi: FfiMode,
+        max_layout_depth: usize,
+        inline_threshold: usize,
+        mir_phase: MirPhase,
     ) -> Self {
         Self {
             grain,
@@ -80,9 +196,85 @@ impl RlcConfig {
             adt_display,
             z3_goal_display,
             icx_slice_display,
+            z3_timeout,
+            unwind,
+            rc_cycle,
+            double_free,
+            use_after_free,
+            divergent_ownership,
+            static_store,
+            param_owned,
+            virtual_call,
+            ffi,
+            max_layout_depth,
+            inline_threshold,
+            mir_phase,
+            ..Self::default()
         }
     }
 
+    // the env vars are the default source of the config, they are loaded once at startup
+    // and the flags given to rlc will override them later
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        // the value of Z3_GOAL other than a flag (e.g., empty or `1`) is the path of the goals as well,
+        // Z3_GOAL_PATH is an alias of the path
+        if let Ok(value) = env::var("Z3_GOAL") {
+            config.set_z3_goal_display(Z3GoalDisplay::Verbose);
+            if !matches!(value.as_str(), "" | "1" | "true" | "V") {
+                config.set_z3_goal_path(Some(value));
+            }
+        }
+        if let Ok(path) = env::var("Z3_GOAL_PATH") {
+            config.set_z3_goal_display(Z3GoalDisplay::Verbose);
+            config.set_z3_goal_path(Some(path));
+        }
+        if env::var_os("ICX_SLICE").is_some() {
+            config.set_icx_slice_display(IcxSliceDisplay::Verbose);
+        }
+        if let Some(timeout) = env::var("Z3_TIMEOUT").ok().and_then(|t| t.parse().ok()) {
+            config.set_z3_timeout(timeout);
+        }
+        config.set_dot_path(env::var("RLC_DOT").ok());
+        config.set_icx_dump_path(env::var("RLC_ICX_DUMP").ok());
+        config.set_smt2_path(env::var("RLC_SMT2").ok());
+        config.set_dry_run(env::var_os("RLC_DRY_RUN").is_some());
+        config.set_json_path(env::var("RLC_JSON").ok());
+        config.set_stats(env::var_os("RLC_STATS").is_some());
+        config.set_sarif_path(env::var("RLC_SARIF").ok());
+        config.set_cache_dir(env::var("RLC_CACHE_DIR").ok());
+        config.set_summaries_path(env::var("RLC_SUMMARIES").ok());
+        if let Ok(value) = env::var("RLC_MIN_CONFIDENCE") {
+            let confidence = Confidence::from_name(value.trim());
+            if confidence.is_none() {
+                rlc_error!("Unknown confidence of RLC_MIN_CONFIDENCE: {}, all findings are kept", value);
+            }
+            config.set_min_confidence(confidence);
+        }
+
+        let mut filter = RlcFilter::default();
+        if let Ok(allow) = env::var("RLC_ALLOW") {
+            filter = filter.with_allow(&allow);
+        }
+        if let Ok(deny) = env::var("RLC_DENY_FN") {
+            filter = filter.with_deny(&deny);
+        }
+        if let Ok(only) = env::var("RLC_ONLY") {
+            filter = filter.with_only(only);
+        }
+        config.set_filter(
+            filter
+                .with_only_callees(env::var_os("RLC_ONLY_CALLEES").is_some())
+                .with_skip_unsafe(env::var_os("RLC_SKIP_UNSAFE").is_some())
+        );
+
+        // RLC_WARN keeps reporting only, even if RLC_DENY is given
+        if let (Ok(scopes), None) = (env::var("RLC_DENY"), env::var_os("RLC_WARN")) {
+            config.set_severity(Severity::from_scopes(&scopes));
+        }
+        config
+    }
+
     pub fn grain(&self) -> RlcGrain { self.grain }
 
     pub fn set_grain(&mut self, grain: RlcGrain) { self.grain = grain;}
@@ -91,6 +283,8 @@ impl RlcConfig {
 
     pub fn set_verbose(&mut self, verbose: Verbosity) { self.verbose = verbose; }
 
+    pub fn is_quiet(&self) -> bool { matches!(self.verbose, Verbosity::Quiet) }
+
     pub fn mir_display(&self) -> MirDisplay { self.mir_display }
 
     pub fn set_mir_display(&mut self, mir_display: MirDisplay) { self.mir_display = mir_display; }
@@ -107,6 +301,110 @@ impl RlcConfig {
 
     pub fn set_icx_slice_display(&mut self, icx_slice_display: IcxSliceDisplay) { self.icx_slice_display = icx_slice_display; }
 
+    pub fn z3_timeout(&self) -> u64 { self.z3_timeout }
+
+    pub fn set_z3_timeout(&mut self, z3_timeout: u64) { self.z3_timeout = z3_timeout; }
+
+    pub fn unwind(&self) -> bool { self.unwind }
+
+    pub fn set_unwind(&mut self, unwind: bool) { self.unwind = unwind; }
+
+    pub fn rc_cycle(&self) -> bool { self.rc_cycle }
+
+    pub fn set_rc_cycle(&mut self, rc_cycle: bool) { self.rc_cycle = rc_cycle; }
+
+    pub fn double_free(&self) -> bool { self.double_free }
+
+    pub fn set_double_free(&mut self, double_free: bool) { self.double_free = double_free; }
+
+    pub fn use_after_free(&self) -> bool { self.use_after_free }
+
+    pub fn set_use_after_free(&mut self, use_after_free: bool) { self.use_after_free = use_after_free; }
+
+    pub fn divergent_ownership(&self) -> bool { self.divergent_ownership }
+
+    pub fn set_divergent_ownership(&mut self, divergent_ownership: bool) { self.divergent_ownership = divergent_ownership; }
+
+    pub fn static_store(&self) -> bool { self.static_store }
+
+    pub fn set_static_store(&mut self, static_store: bool) { self.static_store = static_store; }
+
+    pub fn param_owned(&self) -> bool { self.param_owned }
+
+    pub fn set_param_owned(&mut self, param_owned: bool) { self.param_owned = param_owned; }
+
+    pub fn virtual_call(&self) -> VirtualCallMode { self.virtual_call }
+
+    pub fn set_virtual_call(&mut self, virtual_call: VirtualCallMode) { self.virtual_call = virtual_call; }
+
+    pub fn ffi(&self) -> FfiMode { self.ffi }
+
+    pub fn set_ffi(&mut self, ffi: FfiMode) { self.ffi = ffi; }
+
+    pub fn max_layout_depth(&self) -> usize { self.max_layout_depth }
+
+    pub fn set_max_layout_depth(&mut self, max_layout_depth: usize) { self.max_layout_depth = max_layout_depth; }
+
+    pub fn inline_threshold(&self) -> usize { self.inline_threshold }
+
+    pub fn set_inline_threshold(&mut self, inline_threshold: usize) { self.inline_threshold = inline_threshold; }
+
+    pub fn mir_phase(&self) -> MirPhase { self.mir_phase }
+
+    pub fn set_mir_phase(&mut self, mir_phase: MirPhase) { self.mir_phase = mir_phase; }
+
+    pub fn dot_path(&self) -> Option<&String> { self.dot_path.as_ref() }
+
+    pub fn set_dot_path(&mut self, dot_path: Option<String>) { self.dot_path = dot_path; }
+
+    pub fn icx_dump_path(&self) -> Option<&String> { self.icx_dump_path.as_ref() }
+
+    pub fn set_icx_dump_path(&mut self, icx_dump_path: Option<String>) { self.icx_dump_path = icx_dump_path; }
+
+    pub fn smt2_path(&self) -> Option<&String> { self.smt2_path.as_ref() }
+
+    pub fn set_smt2_path(&mut self, smt2_path: Option<String>) { self.smt2_path = smt2_path; }
+
+    pub fn z3_goal_path(&self) -> Option<&String> { self.z3_goal_path.as_ref() }
+
+    pub fn set_z3_goal_path(&mut self, z3_goal_path: Option<String>) { self.z3_goal_path = z3_goal_path; }
+
+    pub fn stats(&self) -> bool { self.stats }
+
+    pub fn set_stats(&mut self, stats: bool) { self.stats = stats; }
+
+    pub fn dry_run(&self) -> bool { self.dry_run }
+
+    pub fn set_dry_run(&mut self, dry_run: bool) { self.dry_run = dry_run; }
+
+    pub fn json_path(&self) -> Option<&String> { self.json_path.as_ref() }
+
+    pub fn set_json_path(&mut self, json_path: Option<String>) { self.json_path = json_path; }
+
+    pub fn sarif_path(&self) -> Option<&String> { self.sarif_path.as_ref() }
+
+    pub fn set_sarif_path(&mut self, sarif_path: Option<String>) { self.sarif_path = sarif_path; }
+
+    pub fn cache_dir(&self) -> Option<&String> { self.cache_dir.as_ref() }
+
+    pub fn set_cache_dir(&mut self, cache_dir: Option<String>) { self.cache_dir = cache_dir; }
+
+    pub fn summaries_path(&self) -> Option<&String> { self.summaries_path.as_ref() }
+
+    pub fn set_summaries_path(&mut self, summaries_path: Option<String>) { self.summaries_path = summaries_path; }
+
+    pub fn filter(&self) -> &RlcFilter { &self.filter }
+
+    pub fn set_filter(&mut self, filter: RlcFilter) { self.filter = filter; }
+
+    pub fn min_confidence(&self) -> Option<Confidence> { self.min_confidence }
+
+    pub fn set_min_confidence(&mut self, min_confidence: Option<Confidence>) { self.min_confidence = min_confidence; }
+
+    pub fn severity(&self) -> Severity { self.severity }
+
+    pub fn set_severity(&mut self, severity: Severity) { self.severity = severity; }
+
 }
 
 #[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
@@ -153,10 +451,17 @@ fn run_analyzer<F, R>(name: &str, func: F) -> R
     res
 }
 
-pub fn start_analyzer(tcx: TyCtxt, config: RlcConfig) {
-    let rcx_boxed = Box::new(RlcGlobalCtxt::new(tcx, config));
+// run the whole analysis on the crate and return the number of the findings denied by the severity
+pub fn start_analyzer(tcx: TyCtxt, config: RlcConfig) -> usize {
+    let rcx_boxed = Box::new(RlcGlobalCtxt::new(tcx, config.clone()));
     let rcx = Box::leak(rcx_boxed);
 
+    // the results of the unchanged upstream crates are reused from the cache
+    if let Some(dir) = config.cache_dir() {
+        load_caches(rcx, dir);
+    }
+    load_known_summaries(rcx, config.summaries_path().map(String::as_str));
+
     run_analyzer(
         "Type Analysis",
         ||
@@ -166,6 +471,41 @@ pub fn start_analyzer(tcx: TyCtxt, config: RlcConfig) {
     run_analyzer(
         "Flow Analysis",
         ||
-            FlowAnalysis::new(rcx).start()
+            FlowAnalysis::new(rcx).with_unwind(config.unwind()).start()
     );
-}
\ No newline at end of file
+
+    // the dry run computes no summary, the cache of the last full run is kept
+    if let (Some(dir), false) = (config.cache_dir(), config.dry_run()) {
+        save_cache(rcx, dir);
+    }
+
+    // the findings grouped by file on stdout are the primary output, the quiet mode keeps only their summary
+    let quiet = config.is_quiet();
+    if !quiet {
+        emit_diagnostics(tcx, rcx.reports());
+    }
+    TextReporter::new(tcx).with_quiet(quiet).emit(rcx.reports());
+
+    if let Some(path) = config.sarif_path() {
+        emit_sarif(tcx, rcx.reports(), path);
+    }
+
+    config.severity().check(rcx.reports())
+}
+// The entry for embedding rlc in other tools without the driver.
+// It analyzes the given function in default config and returns the leaks found.
+pub fn analyze_body(tcx: TyCtxt, did: DefId) -> Vec<LeakReport> {
+    let mut rcx = RlcGlobalCtxt::new(tcx, RlcConfig::default());
+    TypeAnalysis::new(&mut rcx).start();
+    FlowAnalysis::new(&mut rcx).start_body(did);
+    std::mem::take(rcx.reports_mut())
+}
+
+// The entry for embedding rlc in other tools without the driver.
+// It analyzes all functions of the current crate in default config and returns the leaks found.
+pub fn analyze_crate(tcx: TyCtxt) -> Vec<LeakReport> {
+    let mut rcx = RlcGlobalCtxt::new(tcx, RlcConfig::default());
+    TypeAnalysis::new(&mut rcx).start();
+    FlowAnalysis::new(&mut rcx).start();
+    std::mem::take(rcx.reports_mut())
+}
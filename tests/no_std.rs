@@ -0,0 +1,42 @@
+// Run the driver on the no_std PoCs, the items of std missing there must not crash the analysis.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn run_rlc(poc: &str) -> Output {
+    let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("PoC/NoStd").join(poc);
+    let out_dir = std::env::temp_dir().join(format!("rlc-test-no-std-{}", poc.trim_end_matches(".rs")));
+    let output = Command::new(env!("CARGO_BIN_EXE_rlc"))
+        .arg(&src)
+        .args(["--crate-type", "lib", "--edition", "2021", "--emit", "metadata", "--out-dir"])
+        .arg(&out_dir)
+        .output()
+        .expect("failed to run rlc");
+    assert!(
+        output.status.success(),
+        "rlc failed on {}:\n{}{}",
+        poc,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr),
+    );
+    output
+}
+
+// whether the text report has the function, its line is the def path without the crate name
+fn reports_fn(stdout: &str, name: &str) -> bool {
+    stdout.lines().any(|line| line.trim() == name || line.trim().ends_with(&format!("::{}", name)))
+}
+
+#[test]
+fn core_only() {
+    let stdout = String::from_utf8(run_rlc("core_only.rs").stdout).unwrap();
+    assert!(stdout.contains("RLC: no finding"), "{}", stdout);
+}
+
+#[test]
+fn with_alloc() {
+    let stdout = String::from_utf8(run_rlc("with_alloc.rs").stdout).unwrap();
+    assert!(reports_fn(&stdout, "leaked"), "{}", stdout);
+    assert!(!reports_fn(&stdout, "forgotten"), "{}", stdout);
+    assert!(!reports_fn(&stdout, "deallocated"), "{}", stdout);
+}